@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use bitvec::slice::BitSlice;
+use bitvec::vec::BitVec;
+
+use super::{Array, ArrayBuilder, ArrayIterator};
+use crate::DataType;
+
+/// An [`Array`] of byte strings.
+///
+/// The bytes of all items are concatenated into one buffer, and the `i`-th item is
+/// `data[offsets[i]..offsets[i + 1]]`. [`StringArray`](super::StringArray) has the same layout,
+/// and only differs in that its items are guaranteed to be valid UTF-8.
+#[derive(Clone)]
+pub struct BinaryArray {
+    /// The flattened data of byte strings
+    data: Arc<[u8]>,
+    /// Offsets of each byte string in the data flat array
+    offsets: Arc<[usize]>,
+    /// The null bitmap of this array
+    bitmap: Arc<BitVec>,
+    /// Position of the first item of this array in `offsets` and `bitmap`
+    offset: usize,
+    /// Number of items of this array
+    len: usize,
+}
+
+impl Array for BinaryArray {
+    type RefItem<'a> = &'a [u8];
+    type Builder = BinaryArrayBuilder;
+    type OwnedItem = Vec<u8>;
+
+    fn get(&self, idx: usize) -> Option<Self::RefItem<'_>> {
+        assert!(idx < self.len, "index out of bounds");
+        let idx = self.offset + idx;
+        if self.bitmap[idx] {
+            Some(&self.data[self.offsets[idx]..self.offsets[idx + 1]])
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> ArrayIterator<'_, Self> {
+        ArrayIterator::new(self)
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        assert!(offset + len <= self.len, "slice out of bounds");
+        Self {
+            data: self.data.clone(),
+            offsets: self.offsets.clone(),
+            bitmap: self.bitmap.clone(),
+            offset: self.offset + offset,
+            len,
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Binary
+    }
+
+    fn null_bitmap(&self) -> &BitSlice {
+        &self.bitmap[self.offset..self.offset + self.len]
+    }
+}
+
+/// [`ArrayBuilder`] for [`BinaryArray`]
+pub struct BinaryArrayBuilder {
+    /// The flattened data of byte strings
+    data: Vec<u8>,
+    /// Offsets of each byte string in the data flat array
+    offsets: Vec<usize>,
+    /// The null bitmap of this array
+    bitmap: BitVec,
+}
+
+impl BinaryArrayBuilder {
+    /// Append a byte string which `f` writes into the data buffer piece by piece, so that it is
+    /// not built separately. Nothing is appended if `f` fails.
+    pub fn push_with<E>(&mut self, f: impl FnOnce(&mut Vec<u8>) -> Result<(), E>) -> Result<(), E> {
+        let start = self.data.len();
+        if let Err(e) = f(&mut self.data) {
+            self.data.truncate(start);
+            return Err(e);
+        }
+        self.offsets.push(self.data.len());
+        self.bitmap.push(true);
+        Ok(())
+    }
+}
+
+impl ArrayBuilder for BinaryArrayBuilder {
+    type Array = BinaryArray;
+
+    fn with_capacity(capacity: usize) -> Self {
+        let mut offsets = Vec::with_capacity(capacity + 1);
+        offsets.push(0);
+        Self {
+            data: Vec::with_capacity(capacity),
+            offsets,
+            bitmap: BitVec::with_capacity(capacity),
+        }
+    }
+
+    fn push(&mut self, value: Option<&[u8]>) {
+        match value {
+            Some(v) => {
+                self.data.extend_from_slice(v);
+                self.offsets.push(self.data.len());
+                self.bitmap.push(true);
+            }
+            None => {
+                self.offsets.push(self.data.len());
+                self.bitmap.push(false);
+            }
+        }
+    }
+
+    fn finish(self) -> Self::Array {
+        BinaryArray {
+            len: self.bitmap.len(),
+            offset: 0,
+            data: self.data.into(),
+            offsets: self.offsets.into(),
+            bitmap: Arc::new(self.bitmap),
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Binary
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use bitvec::slice::BitSlice;
+use bitvec::vec::BitVec;
+
+use super::{Array, ArrayBuilder, ArrayIterator};
+use crate::DataType;
+
+/// An [`Array`] of booleans, packed into one bit per item.
+///
+/// The values and the null bitmap are two bit vectors of the same length.
+#[derive(Clone)]
+pub struct BoolArray {
+    /// The values of this array, which are `false` for `NULL` items
+    data: Arc<BitVec>,
+    /// The null bitmap of this array
+    bitmap: Arc<BitVec>,
+    /// Position of the first item of this array in the buffers
+    offset: usize,
+    /// Number of items of this array
+    len: usize,
+}
+
+impl BoolArray {
+    /// Create an array from its values and null bitmap, which must be of the same length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` and `bitmap` have different lengths.
+    pub fn from_bits(data: BitVec, bitmap: BitVec) -> Self {
+        assert_eq!(data.len(), bitmap.len(), "length mismatch");
+        Self {
+            len: data.len(),
+            offset: 0,
+            data: Arc::new(data),
+            bitmap: Arc::new(bitmap),
+        }
+    }
+
+    /// The values of this array. The value of a `NULL` item is unspecified.
+    pub fn values(&self) -> &BitSlice {
+        &self.data[self.offset..self.offset + self.len]
+    }
+}
+
+impl Array for BoolArray {
+    type RefItem<'a> = bool;
+    type Builder = BoolArrayBuilder;
+    type OwnedItem = bool;
+
+    fn get(&self, idx: usize) -> Option<bool> {
+        assert!(idx < self.len, "index out of bounds");
+        let idx = self.offset + idx;
+        if self.bitmap[idx] {
+            Some(self.data[idx])
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> ArrayIterator<'_, Self> {
+        ArrayIterator::new(self)
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        assert!(offset + len <= self.len, "slice out of bounds");
+        Self {
+            data: self.data.clone(),
+            bitmap: self.bitmap.clone(),
+            offset: self.offset + offset,
+            len,
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Bool
+    }
+
+    fn null_bitmap(&self) -> &BitSlice {
+        &self.bitmap[self.offset..self.offset + self.len]
+    }
+}
+
+/// [`ArrayBuilder`] for [`BoolArray`]
+pub struct BoolArrayBuilder {
+    /// The values of this array
+    data: BitVec,
+    /// The null bitmap of this array
+    bitmap: BitVec,
+}
+
+impl ArrayBuilder for BoolArrayBuilder {
+    type Array = BoolArray;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: BitVec::with_capacity(capacity),
+            bitmap: BitVec::with_capacity(capacity),
+        }
+    }
+
+    fn push(&mut self, value: Option<bool>) {
+        self.data.push(value.unwrap_or_default());
+        self.bitmap.push(value.is_some());
+    }
+
+    fn finish(self) -> Self::Array {
+        BoolArray::from_bits(self.data, self.bitmap)
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Bool
+    }
+}
@@ -0,0 +1,230 @@
+use std::sync::Arc;
+
+use bitvec::slice::BitSlice;
+use bitvec::vec::BitVec;
+
+use super::{Array, ArrayBuilder, ArrayIterator};
+use crate::{DataType, Decimal, DecimalError, MAX_DECIMAL_PRECISION};
+
+/// An [`Array`] of [`Decimal`]s which share the same precision and scale.
+///
+/// Only the mantissas are stored, while the precision and scale are kept once for the array.
+#[derive(Clone)]
+pub struct DecimalArray {
+    /// The mantissas of this array
+    data: Arc<[i128]>,
+    /// The null bitmap of this array
+    bitmap: Arc<BitVec>,
+    /// Position of the first item of this array in the buffers
+    offset: usize,
+    /// Number of items of this array
+    len: usize,
+    /// Maximum number of digits of each item
+    precision: u8,
+    /// Number of fractional digits of each item
+    scale: u8,
+}
+
+impl DecimalArray {
+    /// Maximum number of digits of each item
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Number of fractional digits of each item
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+}
+
+impl Array for DecimalArray {
+    type RefItem<'a> = Decimal;
+    type Builder = DecimalArrayBuilder;
+    type OwnedItem = Decimal;
+
+    fn get(&self, idx: usize) -> Option<Self::RefItem<'_>> {
+        assert!(idx < self.len, "index out of bounds");
+        let idx = self.offset + idx;
+        if self.bitmap[idx] {
+            Some(Decimal::new_unchecked(self.data[idx], self.scale))
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> ArrayIterator<'_, Self> {
+        ArrayIterator::new(self)
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        assert!(offset + len <= self.len, "slice out of bounds");
+        Self {
+            data: self.data.clone(),
+            bitmap: self.bitmap.clone(),
+            offset: self.offset + offset,
+            len,
+            precision: self.precision,
+            scale: self.scale,
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Decimal {
+            precision: self.precision,
+            scale: self.scale,
+        }
+    }
+
+    fn null_bitmap(&self) -> &BitSlice {
+        &self.bitmap[self.offset..self.offset + self.len]
+    }
+}
+
+/// [`ArrayBuilder`] for [`DecimalArray`]
+///
+/// If the builder is created with a data type, every pushed value is rescaled to its scale as in
+/// [`Decimal::rescale`]. Otherwise, it uses the maximum precision and the largest scale of the
+/// values pushed so far, so that no value is rounded unless the values cannot all be stored at
+/// that scale within the maximum precision.
+pub struct DecimalArrayBuilder {
+    /// The mantissas of this array
+    data: Vec<i128>,
+    /// The null bitmap of this array
+    bitmap: BitVec,
+    /// Maximum number of digits of each item
+    precision: u8,
+    /// Number of fractional digits of each item, inferred on first push if `None`
+    scale: Option<u8>,
+    /// Whether the scale is inferred from the values rather than given on creation
+    infer_scale: bool,
+}
+
+impl DecimalArrayBuilder {
+    /// Create a builder of the given `precision` and `scale`.
+    pub fn with_precision_scale(
+        capacity: usize,
+        precision: u8,
+        scale: u8,
+    ) -> Result<Self, DecimalError> {
+        if precision == 0 || precision > MAX_DECIMAL_PRECISION || scale > precision {
+            return Err(DecimalError::InvalidPrecisionScale(precision, scale));
+        }
+        Ok(Self {
+            data: Vec::with_capacity(capacity),
+            bitmap: BitVec::with_capacity(capacity),
+            precision,
+            scale: Some(scale),
+            infer_scale: false,
+        })
+    }
+
+    /// Append a value to builder, returning an error if it does not fit into the precision and
+    /// scale of the builder. Nothing is appended on error.
+    pub fn try_push(&mut self, value: Option<Decimal>) -> Result<(), DecimalError> {
+        match value {
+            Some(v) => {
+                let v = if self.infer_scale {
+                    self.widen_scale(v)?
+                } else {
+                    v.rescale_with_precision(self.precision, self.scale.unwrap_or(0))?
+                };
+                self.data.push(v.mantissa());
+                self.bitmap.push(true);
+            }
+            None => {
+                self.data.push(0);
+                self.bitmap.push(false);
+            }
+        }
+        Ok(())
+    }
+
+    /// Widen the inferred scale to the scale of `v` and rescale the values pushed so far, returning
+    /// `v` at the new scale. If the values do not all fit into the precision at that scale, the
+    /// scale is narrowed until they do, rounding the fractional digits that do not fit.
+    fn widen_scale(&mut self, v: Decimal) -> Result<Decimal, DecimalError> {
+        let current = self.scale.unwrap_or(v.scale());
+        let mut scale = current.max(v.scale());
+        loop {
+            let data = if scale == current {
+                Ok(None)
+            } else {
+                self.data
+                    .iter()
+                    .map(|m| {
+                        Decimal::new_unchecked(*m, current)
+                            .rescale_with_precision(self.precision, scale)
+                            .map(|d| d.mantissa())
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(Some)
+            };
+            match (data, v.rescale_with_precision(self.precision, scale)) {
+                (Ok(data), Ok(v)) => {
+                    if let Some(data) = data {
+                        self.data = data;
+                    }
+                    self.scale = Some(scale);
+                    return Ok(v);
+                }
+                (Err(e), _) | (_, Err(e)) if scale == 0 => return Err(e),
+                _ => scale -= 1,
+            }
+        }
+    }
+}
+
+impl ArrayBuilder for DecimalArrayBuilder {
+    type Array = DecimalArray;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            bitmap: BitVec::with_capacity(capacity),
+            precision: MAX_DECIMAL_PRECISION,
+            scale: None,
+            infer_scale: true,
+        }
+    }
+
+    fn with_type(data_type: &DataType, capacity: usize) -> Self {
+        match data_type {
+            DataType::Decimal { precision, scale } => {
+                Self::with_precision_scale(capacity, *precision, *scale).unwrap()
+            }
+            other => panic!("cannot build decimal array of {}", other),
+        }
+    }
+
+    /// Append a value to builder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder is created with a data type and the value does not fit into its
+    /// precision and scale. Use [`DecimalArrayBuilder::try_push`] to handle the error.
+    fn push(&mut self, value: Option<Decimal>) {
+        self.try_push(value).unwrap()
+    }
+
+    fn finish(self) -> Self::Array {
+        DecimalArray {
+            len: self.data.len(),
+            offset: 0,
+            data: self.data.into(),
+            bitmap: Arc::new(self.bitmap),
+            precision: self.precision,
+            scale: self.scale.unwrap_or(0),
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Decimal {
+            precision: self.precision,
+            scale: self.scale.unwrap_or(0),
+        }
+    }
+}
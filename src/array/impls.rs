@@ -1,3 +1,5 @@
+use bitvec::slice::BitSlice;
+
 use crate::macros::for_all_variants;
 use crate::*;
 
@@ -32,6 +34,31 @@ macro_rules! impl_array_dispatch {
                 }
             }
 
+            /// Get a window of `len` items starting at `offset`, which shares the buffers with
+            /// `self`. See [`Array::slice`].
+            pub fn slice(&self, offset: usize, len: usize) -> Self {
+                match self {
+                    $(
+                        Self::$Abc(a) => Self::$Abc(a.slice(offset, len)),
+                    )*
+                }
+            }
+
+            /// Copy the items at `indices` into a new array, in the order of `indices`.
+            pub fn take(&self, indices: &[usize]) -> Self {
+                match self {
+                    $(
+                        Self::$Abc(a) => {
+                            let mut builder = <$AbcArrayBuilder>::with_type(&a.data_type(), indices.len());
+                            for &idx in indices {
+                                builder.push(a.get(idx));
+                            }
+                            Self::$Abc(builder.finish())
+                        }
+                    )*
+                }
+            }
+
             /// Get identifier of the current array
             pub fn identifier(&self) -> &'static str {
                 match self {
@@ -41,6 +68,32 @@ macro_rules! impl_array_dispatch {
                 }
             }
 
+            /// Get data type of the current array
+            pub fn data_type(&self) -> DataType {
+                match self {
+                    $(
+                        Self::$Abc(a) => a.data_type(),
+                    )*
+                }
+            }
+
+            /// The null bitmap of the current array, where a set bit is a non-null item.
+            pub fn null_bitmap(&self) -> &BitSlice {
+                match self {
+                    $(
+                        Self::$Abc(a) => a.null_bitmap(),
+                    )*
+                }
+            }
+
+            /// Number of `NULL` items of the current array
+            pub fn null_count(&self) -> usize {
+                match self {
+                    $(
+                        Self::$Abc(a) => a.null_count(),
+                    )*
+                }
+            }
         }
     };
 }
@@ -51,6 +104,15 @@ for_all_variants! { impl_array_dispatch }
 macro_rules! impl_array_builder_dispatch {
     ([], $({$Abc:ident, $abc:ident, $AbcArray:ty, $AbcArrayBuilder:ty, $Owned:ty, $Ref:ty}),*) => {
         impl ArrayBuilderImpl {
+            /// Create a builder of `data_type` with `capacity`
+            pub fn new(data_type: &DataType, capacity: usize) -> Self {
+                match data_type {
+                    $(
+                        DataType::$Abc { .. } => Self::$Abc(<$AbcArrayBuilder>::with_type(data_type, capacity)),
+                    )*
+                }
+            }
+
             /// Appends an element to the back of array
             pub fn push(&mut self, v: Option<ScalarRefImpl<'_>>) {
                 match (self, v) {
@@ -58,7 +120,7 @@ macro_rules! impl_array_builder_dispatch {
                         (Self::$Abc(a), Some(ScalarRefImpl::$Abc(v))) => a.push(Some(v)),
                         (Self::$Abc(a), None) => a.push(None),
                     )*
-                    (a, Some(b)) => Err(TypeMismatch(a.identifier(), b.identifier())).unwrap(),
+                    (a, Some(b)) => Err(TypeMismatch(a.data_type().kind(), b.data_type())).unwrap(),
                 }
             }
 
@@ -79,6 +141,15 @@ macro_rules! impl_array_builder_dispatch {
                     )*
                 }
             }
+
+            /// Get data type of the current array builder
+            pub fn data_type(&self) -> DataType {
+                match self {
+                    $(
+                        Self::$Abc(a) => a.data_type(),
+                    )*
+                }
+            }
         }
     };
 }
@@ -102,7 +173,7 @@ macro_rules! impl_array_conversion {
                 fn try_from(array: ArrayImpl) -> Result<Self, Self::Error> {
                     match array {
                         ArrayImpl::$Abc(array) => Ok(array),
-                        other => Err(TypeMismatch(stringify!($Abc), other.identifier())),
+                        other => Err(TypeMismatch(DataTypeKind::$Abc, other.data_type())),
                     }
                 }
             }
@@ -113,7 +184,7 @@ macro_rules! impl_array_conversion {
                 fn try_from(array: &'a ArrayImpl) -> Result<Self, Self::Error> {
                     match array {
                         ArrayImpl::$Abc(array) => Ok(array),
-                        other => Err(TypeMismatch(stringify!($Abc), other.identifier())),
+                        other => Err(TypeMismatch(DataTypeKind::$Abc, other.data_type())),
                     }
                 }
             }
@@ -131,7 +202,7 @@ macro_rules! impl_array_conversion {
                 fn try_from(builder: ArrayBuilderImpl) -> Result<Self, Self::Error> {
                     match builder {
                         ArrayBuilderImpl::$Abc(builder) => Ok(builder),
-                        other => Err(TypeMismatch(stringify!($Abc), other.identifier())),
+                        other => Err(TypeMismatch(DataTypeKind::$Abc, other.data_type())),
                     }
                 }
             }
@@ -142,7 +213,7 @@ macro_rules! impl_array_conversion {
                 fn try_from(builder: &'a ArrayBuilderImpl) -> Result<Self, Self::Error> {
                     match builder {
                         ArrayBuilderImpl::$Abc(builder) => Ok(builder),
-                        other => Err(TypeMismatch(stringify!($Abc), other.identifier())),
+                        other => Err(TypeMismatch(DataTypeKind::$Abc, other.data_type())),
                     }
                 }
             }
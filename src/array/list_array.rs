@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use bitvec::slice::BitSlice;
+use bitvec::vec::BitVec;
+use thiserror::Error;
+
+use super::{Array, ArrayBuilder, ArrayBuilderImpl, ArrayImpl, ArrayIterator};
+use crate::{DataType, ListRef, ListValue};
+
+/// An [`Array`] of lists, whose items are stored in a child [`ArrayImpl`].
+///
+/// Like [`StringArray`](super::StringArray), the items of the `i`-th list are
+/// `offsets[i]..offsets[i + 1]` of the child array.
+#[derive(Clone)]
+pub struct ListArray {
+    /// The flattened items of all lists
+    values: Arc<ArrayImpl>,
+    /// Offsets of each list in the child array
+    offsets: Arc<[usize]>,
+    /// The null bitmap of this array
+    bitmap: Arc<BitVec>,
+    /// Position of the first item of this array in `offsets` and `bitmap`
+    offset: usize,
+    /// Number of items of this array
+    len: usize,
+}
+
+impl ListArray {
+    /// Data type of the items of each list
+    pub fn value_type(&self) -> DataType {
+        self.values.data_type()
+    }
+
+    /// The flattened items of all lists in this array, which shares the buffers with `self`.
+    /// Null lists contribute no items.
+    pub fn flat_values(&self) -> ArrayImpl {
+        let start = self.offsets[self.offset];
+        let end = self.offsets[self.offset + self.len];
+        self.values.slice(start, end - start)
+    }
+
+    /// Create an array of the same lists as `self`, whose items are replaced by `values`, e.g.
+    /// the result of a function on [`ListArray::flat_values`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` does not have as many items as [`ListArray::flat_values`].
+    pub fn with_flat_values(&self, values: ArrayImpl) -> Self {
+        let start = self.offsets[self.offset];
+        let offsets: Arc<[usize]> = self.offsets[self.offset..=self.offset + self.len]
+            .iter()
+            .map(|offset| offset - start)
+            .collect();
+        assert_eq!(offsets[self.len], values.len(), "array length mismatch");
+        Self {
+            values: Arc::new(values),
+            offsets,
+            bitmap: Arc::new(self.bitmap[self.offset..self.offset + self.len].to_bitvec()),
+            offset: 0,
+            len: self.len,
+        }
+    }
+}
+
+impl Array for ListArray {
+    type RefItem<'a> = ListRef<'a>;
+    type Builder = ListArrayBuilder;
+    type OwnedItem = ListValue;
+
+    fn get(&self, idx: usize) -> Option<Self::RefItem<'_>> {
+        assert!(idx < self.len, "index out of bounds");
+        let idx = self.offset + idx;
+        if self.bitmap[idx] {
+            Some(ListRef::new(
+                &self.values,
+                self.offsets[idx],
+                self.offsets[idx + 1],
+            ))
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> ArrayIterator<'_, Self> {
+        ArrayIterator::new(self)
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        assert!(offset + len <= self.len, "slice out of bounds");
+        Self {
+            values: self.values.clone(),
+            offsets: self.offsets.clone(),
+            bitmap: self.bitmap.clone(),
+            offset: self.offset + offset,
+            len,
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::List(Box::new(self.value_type()))
+    }
+
+    fn null_bitmap(&self) -> &BitSlice {
+        &self.bitmap[self.offset..self.offset + self.len]
+    }
+}
+
+/// Returned when a list is pushed into a [`ListArrayBuilder`] of another item type.
+#[derive(Error, Debug)]
+#[error("List item type mismatch: expected {0}, get {1}")]
+pub struct ListTypeMismatch(pub DataType, pub DataType);
+
+/// [`ArrayBuilder`] for [`ListArray`]
+///
+/// If the builder is created without a data type, it takes the item type of the first non-null
+/// list. As the item type cannot be known if all lists are null, it then falls back to `int32`,
+/// so callers that know the type of the column should create the builder with
+/// [`ArrayBuilder::with_type`].
+pub struct ListArrayBuilder {
+    /// The builder of the flattened items, created on first push if `None`
+    values: Option<Box<ArrayBuilderImpl>>,
+    /// Offsets of each list in the child array
+    offsets: Vec<usize>,
+    /// The null bitmap of this array
+    bitmap: BitVec,
+    /// Number of items pushed into `values`
+    num_values: usize,
+}
+
+impl ListArrayBuilder {
+    /// Append a list to builder, returning an error if its items are not of the item type of the
+    /// builder. Nothing is appended on error.
+    pub fn try_push(&mut self, value: Option<ListRef<'_>>) -> Result<(), ListTypeMismatch> {
+        match value {
+            Some(list) => {
+                let values = self
+                    .values
+                    .get_or_insert_with(|| Box::new(ArrayBuilderImpl::new(&list.value_type(), 0)));
+                if values.data_type() != list.value_type() {
+                    return Err(ListTypeMismatch(values.data_type(), list.value_type()));
+                }
+                for item in list.iter() {
+                    values.push(item);
+                }
+                self.num_values += list.len();
+                self.offsets.push(self.num_values);
+                self.bitmap.push(true);
+            }
+            None => {
+                self.offsets.push(self.num_values);
+                self.bitmap.push(false);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ArrayBuilder for ListArrayBuilder {
+    type Array = ListArray;
+
+    fn with_capacity(capacity: usize) -> Self {
+        let mut offsets = Vec::with_capacity(capacity + 1);
+        offsets.push(0);
+        Self {
+            values: None,
+            offsets,
+            bitmap: BitVec::with_capacity(capacity),
+            num_values: 0,
+        }
+    }
+
+    fn with_type(data_type: &DataType, capacity: usize) -> Self {
+        match data_type {
+            DataType::List(value_type) => {
+                let mut builder = Self::with_capacity(capacity);
+                builder.values = Some(Box::new(ArrayBuilderImpl::new(value_type, capacity)));
+                builder
+            }
+            other => panic!("cannot build list array of {}", other),
+        }
+    }
+
+    /// Append a list to builder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the items of the list are not of the item type of the builder. Use
+    /// [`ListArrayBuilder::try_push`] to handle the error.
+    fn push(&mut self, value: Option<ListRef<'_>>) {
+        self.try_push(value).unwrap()
+    }
+
+    /// Finish build and return a new array, whose items are `int32` if the item type is unknown.
+    fn finish(self) -> Self::Array {
+        let values = self.values.map_or_else(
+            || ArrayBuilderImpl::new(&DataType::Int32, 0),
+            |values| *values,
+        );
+        ListArray {
+            len: self.bitmap.len(),
+            offset: 0,
+            values: Arc::new(values.finish()),
+            offsets: self.offsets.into(),
+            bitmap: Arc::new(self.bitmap),
+        }
+    }
+
+    /// Data type of the array being built, whose items are `int32` if the item type is unknown.
+    fn data_type(&self) -> DataType {
+        let value_type = match &self.values {
+            Some(values) => values.data_type(),
+            None => DataType::Int32,
+        };
+        DataType::List(Box::new(value_type))
+    }
+}
@@ -1,13 +1,27 @@
+mod binary_array;
+mod bool_array;
+mod decimal_array;
 mod impls;
 mod iterator;
+mod list_array;
 mod primitive_array;
 mod string_array;
+mod struct_array;
+mod timestamp_array;
 
+pub use binary_array::*;
+pub use bool_array::*;
+pub use decimal_array::*;
 pub use iterator::*;
+pub use list_array::*;
 pub use primitive_array::*;
 pub use string_array::*;
+pub use struct_array::*;
+pub use timestamp_array::*;
 
-use crate::{Scalar, ScalarRef};
+use bitvec::slice::BitSlice;
+
+use crate::{DataType, Scalar, ScalarRef};
 
 /// [`Array`] is a collection of data of the some type
 pub trait Array: Send + Sync + Sized + 'static + TryFrom<ArrayImpl> + Into<ArrayImpl>
@@ -37,7 +51,28 @@ where
     }
 
     /// Get iterator of this array
-    fn iter(&self) -> ArrayIterator<Self>;
+    fn iter(&self) -> ArrayIterator<'_, Self>;
+
+    /// Get a window of `len` items starting at `offset`, which shares the buffers with `self`.
+    ///
+    /// The buffers of all arrays are reference-counted, so that cloning and slicing do not copy
+    /// any data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset + len` is larger than the length of the array.
+    fn slice(&self, offset: usize, len: usize) -> Self;
+
+    /// Data type of this array
+    fn data_type(&self) -> DataType;
+
+    /// The null bitmap of this array, where a set bit is a non-null item.
+    fn null_bitmap(&self) -> &BitSlice;
+
+    /// Number of `NULL` items of this array
+    fn null_count(&self) -> usize {
+        self.null_bitmap().count_zeros()
+    }
 
     /// Build array from slice
     fn from_slice(data: &[Option<Self::RefItem<'_>>]) -> Self {
@@ -62,14 +97,27 @@ pub trait ArrayBuilder {
     /// Create a builder with `capacity`
     fn with_capacity(capacity: usize) -> Self;
 
+    /// Create a builder of `data_type` with `capacity`. Only builders of types with parameters,
+    /// such as the precision and scale of a decimal, need to look at `data_type`.
+    fn with_type(_data_type: &DataType, capacity: usize) -> Self
+    where
+        Self: Sized,
+    {
+        Self::with_capacity(capacity)
+    }
+
     /// Append a value to builder
     fn push(&mut self, value: Option<<Self::Array as Array>::RefItem<'_>>);
 
     /// Finish build and return a new array
     fn finish(self) -> Self::Array;
+
+    /// Data type of the array being built
+    fn data_type(&self) -> DataType;
 }
 
 /// Encapsules all variables of [`Array`]
+#[derive(Clone)]
 pub enum ArrayImpl {
     Int16(I16Array),
     Int32(I32Array),
@@ -78,6 +126,14 @@ pub enum ArrayImpl {
     Float64(F64Array),
     Bool(BoolArray),
     String(StringArray),
+    Binary(BinaryArray),
+    Decimal(DecimalArray),
+    Date32(Date32Array),
+    Time64(Time64Array),
+    Timestamp(TimestampArray),
+    Interval(IntervalArray),
+    List(ListArray),
+    Struct(StructArray),
 }
 
 /// Encapsules all variables of [`ArrayBuilder`]
@@ -89,12 +145,20 @@ pub enum ArrayBuilderImpl {
     Float64(F64ArrayBuilder),
     Bool(BoolArrayBuilder),
     String(StringArrayBuilder),
+    Binary(BinaryArrayBuilder),
+    Decimal(DecimalArrayBuilder),
+    Date32(Date32ArrayBuilder),
+    Time64(Time64ArrayBuilder),
+    Timestamp(TimestampArrayBuilder),
+    Interval(IntervalArrayBuilder),
+    List(ListArrayBuilder),
+    Struct(StructArrayBuilder),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::TypeMismatch;
+    use crate::{Decimal, TypeMismatch};
 
     /// Build an array from a vector of data
     fn build_array_from_vec<A: Array>(items: &[Option<A::RefItem<'_>>]) -> A {
@@ -117,18 +181,34 @@ mod tests {
 
     #[test]
     fn test_build_int32_array() {
-        let data = vec![Some(1), Some(2), Some(3), None, Some(5)];
+        let data = [Some(1), Some(2), Some(3), None, Some(5)];
         let array = build_array_from_vec::<I32Array>(&data[..]);
         check_array_eq(&array, &data[..]);
     }
 
     #[test]
     fn test_build_string_array() {
-        let data = vec![Some("1"), Some("2"), Some("3"), None, Some("5"), Some("")];
+        let data = [Some("1"), Some("2"), Some("3"), None, Some("5"), Some("")];
         let array = build_array_from_vec::<StringArray>(&data[..]);
         check_array_eq(&array, &data[..]);
     }
 
+    #[test]
+    fn test_binary_to_string_array() {
+        let data: [Option<&[u8]>; 4] = [Some(b"abc"), None, Some(b""), Some(&[0xe4, 0xb8, 0xad])];
+        let array = build_array_from_vec::<BinaryArray>(&data[..]);
+        check_array_eq(&array, &data[..]);
+
+        let strings = StringArray::try_from(array.slice(1, 3)).unwrap();
+        check_array_eq(&strings, &[None, Some(""), Some("中")]);
+        let bytes = BinaryArray::from(strings);
+        check_array_eq(&bytes, &data[1..]);
+
+        let invalid = BinaryArray::from_slice(&[Some(&b"ok"[..]), Some(&[0xe4, 0xb8])]);
+        let err = StringArray::try_from(invalid).err().unwrap();
+        assert_eq!(err.0, 1);
+    }
+
     fn add_i32(i1: i32, i2: i32) -> i32 {
         i1 + i2
     }
@@ -165,4 +245,75 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_slice_array() {
+        let data = [Some(1), None, Some(3), Some(4), None, Some(6)];
+        let array = I32Array::from_slice(&data);
+        let sliced = array.slice(1, 4);
+        assert_eq!(sliced.len(), 4);
+        check_array_eq(&sliced, &data[1..5]);
+        assert_eq!(sliced.iter().count(), 4);
+
+        let sliced = sliced.slice(2, 2);
+        assert_eq!(sliced.len(), 2);
+        check_array_eq(&sliced, &data[3..5]);
+        assert!(sliced.slice(2, 0).is_empty());
+
+        let data = [Some("a"), Some("bc"), None, Some(""), Some("def")];
+        let array: ArrayImpl = StringArray::from_slice(&data).into();
+        let sliced: StringArray = array.slice(2, 3).try_into().unwrap();
+        check_array_eq(&sliced, &data[2..]);
+        assert_eq!(sliced.iter().count(), 3);
+    }
+
+    #[test]
+    fn test_build_decimal_array() {
+        let data = ["1.5", "-0.2", "3.0"].map(|s| Some(s.parse::<Decimal>().unwrap()));
+        let array = build_array_from_vec::<DecimalArray>(&[data[0], None, data[1], data[2]]);
+        assert_eq!(array.scale(), 1);
+        assert_eq!(array.precision(), crate::MAX_DECIMAL_PRECISION);
+        let items = array
+            .iter()
+            .map(|v| v.map(|v| v.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            [Some("1.5"), None, Some("-0.2"), Some("3.0")].map(|v| v.map(String::from))
+        );
+
+        // the inferred scale is widened to the largest scale, and the values are rescaled
+        let dec = |s: &str| Some(s.parse::<Decimal>().unwrap());
+        let array = DecimalArray::from_slice(&[dec("1.5"), None, dec("-0.25"), dec("3")]);
+        assert_eq!(array.scale(), 2);
+        let items = array
+            .iter()
+            .map(|v| v.map(|v| v.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            [Some("1.50"), None, Some("-0.25"), Some("3.00")].map(|v| v.map(String::from))
+        );
+        // unless the values would not fit into the maximum precision
+        let big = Decimal::new(10i128.pow(30), 0).unwrap();
+        let array = DecimalArray::from_slice(&[dec("0.123456789"), Some(big)]);
+        assert_eq!(array.scale(), 7);
+        assert_eq!(array.get(0).unwrap().to_string(), "0.1234568");
+        assert_eq!(array.get(1).unwrap(), big);
+        let mut builder = DecimalArrayBuilder::with_precision_scale(2, 10, 1).unwrap();
+        builder.push(dec("-0.25"));
+        assert_eq!(builder.finish().get(0).unwrap().to_string(), "-0.3");
+
+        let mut builder = DecimalArrayBuilder::with_precision_scale(2, 3, 2).unwrap();
+        assert!(builder.try_push(data[0]).is_ok());
+        assert!(builder.try_push(Some(Decimal::from(10))).is_err());
+        assert_eq!(builder.finish().len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_slice_out_of_bounds() {
+        let array = I32Array::from_slice(&[Some(1), Some(2), Some(3)]);
+        array.slice(1, 1).get(1);
+    }
 }
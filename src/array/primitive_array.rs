@@ -1,38 +1,71 @@
 use std::fmt::Debug;
+use std::sync::Arc;
 
+use bitvec::slice::BitSlice;
 use bitvec::vec::BitVec;
 
 use super::{Array, ArrayBuilder, ArrayIterator};
-use crate::{ArrayImpl, Scalar, ScalarRef};
+use crate::{ArrayImpl, DataType, Date, Interval, Scalar, ScalarRef, Time};
 
-pub trait PrimitiveType: Copy + Send + Sync + Default + Debug + 'static {}
+pub trait PrimitiveType: Copy + Send + Sync + Default + Debug + 'static {
+    /// Data type of [`PrimitiveArray`] of this type
+    const DATA_TYPE: DataType;
+}
 
 pub type I16Array = PrimitiveArray<i16>;
 pub type I32Array = PrimitiveArray<i32>;
 pub type I64Array = PrimitiveArray<i64>;
 pub type F32Array = PrimitiveArray<f32>;
 pub type F64Array = PrimitiveArray<f64>;
-pub type BoolArray = PrimitiveArray<bool>;
+pub type Date32Array = PrimitiveArray<Date>;
+pub type Time64Array = PrimitiveArray<Time>;
+pub type IntervalArray = PrimitiveArray<Interval>;
 
 pub type I16ArrayBuilder = PrimitiveArrayBuilder<i16>;
 pub type I32ArrayBuilder = PrimitiveArrayBuilder<i32>;
 pub type I64ArrayBuilder = PrimitiveArrayBuilder<i64>;
 pub type F32ArrayBuilder = PrimitiveArrayBuilder<f32>;
 pub type F64ArrayBuilder = PrimitiveArrayBuilder<f64>;
-pub type BoolArrayBuilder = PrimitiveArrayBuilder<bool>;
+pub type Date32ArrayBuilder = PrimitiveArrayBuilder<Date>;
+pub type Time64ArrayBuilder = PrimitiveArrayBuilder<Time>;
+pub type IntervalArrayBuilder = PrimitiveArrayBuilder<Interval>;
 
-impl PrimitiveType for i16 {}
-impl PrimitiveType for i32 {}
-impl PrimitiveType for i64 {}
-impl PrimitiveType for f32 {}
-impl PrimitiveType for f64 {}
-impl PrimitiveType for bool {}
+impl PrimitiveType for i16 {
+    const DATA_TYPE: DataType = DataType::Int16;
+}
+impl PrimitiveType for i32 {
+    const DATA_TYPE: DataType = DataType::Int32;
+}
+impl PrimitiveType for i64 {
+    const DATA_TYPE: DataType = DataType::Int64;
+}
+impl PrimitiveType for f32 {
+    const DATA_TYPE: DataType = DataType::Float32;
+}
+impl PrimitiveType for f64 {
+    const DATA_TYPE: DataType = DataType::Float64;
+}
+impl PrimitiveType for Date {
+    const DATA_TYPE: DataType = DataType::Date32;
+}
+impl PrimitiveType for Time {
+    const DATA_TYPE: DataType = DataType::Time64;
+}
+impl PrimitiveType for Interval {
+    const DATA_TYPE: DataType = DataType::Interval;
+}
 
+/// An [`Array`] of fixed-size values, stored contiguously next to a null bitmap.
+#[derive(Clone)]
 pub struct PrimitiveArray<T: PrimitiveType> {
     /// The actual data of this array
-    data: Vec<T>,
+    data: Arc<[T]>,
     /// The null bitmap of this array
-    bitmap: BitVec,
+    bitmap: Arc<BitVec>,
+    /// Position of the first item of this array in the buffers
+    offset: usize,
+    /// Number of items of this array
+    len: usize,
 }
 
 impl<T> Array for PrimitiveArray<T>
@@ -49,6 +82,8 @@ where
     type OwnedItem = T;
 
     fn get(&self, idx: usize) -> Option<Self::RefItem<'_>> {
+        assert!(idx < self.len, "index out of bounds");
+        let idx = self.offset + idx;
         if self.bitmap[idx] {
             Some(self.data[idx])
         } else {
@@ -57,12 +92,30 @@ where
     }
 
     fn len(&self) -> usize {
-        self.data.len()
+        self.len
     }
 
-    fn iter(&self) -> ArrayIterator<Self> {
+    fn iter(&self) -> ArrayIterator<'_, Self> {
         ArrayIterator::new(self)
     }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        assert!(offset + len <= self.len, "slice out of bounds");
+        Self {
+            data: self.data.clone(),
+            bitmap: self.bitmap.clone(),
+            offset: self.offset + offset,
+            len,
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        T::DATA_TYPE
+    }
+
+    fn null_bitmap(&self) -> &BitSlice {
+        &self.bitmap[self.offset..self.offset + self.len]
+    }
 }
 
 /// [`ArrayBuilder`] for [`PrimitiveArray`]
@@ -106,8 +159,14 @@ where
 
     fn finish(self) -> Self::Array {
         PrimitiveArray {
-            data: self.data,
-            bitmap: self.bitmap,
+            len: self.data.len(),
+            offset: 0,
+            data: self.data.into(),
+            bitmap: Arc::new(self.bitmap),
         }
     }
+
+    fn data_type(&self) -> DataType {
+        T::DATA_TYPE
+    }
 }
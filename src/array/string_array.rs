@@ -1,15 +1,23 @@
-use bitvec::vec::BitVec;
+use std::fmt;
+use std::str::Utf8Error;
 
-use super::{Array, ArrayBuilder, ArrayIterator};
+use bitvec::slice::BitSlice;
+use thiserror::Error;
 
-pub struct StringArray {
-    /// The flattened data of string
-    data: Vec<u8>,
-    /// Offsets of each string in the data flat array
-    offsets: Vec<usize>,
-    /// The null bitmap of this array
-    bitmap: BitVec,
-}
+use super::{Array, ArrayBuilder, ArrayIterator, BinaryArray, BinaryArrayBuilder};
+use crate::DataType;
+
+/// Returned when converting a [`BinaryArray`] with an item that is not valid UTF-8 into a
+/// [`StringArray`].
+#[derive(Error, Debug)]
+#[error("Invalid UTF-8 in row {0}: {1}")]
+pub struct InvalidUtf8(pub usize, #[source] pub Utf8Error);
+
+/// An [`Array`] of strings.
+///
+/// It is a [`BinaryArray`] whose items are all valid UTF-8.
+#[derive(Clone)]
+pub struct StringArray(BinaryArray);
 
 impl Array for StringArray {
     type RefItem<'a> = &'a str;
@@ -17,65 +25,107 @@ impl Array for StringArray {
     type OwnedItem = String;
 
     fn get(&self, idx: usize) -> Option<Self::RefItem<'_>> {
-        if self.bitmap[idx] {
-            let range = self.offsets[idx]..self.offsets[idx + 1];
-            Some(unsafe { std::str::from_utf8_unchecked(&self.data[range]) })
-        } else {
-            None
-        }
+        // Safety: every item is validated on construction
+        self.0
+            .get(idx)
+            .map(|v| unsafe { std::str::from_utf8_unchecked(v) })
     }
 
     fn len(&self) -> usize {
-        self.bitmap.len()
+        self.0.len()
     }
 
-    fn iter(&self) -> ArrayIterator<Self> {
+    fn iter(&self) -> ArrayIterator<'_, Self> {
         ArrayIterator::new(self)
     }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        Self(self.0.slice(offset, len))
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::String
+    }
+
+    fn null_bitmap(&self) -> &BitSlice {
+        self.0.null_bitmap()
+    }
+}
+
+/// Implement [`StringArray`] -> [`BinaryArray`], which shares the buffers.
+impl From<StringArray> for BinaryArray {
+    fn from(array: StringArray) -> Self {
+        array.0
+    }
+}
+
+/// Implement [`BinaryArray`] -> [`StringArray`], which shares the buffers after checking that
+/// every item is valid UTF-8.
+impl TryFrom<BinaryArray> for StringArray {
+    type Error = InvalidUtf8;
+
+    fn try_from(array: BinaryArray) -> Result<Self, Self::Error> {
+        for (row, item) in array.iter().enumerate() {
+            if let Some(v) = item {
+                std::str::from_utf8(v).map_err(|e| InvalidUtf8(row, e))?;
+            }
+        }
+        Ok(Self(array))
+    }
 }
 
 /// [`ArrayBuilder`] for [`StringArray`]
-pub struct StringArrayBuilder {
-    /// The flattened data of string
-    data: Vec<u8>,
-    /// Offsets of each string in the data flat array
-    offsets: Vec<usize>,
-    /// The null bitmap of this array
-    bitmap: BitVec,
+pub struct StringArrayBuilder(BinaryArrayBuilder);
+
+impl StringArrayBuilder {
+    /// Append a string which `f` writes piece by piece, so that it is not built separately.
+    /// Nothing is appended if `f` fails.
+    pub fn push_with<E>(
+        &mut self,
+        f: impl FnOnce(&mut StringWriter<'_>) -> Result<(), E>,
+    ) -> Result<(), E> {
+        self.0.push_with(|data| f(&mut StringWriter(data)))
+    }
+}
+
+/// Writes a string into a [`StringArrayBuilder`], see [`StringArrayBuilder::push_with`].
+pub struct StringWriter<'a>(&'a mut Vec<u8>);
+
+impl StringWriter<'_> {
+    /// Append a string slice.
+    pub fn push_str(&mut self, s: &str) {
+        self.0.extend_from_slice(s.as_bytes());
+    }
+
+    /// Append a char.
+    pub fn push(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+}
+
+impl fmt::Write for StringWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
 }
 
 impl ArrayBuilder for StringArrayBuilder {
     type Array = StringArray;
 
     fn with_capacity(capacity: usize) -> Self {
-        let mut offsets = Vec::with_capacity(capacity + 1);
-        offsets.push(0);
-        Self {
-            data: Vec::with_capacity(capacity),
-            offsets,
-            bitmap: BitVec::with_capacity(capacity),
-        }
+        Self(BinaryArrayBuilder::with_capacity(capacity))
     }
 
-    fn push(&mut self, value: Option<<Self::Array as Array>::RefItem<'_>>) {
-        match value {
-            Some(v) => {
-                self.data.extend(v.as_bytes());
-                self.offsets.push(self.data.len());
-                self.bitmap.push(true);
-            }
-            None => {
-                self.offsets.push(self.data.len());
-                self.bitmap.push(false);
-            }
-        }
+    fn push(&mut self, value: Option<&str>) {
+        self.0.push(value.map(str::as_bytes))
     }
 
     fn finish(self) -> Self::Array {
-        StringArray {
-            data: self.data,
-            offsets: self.offsets,
-            bitmap: self.bitmap,
-        }
+        StringArray(self.0.finish())
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::String
     }
 }
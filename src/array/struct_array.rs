@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use bitvec::slice::BitSlice;
+use bitvec::vec::BitVec;
+
+use super::{Array, ArrayBuilder, ArrayBuilderImpl, ArrayImpl, ArrayIterator};
+use crate::{DataType, Field, ScalarRefImpl, StructRef, StructValue};
+
+/// An [`Array`] of structs, which stores each field in a child [`ArrayImpl`].
+///
+/// The `i`-th struct consists of the `i`-th item of every child array. A null struct still has an
+/// item, usually null, in each child.
+#[derive(Clone)]
+pub struct StructArray {
+    /// Names and types of the fields
+    fields: Arc<[Field]>,
+    /// One array for each field, all of the same length
+    children: Arc<[ArrayImpl]>,
+    /// The null bitmap of this array
+    bitmap: Arc<BitVec>,
+    /// Position of the first item of this array in `children` and `bitmap`
+    offset: usize,
+    /// Number of items of this array
+    len: usize,
+}
+
+impl StructArray {
+    /// Create an array of `len` structs of `fields`, with one child array of each field. If
+    /// `bitmap` is `None`, no struct is null.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a child is not of the type of its field, or if the children and `bitmap` are not
+    /// of length `len`.
+    pub fn new(
+        fields: Vec<Field>,
+        children: Vec<ArrayImpl>,
+        len: usize,
+        bitmap: Option<BitVec>,
+    ) -> Self {
+        assert_eq!(fields.len(), children.len(), "number of fields mismatch");
+        for (field, child) in fields.iter().zip(&children) {
+            assert_eq!(field.data_type(), &child.data_type(), "field type mismatch");
+        }
+        assert!(
+            children.iter().all(|child| child.len() == len)
+                && bitmap.as_ref().is_none_or(|bitmap| bitmap.len() == len),
+            "array length mismatch"
+        );
+        Self {
+            fields: fields.into(),
+            children: children.into(),
+            bitmap: Arc::new(bitmap.unwrap_or_else(|| BitVec::repeat(true, len))),
+            offset: 0,
+            len,
+        }
+    }
+
+    /// Names and types of the fields
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Position of the field of `name`, if any.
+    pub fn field_index(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|field| field.name() == name)
+    }
+
+    /// The values of the field at position `idx`, which share the buffers with `self`. Items of
+    /// null structs are taken from the child as is, and are not necessarily null.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn child(&self, idx: usize) -> ArrayImpl {
+        self.children[idx].slice(self.offset, self.len)
+    }
+
+    /// Get the item of field `field` in the struct at index `idx`, ignoring whether the struct is
+    /// null.
+    pub(crate) fn child_value(&self, field: usize, idx: usize) -> Option<ScalarRefImpl<'_>> {
+        self.children[field].get(self.offset + idx)
+    }
+}
+
+impl Array for StructArray {
+    type RefItem<'a> = StructRef<'a>;
+    type Builder = StructArrayBuilder;
+    type OwnedItem = StructValue;
+
+    fn get(&self, idx: usize) -> Option<Self::RefItem<'_>> {
+        assert!(idx < self.len, "index out of bounds");
+        if self.bitmap[self.offset + idx] {
+            Some(StructRef::new(self, idx))
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> ArrayIterator<'_, Self> {
+        ArrayIterator::new(self)
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        assert!(offset + len <= self.len, "slice out of bounds");
+        Self {
+            fields: self.fields.clone(),
+            children: self.children.clone(),
+            bitmap: self.bitmap.clone(),
+            offset: self.offset + offset,
+            len,
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Struct(self.fields.to_vec())
+    }
+
+    fn null_bitmap(&self) -> &BitSlice {
+        &self.bitmap[self.offset..self.offset + self.len]
+    }
+}
+
+/// [`ArrayBuilder`] for [`StructArray`]
+///
+/// If the builder is created without a data type, it takes the fields of the first non-null
+/// struct, or no fields if there is none.
+pub struct StructArrayBuilder {
+    /// Names and types of the fields, decided on first push if `None`
+    fields: Option<Vec<Field>>,
+    /// One builder for each field
+    children: Vec<ArrayBuilderImpl>,
+    /// The null bitmap of this array
+    bitmap: BitVec,
+}
+
+impl StructArrayBuilder {
+    /// Create the child builders of `fields`, filled with nulls for the structs pushed so far.
+    fn init_children(&mut self, fields: Vec<Field>) {
+        self.children = fields
+            .iter()
+            .map(|field| {
+                let mut child = ArrayBuilderImpl::new(field.data_type(), self.bitmap.capacity());
+                for _ in 0..self.bitmap.len() {
+                    child.push(None);
+                }
+                child
+            })
+            .collect();
+        self.fields = Some(fields);
+    }
+}
+
+impl ArrayBuilder for StructArrayBuilder {
+    type Array = StructArray;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            fields: None,
+            children: vec![],
+            bitmap: BitVec::with_capacity(capacity),
+        }
+    }
+
+    fn with_type(data_type: &DataType, capacity: usize) -> Self {
+        match data_type {
+            DataType::Struct(fields) => {
+                let mut builder = Self::with_capacity(capacity);
+                builder.init_children(fields.clone());
+                builder
+            }
+            other => panic!("cannot build struct array of {}", other),
+        }
+    }
+
+    /// Append a struct to builder. The fields of a null struct are pushed as nulls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the struct does not have the fields of the builder.
+    fn push(&mut self, value: Option<StructRef<'_>>) {
+        match value {
+            Some(v) => {
+                if self.fields.is_none() {
+                    self.init_children(v.fields().to_vec());
+                }
+                assert_eq!(
+                    self.children.len(),
+                    v.fields().len(),
+                    "number of fields mismatch"
+                );
+                for (child, item) in self.children.iter_mut().zip(v.iter()) {
+                    child.push(item);
+                }
+                self.bitmap.push(true);
+            }
+            None => {
+                for child in &mut self.children {
+                    child.push(None);
+                }
+                self.bitmap.push(false);
+            }
+        }
+    }
+
+    fn finish(self) -> Self::Array {
+        StructArray {
+            fields: self.fields.unwrap_or_default().into(),
+            children: self.children.into_iter().map(|c| c.finish()).collect(),
+            len: self.bitmap.len(),
+            offset: 0,
+            bitmap: Arc::new(self.bitmap),
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Struct(self.fields.clone().unwrap_or_default())
+    }
+}
@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use bitvec::slice::BitSlice;
+use bitvec::vec::BitVec;
+
+use super::{Array, ArrayBuilder, ArrayIterator};
+use crate::{DataType, Timestamp};
+
+/// An [`Array`] of [`Timestamp`]s, optionally tagged with a timezone.
+///
+/// The timezone tag does not change the stored values, which are always microseconds since the
+/// UNIX epoch in UTC.
+#[derive(Clone)]
+pub struct TimestampArray {
+    /// The actual data of this array
+    data: Arc<[Timestamp]>,
+    /// The null bitmap of this array
+    bitmap: Arc<BitVec>,
+    /// Position of the first item of this array in the buffers
+    offset: usize,
+    /// Number of items of this array
+    len: usize,
+    /// Timezone tag of this array
+    tz: Option<Arc<str>>,
+}
+
+impl TimestampArray {
+    /// Timezone tag of this array
+    pub fn tz(&self) -> Option<&str> {
+        self.tz.as_deref()
+    }
+}
+
+impl Array for TimestampArray {
+    type RefItem<'a> = Timestamp;
+    type Builder = TimestampArrayBuilder;
+    type OwnedItem = Timestamp;
+
+    fn get(&self, idx: usize) -> Option<Self::RefItem<'_>> {
+        assert!(idx < self.len, "index out of bounds");
+        let idx = self.offset + idx;
+        if self.bitmap[idx] {
+            Some(self.data[idx])
+        } else {
+            None
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> ArrayIterator<'_, Self> {
+        ArrayIterator::new(self)
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Self {
+        assert!(offset + len <= self.len, "slice out of bounds");
+        Self {
+            data: self.data.clone(),
+            bitmap: self.bitmap.clone(),
+            offset: self.offset + offset,
+            len,
+            tz: self.tz.clone(),
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Timestamp {
+            tz: self.tz.as_deref().map(String::from),
+        }
+    }
+
+    fn null_bitmap(&self) -> &BitSlice {
+        &self.bitmap[self.offset..self.offset + self.len]
+    }
+}
+
+/// [`ArrayBuilder`] for [`TimestampArray`]
+pub struct TimestampArrayBuilder {
+    /// The actual data of this array
+    data: Vec<Timestamp>,
+    /// The null bitmap of this array
+    bitmap: BitVec,
+    /// Timezone tag of this array
+    tz: Option<Arc<str>>,
+}
+
+impl TimestampArrayBuilder {
+    /// Create a builder of timestamps tagged with timezone `tz`.
+    pub fn with_tz(capacity: usize, tz: Option<&str>) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            bitmap: BitVec::with_capacity(capacity),
+            tz: tz.map(Arc::from),
+        }
+    }
+}
+
+impl ArrayBuilder for TimestampArrayBuilder {
+    type Array = TimestampArray;
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self::with_tz(capacity, None)
+    }
+
+    fn with_type(data_type: &DataType, capacity: usize) -> Self {
+        match data_type {
+            DataType::Timestamp { tz } => Self::with_tz(capacity, tz.as_deref()),
+            other => panic!("cannot build timestamp array of {}", other),
+        }
+    }
+
+    fn push(&mut self, value: Option<Timestamp>) {
+        self.data.push(value.unwrap_or_default());
+        self.bitmap.push(value.is_some());
+    }
+
+    fn finish(self) -> Self::Array {
+        TimestampArray {
+            len: self.data.len(),
+            offset: 0,
+            data: self.data.into(),
+            bitmap: Arc::new(self.bitmap),
+            tz: self.tz,
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Timestamp {
+            tz: self.tz.as_deref().map(String::from),
+        }
+    }
+}
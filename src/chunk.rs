@@ -0,0 +1,263 @@
+use bitvec::vec::BitVec;
+use thiserror::Error;
+
+use crate::{ArrayImpl, DataType, ScalarRefImpl, Schema};
+
+/// Returned when the columns of a [`DataChunk`] do not match its schema.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DataChunkError {
+    #[error("Expect {0} columns, get {1}")]
+    ColumnCount(usize, usize),
+    #[error("Column {0} should be of type {1}, get {2}")]
+    ColumnType(String, DataType, DataType),
+    #[error("Column {0} should have {1} rows, get {2}")]
+    ColumnLength(String, usize, usize),
+    #[error("Column {0} is not nullable, but has a null in row {1}")]
+    UnexpectedNull(String, usize),
+    #[error("Visibility bitmap should have {0} rows, get {1}")]
+    VisibilityLength(usize, usize),
+}
+
+/// A reference to a column of a [`DataChunk`], either by position or by name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColumnRef {
+    Index(usize),
+    Name(String),
+}
+
+impl From<usize> for ColumnRef {
+    fn from(idx: usize) -> Self {
+        Self::Index(idx)
+    }
+}
+
+impl From<&str> for ColumnRef {
+    fn from(name: &str) -> Self {
+        Self::Name(name.to_string())
+    }
+}
+
+/// A batch of rows stored as one [`ArrayImpl`] per column, described by a [`Schema`].
+///
+/// All columns have the same number of rows. An optional visibility bitmap marks rows that are
+/// filtered out without copying the columns. Invisible rows are still stored, and expressions are
+/// evaluated on them as well.
+#[derive(Clone)]
+pub struct DataChunk {
+    schema: Schema,
+    columns: Vec<ArrayImpl>,
+    visibility: Option<BitVec>,
+    num_rows: usize,
+}
+
+impl DataChunk {
+    /// Create a chunk of `columns`, returning an error if they do not match `schema` or do not
+    /// have the same length.
+    pub fn new(schema: Schema, columns: Vec<ArrayImpl>) -> Result<Self, DataChunkError> {
+        if schema.len() != columns.len() {
+            return Err(DataChunkError::ColumnCount(schema.len(), columns.len()));
+        }
+        let num_rows = columns.first().map_or(0, ArrayImpl::len);
+        for (field, column) in schema.fields().iter().zip(&columns) {
+            let name = || field.name().to_string();
+            if &column.data_type() != field.data_type() {
+                return Err(DataChunkError::ColumnType(
+                    name(),
+                    field.data_type().clone(),
+                    column.data_type(),
+                ));
+            }
+            if column.len() != num_rows {
+                return Err(DataChunkError::ColumnLength(name(), num_rows, column.len()));
+            }
+            if !field.is_nullable() {
+                if let Some(row) = (0..num_rows).find(|&row| column.get(row).is_none()) {
+                    return Err(DataChunkError::UnexpectedNull(name(), row));
+                }
+            }
+        }
+        Ok(Self {
+            schema,
+            columns,
+            visibility: None,
+            num_rows,
+        })
+    }
+
+    /// Set the visibility bitmap, where a `false` bit hides the row.
+    pub fn with_visibility(self, visibility: BitVec) -> Result<Self, DataChunkError> {
+        if visibility.len() != self.num_rows {
+            return Err(DataChunkError::VisibilityLength(
+                self.num_rows,
+                visibility.len(),
+            ));
+        }
+        Ok(Self {
+            visibility: Some(visibility),
+            ..self
+        })
+    }
+
+    /// The schema of the chunk
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// All columns, including invisible rows
+    pub fn columns(&self) -> &[ArrayImpl] {
+        &self.columns
+    }
+
+    /// The visibility bitmap, if any
+    pub fn visibility(&self) -> Option<&BitVec> {
+        self.visibility.as_ref()
+    }
+
+    /// Number of rows, including invisible ones
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Number of visible rows
+    pub fn cardinality(&self) -> usize {
+        match &self.visibility {
+            Some(visibility) => visibility.count_ones(),
+            None => self.num_rows,
+        }
+    }
+
+    /// Whether the row at `idx` is visible.
+    pub fn is_visible(&self, idx: usize) -> bool {
+        self.visibility.as_ref().is_none_or(|v| v[idx])
+    }
+
+    /// Get the column referenced by `column`, or `None` if there is no such column.
+    pub fn column(&self, column: &ColumnRef) -> Option<&ArrayImpl> {
+        let idx = match column {
+            ColumnRef::Index(idx) => *idx,
+            ColumnRef::Name(name) => self.schema.index_of(name)?,
+        };
+        self.columns.get(idx)
+    }
+
+    /// Get the values of the row at `idx`, whether it is visible or not.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn row(&self, idx: usize) -> Vec<Option<ScalarRefImpl<'_>>> {
+        assert!(idx < self.num_rows, "index out of bounds");
+        self.columns.iter().map(|column| column.get(idx)).collect()
+    }
+
+    /// Get iterator of the visible rows
+    pub fn rows(&self) -> impl Iterator<Item = Vec<Option<ScalarRefImpl<'_>>>> + '_ {
+        (0..self.num_rows)
+            .filter(|&idx| self.is_visible(idx))
+            .map(|idx| self.row(idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitvec::prelude::*;
+
+    use super::*;
+    use crate::{
+        build_binary_expression, Array, BoolArray, ExpressionFunc, Field, I32Array, I64Array,
+        StringArray,
+    };
+
+    fn schema() -> Schema {
+        Schema::new(
+            ["id int64 not null", "name string", "score int32"]
+                .map(|field| field.parse::<Field>().unwrap())
+                .to_vec(),
+        )
+    }
+
+    fn columns() -> Vec<ArrayImpl> {
+        vec![
+            I64Array::from_slice(&[Some(1), Some(2), Some(3)]).into(),
+            StringArray::from_slice(&[Some("a"), None, Some("c")]).into(),
+            I32Array::from_slice(&[Some(5), Some(1), None]).into(),
+        ]
+    }
+
+    #[test]
+    fn test_data_chunk() {
+        let chunk = DataChunk::new(schema(), columns())
+            .unwrap()
+            .with_visibility(bitvec![1, 0, 1])
+            .unwrap();
+        assert_eq!(chunk.num_rows(), 3);
+        assert_eq!(chunk.cardinality(), 2);
+        assert_eq!(
+            chunk.row(1),
+            [
+                Some(ScalarRefImpl::Int64(2)),
+                None,
+                Some(ScalarRefImpl::Int32(1))
+            ]
+        );
+        let rows = chunk.rows().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1][1], Some(ScalarRefImpl::String("c")));
+        assert_eq!(
+            chunk.column(&"score".into()).unwrap().data_type(),
+            DataType::Int32
+        );
+        assert!(chunk.column(&"age".into()).is_none());
+        assert!(chunk.column(&3.into()).is_none());
+    }
+
+    #[test]
+    fn test_data_chunk_errors() {
+        let mut columns = columns();
+        columns.pop();
+        assert_eq!(
+            DataChunk::new(schema(), columns.clone()).err(),
+            Some(DataChunkError::ColumnCount(3, 2))
+        );
+
+        columns.push(I32Array::from_slice(&[Some(1)]).into());
+        assert_eq!(
+            DataChunk::new(schema(), columns.clone()).err(),
+            Some(DataChunkError::ColumnLength("score".to_string(), 3, 1))
+        );
+
+        columns[2] = I64Array::from_slice(&[None; 3]).into();
+        assert!(matches!(
+            DataChunk::new(schema(), columns.clone()),
+            Err(DataChunkError::ColumnType(..))
+        ));
+
+        columns[0] = I64Array::from_slice(&[Some(1), None, Some(3)]).into();
+        columns[2] = I32Array::from_slice(&[None; 3]).into();
+        assert_eq!(
+            DataChunk::new(schema(), columns).err(),
+            Some(DataChunkError::UnexpectedNull("id".to_string(), 1))
+        );
+
+        let chunk = DataChunk::new(schema(), self::columns()).unwrap();
+        assert!(chunk.with_visibility(bitvec![1]).is_err());
+    }
+
+    #[test]
+    fn test_eval_chunk() {
+        let chunk = DataChunk::new(schema(), columns()).unwrap();
+        let expr =
+            build_binary_expression(ExpressionFunc::CmpGe, &DataType::Int32, &DataType::Int64)
+                .unwrap();
+        let result: BoolArray = expr
+            .eval_chunk(&chunk, &["score".into(), 0.into()])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(true), Some(false), None]
+        );
+        assert!(expr.eval_chunk(&chunk, &["age".into(), 0.into()]).is_err());
+    }
+}
@@ -0,0 +1,445 @@
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::macros::for_all_variants;
+use crate::MAX_DECIMAL_PRECISION;
+
+/// Split `s` at the commas which are not enclosed in parentheses.
+fn split_params(s: &str) -> Vec<&str> {
+    let mut params = vec![];
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                params.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    params.push(s[start..].trim());
+    params
+}
+
+/// Returned by [`DataType::from_str`] if the name does not match any type.
+#[derive(Error, Debug)]
+#[error("Unknown data type: {0}")]
+pub struct ParseDataTypeError(String);
+
+/// Runtime type information of [`ArrayImpl`](crate::ArrayImpl) and
+/// [`ScalarImpl`](crate::ScalarImpl).
+///
+/// A [`DataType`] describes a type without holding any value, so that arrays and expressions can
+/// be built from type metadata alone. There is one variant for each variant of `ArrayImpl`, which
+/// also carries the parameters of the type, if any.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DataType {
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Bool,
+    String,
+    /// Byte string
+    Binary,
+    /// Fixed-point decimal with at most `precision` digits, `scale` of which are fractional.
+    Decimal {
+        precision: u8,
+        scale: u8,
+    },
+    /// Days since 1970-01-01
+    Date32,
+    /// Microseconds since midnight
+    Time64,
+    /// Microseconds since 1970-01-01 00:00:00 UTC, tagged with an optional timezone name.
+    Timestamp {
+        tz: Option<String>,
+    },
+    /// Months, days and microseconds
+    Interval,
+    /// Variable-length list of items of the inner type
+    List(Box<DataType>),
+    /// Named fields of possibly different types
+    Struct(Vec<Field>),
+}
+
+/// A named field of a [`DataType::Struct`] or a [`Schema`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Field {
+    name: String,
+    data_type: DataType,
+    nullable: bool,
+}
+
+impl Field {
+    /// Create a nullable field of `name` and `data_type`.
+    pub fn new(name: impl Into<String>, data_type: DataType) -> Self {
+        Self {
+            name: name.into(),
+            data_type,
+            nullable: true,
+        }
+    }
+
+    /// Set whether the field may contain nulls.
+    pub fn with_nullable(self, nullable: bool) -> Self {
+        Self { nullable, ..self }
+    }
+
+    /// Whether the field may contain nulls
+    pub fn is_nullable(&self) -> bool {
+        self.nullable
+    }
+
+    /// Name of the field
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Data type of the field
+    pub fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.data_type)?;
+        if !self.nullable {
+            f.write_str(" not null")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Field {
+    type Err = ParseDataTypeError;
+
+    /// Parse a field from its name and data type, optionally followed by `not null`, e.g.
+    /// `id int64 not null`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, data_type) = s
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| ParseDataTypeError(s.to_string()))?;
+        let lower = data_type.to_ascii_lowercase();
+        match lower.strip_suffix("not null") {
+            Some(rest) if rest.ends_with(' ') => {
+                Ok(Self::new(name, data_type[..rest.len()].parse()?).with_nullable(false))
+            }
+            _ => Ok(Self::new(name, data_type.parse()?)),
+        }
+    }
+}
+
+/// Names, types and nullability of the columns of a [`DataChunk`](crate::DataChunk).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Schema {
+    fields: Vec<Field>,
+}
+
+impl Schema {
+    /// Create a schema of `fields`.
+    pub fn new(fields: Vec<Field>) -> Self {
+        Self { fields }
+    }
+
+    /// The fields of the schema
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Get the field at position `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn field(&self, idx: usize) -> &Field {
+        &self.fields[idx]
+    }
+
+    /// Position of the first field of `name`, if any.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|field| field.name() == name)
+    }
+
+    /// Number of fields
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Indicates whether the schema has no fields
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+/// Write `fields` separated by commas.
+fn fmt_fields(f: &mut fmt::Formatter<'_>, fields: &[Field]) -> fmt::Result {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}", field)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_fields(f, &self.fields)
+    }
+}
+
+/// Implements [`DataTypeKind`] and dispatch functions for [`DataType`]
+macro_rules! impl_data_type_dispatch {
+    ([], $({$Abc:ident, $abc:ident, $AbcArray:ty, $AbcArrayBuilder:ty, $Owned:ty, $Ref:ty}),*) => {
+        /// The variant of a [`DataType`] without its parameters, e.g. [`DataTypeKind::Decimal`]
+        /// for `decimal(10, 2)`.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum DataTypeKind {
+            $(
+                $Abc,
+            )*
+        }
+
+        impl DataTypeKind {
+            /// Name of the kind, e.g. `decimal`
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(
+                        Self::$Abc => stringify!($abc),
+                    )*
+                }
+            }
+        }
+
+        impl DataType {
+            /// The variant of the data type without parameters
+            pub fn kind(&self) -> DataTypeKind {
+                match self {
+                    $(
+                        Self::$Abc { .. } => DataTypeKind::$Abc,
+                    )*
+                }
+            }
+
+            /// Name of the data type without parameters, e.g. `decimal` for `decimal(10, 2)`.
+            pub fn name(&self) -> &'static str {
+                self.kind().name()
+            }
+        }
+    };
+}
+
+for_all_variants! { impl_data_type_dispatch }
+
+impl fmt::Display for DataTypeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decimal { precision, scale } => write!(f, "decimal({}, {})", precision, scale),
+            Self::Timestamp { tz: Some(tz) } => write!(f, "timestamp({})", tz),
+            Self::List(value_type) => write!(f, "list({})", value_type),
+            Self::Struct(fields) => {
+                f.write_str("struct(")?;
+                fmt_fields(f, fields)?;
+                f.write_str(")")
+            }
+            other => f.write_str(other.name()),
+        }
+    }
+}
+
+impl FromStr for DataType {
+    type Err = ParseDataTypeError;
+
+    /// Parse a data type from its name, followed by the parameters in parentheses if any, e.g.
+    /// `int32`, `decimal(10, 2)`, `timestamp(UTC)`, `list(decimal(10, 2))` or
+    /// `struct(id int64, tags list(string))`. Names of types are case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseDataTypeError(s.to_string());
+        let s = s.trim();
+        let (name, params) = match s.split_once('(') {
+            Some((name, rest)) => {
+                let params = rest.strip_suffix(')').ok_or_else(err)?;
+                (name.trim(), split_params(params))
+            }
+            None => (s, vec![]),
+        };
+        let int_param = |p: &str| p.parse::<u8>().map_err(|_| err());
+        let data_type = match (name.to_ascii_lowercase().as_str(), params.as_slice()) {
+            ("int16", []) => Self::Int16,
+            ("int32", []) => Self::Int32,
+            ("int64", []) => Self::Int64,
+            ("float32", []) => Self::Float32,
+            ("float64", []) => Self::Float64,
+            ("bool", []) => Self::Bool,
+            ("string", []) => Self::String,
+            ("binary" | "bytea", []) => Self::Binary,
+            ("decimal", []) => Self::Decimal {
+                precision: MAX_DECIMAL_PRECISION,
+                scale: 0,
+            },
+            ("decimal", [precision]) => Self::Decimal {
+                precision: int_param(precision)?,
+                scale: 0,
+            },
+            ("decimal", [precision, scale]) => Self::Decimal {
+                precision: int_param(precision)?,
+                scale: int_param(scale)?,
+            },
+            ("date32", []) => Self::Date32,
+            ("time64", []) => Self::Time64,
+            ("timestamp", []) => Self::Timestamp { tz: None },
+            ("timestamp", [tz]) if !tz.is_empty() => Self::Timestamp {
+                tz: Some(tz.to_string()),
+            },
+            ("interval", []) => Self::Interval,
+            ("list", [value_type]) => Self::List(Box::new(value_type.parse()?)),
+            ("struct", [""]) => Self::Struct(vec![]),
+            ("struct", fields) => Self::Struct(
+                fields
+                    .iter()
+                    .map(|field| field.parse())
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => return Err(err()),
+        };
+        if let Self::Decimal { precision, scale } = data_type {
+            if precision == 0 || precision > MAX_DECIMAL_PRECISION || scale > precision {
+                return Err(err());
+            }
+        }
+        Ok(data_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Array, ArrayBuilderImpl, ArrayImpl, Decimal, I32Array, ScalarImpl, ScalarRefImpl};
+
+    fn all() -> Vec<DataType> {
+        vec![
+            DataType::Int16,
+            DataType::Int32,
+            DataType::Int64,
+            DataType::Float32,
+            DataType::Float64,
+            DataType::Bool,
+            DataType::String,
+            DataType::Binary,
+            DataType::Decimal {
+                precision: 10,
+                scale: 2,
+            },
+            DataType::Date32,
+            DataType::Time64,
+            DataType::Timestamp { tz: None },
+            DataType::Timestamp {
+                tz: Some(String::from("Asia/Shanghai")),
+            },
+            DataType::Interval,
+            DataType::List(Box::new(DataType::Int32)),
+            DataType::Struct(vec![]),
+            DataType::Struct(vec![
+                Field::new("id", DataType::Int64).with_nullable(false),
+                Field::new(
+                    "tags",
+                    DataType::List(Box::new(DataType::Struct(vec![Field::new(
+                        "name",
+                        DataType::String,
+                    )]))),
+                ),
+            ]),
+            DataType::List(Box::new(DataType::List(Box::new(DataType::Decimal {
+                precision: 10,
+                scale: 2,
+            })))),
+        ]
+    }
+
+    #[test]
+    fn test_display_from_str() {
+        for ty in &all() {
+            assert_eq!(&ty.to_string().parse::<DataType>().unwrap(), ty);
+        }
+        assert_eq!("Int32".parse::<DataType>().unwrap(), DataType::Int32);
+        assert_eq!(
+            "DECIMAL(5)".parse::<DataType>().unwrap(),
+            DataType::Decimal {
+                precision: 5,
+                scale: 0
+            }
+        );
+        assert!("int128".parse::<DataType>().is_err());
+        assert!("int32(1)".parse::<DataType>().is_err());
+        assert!("decimal(5, 6)".parse::<DataType>().is_err());
+        assert!("decimal(39, 0)".parse::<DataType>().is_err());
+        assert!("decimal(10, 2".parse::<DataType>().is_err());
+        assert!("timestamp()".parse::<DataType>().is_err());
+        assert!("list(int32, int32)".parse::<DataType>().is_err());
+        assert!("list(decimal(39, 0))".parse::<DataType>().is_err());
+        assert!("struct(int32)".parse::<DataType>().is_err());
+        assert!("struct(a int32,)".parse::<DataType>().is_err());
+        assert!("struct(a int32 not)".parse::<DataType>().is_err());
+        assert!("struct(a not null)".parse::<DataType>().is_err());
+        assert_eq!(
+            "id Int64 NOT NULL".parse::<Field>().unwrap(),
+            Field::new("id", DataType::Int64).with_nullable(false)
+        );
+    }
+
+    #[test]
+    fn test_build_from_data_type() {
+        for ty in &all() {
+            let builder = ArrayBuilderImpl::new(ty, 0);
+            assert_eq!(&builder.data_type(), ty);
+            assert_eq!(&builder.finish().data_type(), ty);
+        }
+
+        let mut builder = ArrayBuilderImpl::new(&DataType::Int64, 2);
+        builder.push(Some(ScalarRefImpl::Int64(1)));
+        builder.push(None);
+        let array = builder.finish();
+        assert_eq!(array.len(), 2);
+        assert_eq!(array.get(0), Some(ScalarRefImpl::Int64(1)));
+    }
+
+    #[test]
+    fn test_data_type_of_values() {
+        let array: ArrayImpl = I32Array::from_slice(&[Some(1)]).into();
+        assert_eq!(array.data_type(), DataType::Int32);
+        assert_eq!(array.get(0).unwrap().data_type(), DataType::Int32);
+        assert_eq!(
+            ScalarImpl::from("x".to_string()).data_type(),
+            DataType::String
+        );
+        assert_eq!(
+            ScalarImpl::from("1.50".parse::<Decimal>().unwrap()).data_type(),
+            DataType::Decimal {
+                precision: MAX_DECIMAL_PRECISION,
+                scale: 2
+            }
+        );
+
+        let decimal_type: DataType = "decimal(10, 2)".parse().unwrap();
+        assert_eq!(decimal_type.kind(), DataTypeKind::Decimal);
+        let err = crate::DecimalArray::try_from(array).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Type mispatch on conversion: expected decimal, get int32"
+        );
+    }
+}
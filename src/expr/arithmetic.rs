@@ -0,0 +1,196 @@
+use std::marker::PhantomData;
+
+use anyhow::Result;
+use thiserror::Error;
+
+use super::cmp::CmpCast;
+use super::vectorize::{TryBinaryExprFunc, TryUnaryExprFunc};
+use crate::Array;
+
+/// Errors of arithmetic functions
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ArithmeticError {
+    #[error("{0} out of range")]
+    Overflow(&'static str),
+    #[error("division by zero")]
+    DivisionByZero,
+}
+
+/// Numeric types with checked arithmetic, which returns an error instead of wrapping, panicking
+/// or producing an infinity.
+pub trait Numeric: Copy {
+    fn add(self, rhs: Self) -> Result<Self, ArithmeticError>;
+    fn sub(self, rhs: Self) -> Result<Self, ArithmeticError>;
+    fn mul(self, rhs: Self) -> Result<Self, ArithmeticError>;
+    fn div(self, rhs: Self) -> Result<Self, ArithmeticError>;
+    fn rem(self, rhs: Self) -> Result<Self, ArithmeticError>;
+    fn neg(self) -> Result<Self, ArithmeticError>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty: $name:literal),*) => {
+        $(
+            impl Numeric for $t {
+                fn add(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                    self.checked_add(rhs).ok_or(ArithmeticError::Overflow($name))
+                }
+
+                fn sub(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                    self.checked_sub(rhs).ok_or(ArithmeticError::Overflow($name))
+                }
+
+                fn mul(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                    self.checked_mul(rhs).ok_or(ArithmeticError::Overflow($name))
+                }
+
+                fn div(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                    if rhs == 0 {
+                        return Err(ArithmeticError::DivisionByZero);
+                    }
+                    self.checked_div(rhs).ok_or(ArithmeticError::Overflow($name))
+                }
+
+                /// The remainder of `MIN % -1` is 0, even though `MIN / -1` overflows.
+                fn rem(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                    if rhs == 0 {
+                        return Err(ArithmeticError::DivisionByZero);
+                    }
+                    Ok(self.wrapping_rem(rhs))
+                }
+
+                fn neg(self) -> Result<Self, ArithmeticError> {
+                    self.checked_neg().ok_or(ArithmeticError::Overflow($name))
+                }
+            }
+        )*
+    };
+}
+
+impl_integer! { i16: "smallint", i32: "integer", i64: "bigint" }
+
+macro_rules! impl_float {
+    ($($t:ty: $name:literal),*) => {
+        $(
+            impl Numeric for $t {
+                fn add(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                    check_float(self + rhs, self, rhs, $name)
+                }
+
+                fn sub(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                    check_float(self - rhs, self, rhs, $name)
+                }
+
+                fn mul(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                    check_float(self * rhs, self, rhs, $name)
+                }
+
+                fn div(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                    if rhs == 0.0 {
+                        return Err(ArithmeticError::DivisionByZero);
+                    }
+                    check_float(self / rhs, self, rhs, $name)
+                }
+
+                fn rem(self, rhs: Self) -> Result<Self, ArithmeticError> {
+                    if rhs == 0.0 {
+                        return Err(ArithmeticError::DivisionByZero);
+                    }
+                    Ok(self % rhs)
+                }
+
+                fn neg(self) -> Result<Self, ArithmeticError> {
+                    Ok(-self)
+                }
+            }
+        )*
+    };
+}
+
+impl_float! { f32: "real", f64: "double precision" }
+
+/// Returns an error if `result` is infinite while both operands are finite.
+fn check_float<T: Into<f64> + Copy>(
+    result: T,
+    lhs: T,
+    rhs: T,
+    name: &'static str,
+) -> Result<T, ArithmeticError> {
+    if result.into().is_infinite() && lhs.into().is_finite() && rhs.into().is_finite() {
+        return Err(ArithmeticError::Overflow(name));
+    }
+    Ok(result)
+}
+
+macro_rules! impl_arithmetic_func {
+    ($($Func:ident, $op:ident, $doc:literal);*) => {
+        $(
+            #[doc = concat!("Returns `", $doc, "`. Note that `i1` and `i2` could be different types. ")]
+            /// This function will automatically cast them into `C` type, which is also the type of
+            /// the result.
+            ///
+            /// * `I1`: left input type.
+            /// * `I2`: right input type.
+            /// * `C`: cast type.
+            pub struct $Func<I1: Array, I2: Array, C: Array>(pub PhantomData<(I1, I2, C)>);
+
+            impl<I1: Array, I2: Array, C: Array> TryBinaryExprFunc<I1, I2, C> for $Func<I1, I2, C>
+            where
+                for<'a> I1::RefItem<'a>: CmpCast<C::OwnedItem>,
+                for<'a> I2::RefItem<'a>: CmpCast<C::OwnedItem>,
+                C::OwnedItem: Numeric,
+            {
+                fn eval<'a>(&self, i1: I1::RefItem<'a>, i2: I2::RefItem<'a>) -> Result<C::OwnedItem> {
+                    Ok(i1.cmp_cast().$op(i2.cmp_cast())?)
+                }
+            }
+        )*
+    };
+}
+
+impl_arithmetic_func! {
+    ExprAdd, add, "i1 + i2";
+    ExprSubtract, sub, "i1 - i2";
+    ExprMultiply, mul, "i1 * i2";
+    ExprDivide, div, "i1 / i2";
+    ExprModulo, rem, "i1 % i2"
+}
+
+/// Returns `-i1`.
+pub struct ExprNegate<I1: Array>(pub PhantomData<I1>);
+
+impl<I1: Array> TryUnaryExprFunc<I1, I1> for ExprNegate<I1>
+where
+    for<'a> I1::RefItem<'a>: Into<I1::OwnedItem>,
+    I1::OwnedItem: Numeric,
+{
+    fn eval<'a>(&self, i1: I1::RefItem<'a>) -> Result<I1::OwnedItem> {
+        Ok(i1.into().neg()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numeric() {
+        assert_eq!(i16::MAX.add(1), Err(ArithmeticError::Overflow("smallint")));
+        assert_eq!(i32::MIN.sub(1), Err(ArithmeticError::Overflow("integer")));
+        assert_eq!(i64::MAX.mul(2), Err(ArithmeticError::Overflow("bigint")));
+        assert_eq!(i32::MIN.div(-1), Err(ArithmeticError::Overflow("integer")));
+        assert_eq!(i32::MIN.rem(-1), Ok(0));
+        assert_eq!((-7i32).rem(2), Ok(-1));
+        assert_eq!(1i32.div(0), Err(ArithmeticError::DivisionByZero));
+        assert_eq!(1i64.rem(0), Err(ArithmeticError::DivisionByZero));
+        assert_eq!(i16::MIN.neg(), Err(ArithmeticError::Overflow("smallint")));
+
+        assert_eq!(1.5f64.div(0.5), Ok(3.0));
+        assert_eq!(1.0f32.div(0.0), Err(ArithmeticError::DivisionByZero));
+        assert_eq!(
+            f64::MAX.mul(2.0),
+            Err(ArithmeticError::Overflow("double precision"))
+        );
+        assert_eq!(f32::INFINITY.add(1.0), Ok(f32::INFINITY));
+        assert!(f64::NAN.sub(1.0).unwrap().is_nan());
+    }
+}
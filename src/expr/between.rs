@@ -0,0 +1,301 @@
+use std::cmp::Ordering;
+
+use anyhow::{anyhow, Result};
+
+use super::cmp::{comparison_type, is_comparable};
+use super::{cast, Datum, Expression, TotalOrd};
+use crate::{ArrayBuilder, ArrayImpl, BoolArray, BoolArrayBuilder, ScalarRefImpl};
+
+/// `i1 BETWEEN i2 AND i3`, i.e. `i2 <= i1 AND i1 <= i3`, or its variants.
+///
+/// The bounds are inclusive by default, and either of them can be made exclusive. With
+/// `SYMMETRIC`, the bounds are swapped if `i2 > i3`. Values are compared by [`TotalOrd`], so that
+/// the predicate works on all types, and `NaN` is greater than any other float.
+///
+/// As with `AND`, the result is `NULL` if `i1` is `NULL`, or if a bound is `NULL` and the other
+/// bound does not exclude `i1`, e.g. `5 BETWEEN NULL AND 3` is `FALSE`.
+pub struct BetweenExpression {
+    low_inclusive: bool,
+    high_inclusive: bool,
+    symmetric: bool,
+    negated: bool,
+}
+
+/// `a AND b` of three-valued booleans
+fn and(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    }
+}
+
+/// `a OR b` of three-valued booleans
+fn or(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    and(a.map(|a| !a), b.map(|b| !b)).map(|result| !result)
+}
+
+impl BetweenExpression {
+    /// `i1 BETWEEN i2 AND i3`
+    pub fn between() -> Self {
+        Self {
+            low_inclusive: true,
+            high_inclusive: true,
+            symmetric: false,
+            negated: false,
+        }
+    }
+
+    /// `i1 NOT BETWEEN i2 AND i3`, i.e. `i1 < i2 OR i1 > i3`
+    pub fn not_between() -> Self {
+        Self {
+            negated: true,
+            ..Self::between()
+        }
+    }
+
+    /// `BETWEEN SYMMETRIC`, i.e. `(i1 BETWEEN i2 AND i3) OR (i1 BETWEEN i3 AND i2)`
+    pub fn symmetric(mut self) -> Self {
+        self.symmetric = true;
+        self
+    }
+
+    /// Set if `i1` is in range when it is equal to the lower or the upper bound, e.g.
+    /// `with_bounds(true, false)` for `i2 <= i1 AND i1 < i3`.
+    pub fn with_bounds(mut self, low_inclusive: bool, high_inclusive: bool) -> Self {
+        self.low_inclusive = low_inclusive;
+        self.high_inclusive = high_inclusive;
+        self
+    }
+
+    fn in_range(
+        &self,
+        value: ScalarRefImpl<'_>,
+        low: Option<ScalarRefImpl<'_>>,
+        high: Option<ScalarRefImpl<'_>>,
+    ) -> Option<bool> {
+        let above = low.map(|low| match value.total_order(&low) {
+            Ordering::Greater => true,
+            Ordering::Equal => self.low_inclusive,
+            Ordering::Less => false,
+        });
+        let below = high.map(|high| match value.total_order(&high) {
+            Ordering::Less => true,
+            Ordering::Equal => self.high_inclusive,
+            Ordering::Greater => false,
+        });
+        and(above, below)
+    }
+
+    /// Evaluate the predicate on three inputs, of which some may be constants. Inputs of different
+    /// types are cast into the first of their types that the others can be implicitly cast into.
+    pub fn eval_batch(&self, i1: &Datum, i2: &Datum, i3: &Datum) -> Result<BoolArray> {
+        let len = i1.num_rows().or(i2.num_rows()).or(i3.num_rows());
+        if [i1, i2, i3]
+            .iter()
+            .any(|d| d.num_rows().is_some_and(|l| Some(l) != len))
+        {
+            return Err(anyhow!("Inputs have different lengths"));
+        }
+        let len = len.unwrap_or(1);
+
+        let types = [i1, i2, i3].map(Datum::data_type);
+        let Some(to) = comparison_type(&types) else {
+            return Err(anyhow!(
+                "BETWEEN cannot compare {}, {} and {}",
+                types[0],
+                types[1],
+                types[2]
+            ));
+        };
+        let cast_datum = |d: &Datum| -> Result<Datum> {
+            if is_comparable(&d.data_type(), to) {
+                return Ok(d.clone());
+            }
+            let array = cast(d.as_array(), to)?;
+            Ok(if d.is_scalar() {
+                Datum::scalar(array.get(0), to)
+            } else {
+                Datum::array(array)
+            })
+        };
+        let data = [cast_datum(i1)?, cast_datum(i2)?, cast_datum(i3)?];
+        let [i1, i2, i3] = &data;
+
+        let mut builder = BoolArrayBuilder::with_capacity(len);
+        for row in 0..len {
+            let result = i1.get(row).and_then(|value| {
+                let (low, high) = (i2.get(row), i3.get(row));
+                let result = self.in_range(value, low, high);
+                if self.symmetric {
+                    or(result, self.in_range(value, high, low))
+                } else {
+                    result
+                }
+            });
+            builder.push(result.map(|result| result != self.negated));
+        }
+        Ok(builder.finish())
+    }
+}
+
+impl Expression for BetweenExpression {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        match data {
+            [i1, i2, i3] => Ok(self
+                .eval_batch(
+                    &Datum::array((*i1).clone()),
+                    &Datum::array((*i2).clone()),
+                    &Datum::array((*i3).clone()),
+                )?
+                .into()),
+            _ => Err(anyhow!("Except 3 input(s) for BetweenExpression")),
+        }
+    }
+
+    fn eval_datums(&self, data: &[Datum]) -> Result<ArrayImpl> {
+        match data {
+            [i1, i2, i3] => Ok(self.eval_batch(i1, i2, i3)?.into()),
+            _ => Err(anyhow!("Except 3 input(s) for BetweenExpression")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Array, DataType, F64Array, I16Array, I32Array, I64Array, ListArray, ListValue, StringArray,
+    };
+
+    fn eval(expr: &BetweenExpression, data: [Datum; 3]) -> Vec<Option<bool>> {
+        let [i1, i2, i3] = &data;
+        expr.eval_batch(i1, i2, i3).unwrap().iter().collect()
+    }
+
+    fn int(v: Option<i32>) -> Datum {
+        Datum::scalar(v.map(ScalarRefImpl::Int32), &DataType::Int32)
+    }
+
+    #[test]
+    fn test_between() {
+        let values = || {
+            Datum::array(I32Array::from_slice(&[Some(0), Some(1), Some(5), Some(10), None]).into())
+        };
+        let result = eval(
+            &BetweenExpression::between(),
+            [values(), int(Some(1)), int(Some(10))],
+        );
+        assert_eq!(
+            result,
+            [Some(false), Some(true), Some(true), Some(true), None]
+        );
+        let result = eval(
+            &BetweenExpression::not_between(),
+            [values(), int(Some(1)), int(Some(10))],
+        );
+        assert_eq!(
+            result,
+            [Some(true), Some(false), Some(false), Some(false), None]
+        );
+        let result = eval(
+            &BetweenExpression::between().with_bounds(false, true),
+            [values(), int(Some(1)), int(Some(10))],
+        );
+        assert_eq!(
+            result,
+            [Some(false), Some(false), Some(true), Some(true), None]
+        );
+        let result = eval(
+            &BetweenExpression::between().with_bounds(true, false),
+            [values(), int(Some(1)), int(Some(10))],
+        );
+        assert_eq!(
+            result,
+            [Some(false), Some(true), Some(true), Some(false), None]
+        );
+
+        // reversed bounds only match with SYMMETRIC
+        let result = eval(
+            &BetweenExpression::between(),
+            [values(), int(Some(10)), int(Some(1))],
+        );
+        assert_eq!(
+            result,
+            [Some(false), Some(false), Some(false), Some(false), None]
+        );
+        let result = eval(
+            &BetweenExpression::between().symmetric(),
+            [values(), int(Some(10)), int(Some(1))],
+        );
+        assert_eq!(
+            result,
+            [Some(false), Some(true), Some(true), Some(true), None]
+        );
+
+        // a `NULL` bound is only ignored if the other bound excludes the value
+        let result = eval(
+            &BetweenExpression::between(),
+            [values(), int(None), int(Some(1))],
+        );
+        assert_eq!(result, [None, None, Some(false), Some(false), None]);
+        let result = eval(
+            &BetweenExpression::not_between().symmetric(),
+            [values(), int(Some(1)), int(None)],
+        );
+        assert_eq!(result, [None, None, None, None, None]);
+    }
+
+    #[test]
+    fn test_between_types() {
+        // inputs are cast into a common type
+        let result = eval(
+            &BetweenExpression::between(),
+            [
+                Datum::array(I16Array::from_slice(&[Some(1), Some(3)]).into()),
+                Datum::array(I64Array::from_slice(&[Some(0), Some(0)]).into()),
+                Datum::scalar(Some(ScalarRefImpl::Float64(2.5)), &DataType::Float64),
+            ],
+        );
+        assert_eq!(result, [Some(true), Some(false)]);
+        let strings = Datum::array(StringArray::from_slice(&[Some("a")]).into());
+        assert!(BetweenExpression::between()
+            .eval_batch(&strings, &int(Some(1)), &int(Some(2)))
+            .is_err());
+        assert!(BetweenExpression::between()
+            .eval_batch(&int(Some(1)), &values_of_len(2), &values_of_len(3))
+            .is_err());
+
+        let float = |v: f64| Datum::scalar(Some(ScalarRefImpl::Float64(v)), &DataType::Float64);
+        let floats =
+            Datum::array(F64Array::from_slice(&[Some(f64::NAN), Some(f64::INFINITY)]).into());
+        let result = eval(
+            &BetweenExpression::between(),
+            [floats, float(0.0), float(f64::NAN)],
+        );
+        assert_eq!(result, [Some(true), Some(true)]);
+
+        let list = |items: &[Option<i32>]| ListValue::new(I32Array::from_slice(items).into());
+        let list_type = DataType::List(Box::new(DataType::Int32));
+        let lists = [list(&[Some(1)]), list(&[Some(1), None]), list(&[Some(2)])];
+        let lists = ListArray::from_slice(&[
+            Some(lists[0].as_list_ref()),
+            Some(lists[1].as_list_ref()),
+            Some(lists[2].as_list_ref()),
+        ]);
+        let (low, high) = (list(&[Some(1), Some(5)]), list(&[Some(2)]));
+        let result = eval(
+            &BetweenExpression::between(),
+            [
+                Datum::array(lists.into()),
+                Datum::scalar(Some(ScalarRefImpl::List(low.as_list_ref())), &list_type),
+                Datum::scalar(Some(ScalarRefImpl::List(high.as_list_ref())), &list_type),
+            ],
+        );
+        assert_eq!(result, [Some(false), Some(true), Some(true)]);
+    }
+
+    fn values_of_len(len: usize) -> Datum {
+        Datum::array(I32Array::from_slice(&vec![Some(1); len]).into())
+    }
+}
@@ -0,0 +1,306 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use thiserror::Error;
+
+use crate::{BinaryArray, I32Array, StringArray};
+
+use super::vectorize::{BinaryExprFunc, TryBinaryExprFunc, TryTernaryExprFunc, UnaryExprFunc};
+
+/// Returned when a string cannot be decoded into bytes, or does not name a [`BinaryFormat`].
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("Unknown binary format: {0}")]
+    UnknownFormat(String),
+    #[error("Invalid {0} digit: {1:?}")]
+    InvalidDigit(&'static str, char),
+    #[error("Unexpected end of {0} data")]
+    UnexpectedEnd(&'static str),
+}
+
+/// Textual representation of byte strings, which is used by `encode` and `decode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryFormat {
+    /// Two lowercase hex digits per byte
+    Hex,
+    /// Base64 with the standard alphabet and padding
+    Base64,
+}
+
+impl FromStr for BinaryFormat {
+    type Err = DecodeError;
+
+    /// Parse a format name, which is case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "hex" => Ok(Self::Hex),
+            "base64" => Ok(Self::Base64),
+            _ => Err(DecodeError::UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+const BASE64_DIGITS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Number of bytes in `bytes`.
+pub fn length(bytes: &[u8]) -> i32 {
+    bytes.len() as i32
+}
+
+/// The part of `bytes` starting at the 1-based position `start` with at most `count` bytes, or
+/// up to the end if `count` is `None`. Positions out of the range of `bytes` are ignored, so that
+/// `substr(b"abc", 0, Some(2))` is `b"a"`, as in Postgres.
+///
+/// # Panics
+///
+/// Panics if `count` is negative.
+pub fn substr(bytes: &[u8], start: i32, count: Option<i32>) -> &[u8] {
+    let start = start as i64;
+    let end = match count {
+        Some(count) => {
+            assert!(count >= 0, "negative substring length not allowed");
+            start + count as i64
+        }
+        None => i64::MAX,
+    };
+    let clamp = |pos: i64| (pos.max(1) - 1).min(bytes.len() as i64) as usize;
+    let (start, end) = (clamp(start), clamp(end));
+    &bytes[start..end.max(start)]
+}
+
+/// Encode `bytes` as text in the given format.
+pub fn encode(format: BinaryFormat, bytes: &[u8]) -> String {
+    match format {
+        BinaryFormat::Hex => {
+            let mut s = String::with_capacity(bytes.len() * 2);
+            for b in bytes {
+                s.push(HEX_DIGITS[(b >> 4) as usize] as char);
+                s.push(HEX_DIGITS[(b & 0xf) as usize] as char);
+            }
+            s
+        }
+        BinaryFormat::Base64 => {
+            let mut s = String::with_capacity(bytes.len().div_ceil(3) * 4);
+            for chunk in bytes.chunks(3) {
+                let n = chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+                for i in 0..4 {
+                    if i <= chunk.len() {
+                        s.push(BASE64_DIGITS[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+                    } else {
+                        s.push('=');
+                    }
+                }
+            }
+            s
+        }
+    }
+}
+
+/// Decode text in the given format into bytes. Whitespace between digits is ignored.
+pub fn decode(format: BinaryFormat, s: &str) -> Result<Vec<u8>, DecodeError> {
+    let mut digits = s.chars().filter(|c| !c.is_ascii_whitespace());
+    match format {
+        BinaryFormat::Hex => {
+            let mut bytes = Vec::with_capacity(s.len() / 2);
+            let digit = |c: char| {
+                c.to_digit(16)
+                    .map(|d| d as u8)
+                    .ok_or(DecodeError::InvalidDigit("hex", c))
+            };
+            while let Some(hi) = digits.next() {
+                let lo = digits.next().ok_or(DecodeError::UnexpectedEnd("hex"))?;
+                bytes.push(digit(hi)? << 4 | digit(lo)?);
+            }
+            Ok(bytes)
+        }
+        BinaryFormat::Base64 => {
+            let mut bytes = Vec::with_capacity(s.len() / 4 * 3);
+            let (mut n, mut bits) = (0u32, 0);
+            let (mut len, mut padding) = (0, 0);
+            for c in digits {
+                if c == '=' {
+                    padding += 1;
+                    continue;
+                }
+                let d = match BASE64_DIGITS.iter().position(|&d| d as char == c) {
+                    Some(d) if padding == 0 => d as u32,
+                    _ => return Err(DecodeError::InvalidDigit("base64", c)),
+                };
+                len += 1;
+                n = n << 6 | d;
+                bits += 6;
+                if bits >= 8 {
+                    bits -= 8;
+                    bytes.push((n >> bits) as u8);
+                    n &= (1 << bits) - 1;
+                }
+            }
+            // the digits and padding of the last group must add up to 4
+            if len % 4 == 1 || padding > 2 || (len + padding) % 4 != 0 {
+                return Err(DecodeError::UnexpectedEnd("base64"));
+            }
+            Ok(bytes)
+        }
+    }
+}
+
+/// Returns `length(i1)`, i.e. the number of bytes of `i1`.
+pub struct ExprBinaryLength;
+
+impl UnaryExprFunc<BinaryArray, I32Array> for ExprBinaryLength {
+    fn eval(&self, i1: &[u8]) -> i32 {
+        length(i1)
+    }
+}
+
+/// Returns `substr(i1, i2)`, i.e. the bytes of `i1` from the 1-based position `i2` to the end,
+/// or `substr(i1, i2, i3)` with at most `i3` bytes. Fails if `i3` is negative.
+pub struct ExprSubstr;
+
+impl BinaryExprFunc<BinaryArray, I32Array, BinaryArray> for ExprSubstr {
+    fn eval(&self, i1: &[u8], i2: i32) -> Vec<u8> {
+        substr(i1, i2, None).to_vec()
+    }
+}
+
+impl TryTernaryExprFunc<BinaryArray, I32Array, I32Array, BinaryArray> for ExprSubstr {
+    fn eval(&self, i1: &[u8], i2: i32, i3: i32) -> Result<Vec<u8>> {
+        if i3 < 0 {
+            bail!("negative substring length not allowed");
+        }
+        Ok(substr(i1, i2, Some(i3)).to_vec())
+    }
+}
+
+/// Returns `encode(i1, i2)`, i.e. the bytes `i1` as text in the [`BinaryFormat`] named by `i2`.
+/// Fails if `i2` is not a valid format.
+pub struct ExprEncode;
+
+impl TryBinaryExprFunc<BinaryArray, StringArray, StringArray> for ExprEncode {
+    fn eval(&self, i1: &[u8], i2: &str) -> Result<String> {
+        Ok(encode(i2.parse()?, i1))
+    }
+}
+
+/// Returns `decode(i1, i2)`, i.e. the bytes represented by the text `i1` in the [`BinaryFormat`]
+/// named by `i2`. Fails if `i2` is not a valid format, or `i1` is not valid in that format.
+pub struct ExprDecode;
+
+impl TryBinaryExprFunc<StringArray, StringArray, BinaryArray> for ExprDecode {
+    fn eval(&self, i1: &str, i2: &str) -> Result<Vec<u8>> {
+        Ok(decode(i2.parse()?, i1)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::vectorize::{
+        ErrorPolicy, RowErrors, TryBinaryExpression, TryTernaryExpression, UnaryExpression,
+    };
+    use crate::{Array, ArrayImpl, Expression};
+
+    #[test]
+    fn test_substr() {
+        assert_eq!(substr(b"abcdef", 2, Some(3)), b"bcd");
+        assert_eq!(substr(b"abcdef", 0, Some(2)), b"a");
+        assert_eq!(substr(b"abcdef", -5, Some(2)), b"");
+        assert_eq!(substr(b"abcdef", 4, None), b"def");
+        assert_eq!(substr(b"abcdef", 10, None), b"");
+        assert_eq!(substr(b"abcdef", 3, Some(0)), b"");
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let cases: &[(&[u8], &str, &str)] = &[
+            (b"", "", ""),
+            (b"f", "66", "Zg=="),
+            (b"fo", "666f", "Zm8="),
+            (b"foo", "666f6f", "Zm9v"),
+            (b"foob", "666f6f62", "Zm9vYg=="),
+            (&[0x00, 0xff, 0x10], "00ff10", "AP8Q"),
+        ];
+        for &(bytes, hex, base64) in cases {
+            assert_eq!(encode(BinaryFormat::Hex, bytes), hex);
+            assert_eq!(encode(BinaryFormat::Base64, bytes), base64);
+            assert_eq!(decode(BinaryFormat::Hex, hex).unwrap(), bytes);
+            assert_eq!(decode(BinaryFormat::Base64, base64).unwrap(), bytes);
+        }
+        assert_eq!(decode(BinaryFormat::Hex, "DE AD").unwrap(), [0xde, 0xad]);
+        assert_eq!(
+            decode(BinaryFormat::Hex, "abc"),
+            Err(DecodeError::UnexpectedEnd("hex"))
+        );
+        assert_eq!(
+            decode(BinaryFormat::Hex, "zz"),
+            Err(DecodeError::InvalidDigit("hex", 'z'))
+        );
+        assert!(decode(BinaryFormat::Base64, "Zm9").is_err());
+        assert!(decode(BinaryFormat::Base64, "Z===").is_err());
+        assert!(decode(BinaryFormat::Base64, "Zg==Zg==").is_err());
+        assert!("base32".parse::<BinaryFormat>().is_err());
+    }
+
+    #[test]
+    fn test_binary_length_substr() {
+        let array: ArrayImpl =
+            BinaryArray::from_slice(&[Some(&b"abc"[..]), Some(b""), None, Some(b"xyz")]).into();
+        let result: I32Array = UnaryExpression::new(ExprBinaryLength)
+            .eval_expr(&[&array])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(3), Some(0), None, Some(3)]
+        );
+
+        let expr = TryTernaryExpression::new(ExprSubstr);
+        let start: ArrayImpl = I32Array::from_slice(&[Some(2), Some(1), Some(1), None]).into();
+        let result: BinaryArray = expr
+            .eval_expr(&[&array, &start, &I32Array::from_slice(&[Some(1); 4]).into()])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(&b"b"[..]), Some(b""), None, None]
+        );
+
+        let count: ArrayImpl = I32Array::from_slice(&[Some(1), Some(-1), Some(1), Some(1)]).into();
+        let error = expr.eval_expr(&[&array, &start, &count]).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "negative substring length not allowed in row 1 with inputs ([], 1, -1)"
+        );
+    }
+
+    #[test]
+    fn test_error_policy() {
+        let errors = RowErrors::new();
+        let expr = TryBinaryExpression::new(ExprDecode)
+            .with_error_policy(ErrorPolicy::Null(errors.clone()));
+        let result: BinaryArray = expr
+            .eval_expr(&[
+                &StringArray::from_slice(&[Some("ff"), Some("zz"), None, Some("00")]).into(),
+                &StringArray::from_slice(&[Some("hex"), Some("hex"), Some("hex"), Some("b32")])
+                    .into(),
+            ])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(&[0xff][..]), None, None, None]
+        );
+        let errors = errors.take();
+        assert_eq!(errors.iter().map(|e| e.row).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(errors[1].inputs, ["\"00\"", "\"b32\""]);
+    }
+}
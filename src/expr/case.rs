@@ -0,0 +1,368 @@
+use anyhow::{anyhow, Result};
+use thiserror::Error;
+
+use super::{can_cast, Cast, CastContext, ExprNode, Expression};
+use crate::{Array, ArrayBuilderImpl, ArrayImpl, BoolArray, DataType};
+
+/// Returned when a [`CaseExpression`] cannot be built.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CaseError {
+    #[error("CASE needs at least one branch")]
+    NoBranch,
+    #[error("CASE condition should be of type bool, get {0}")]
+    NotBool(DataType),
+    #[error("CASE types {0:?} cannot be matched")]
+    NoCommonType(Vec<DataType>),
+}
+
+enum Branch {
+    /// Takes the rows where `condition` is `TRUE`, with the result of `value`.
+    When {
+        condition: ExprNode,
+        value: ExprNode,
+    },
+    /// Takes the rows where `value` is not `NULL`.
+    NotNull(ExprNode),
+}
+
+/// `CASE WHEN c1 THEN v1 WHEN c2 THEN v2 ... ELSE e END`, i.e. the value of the first branch whose
+/// condition is `TRUE`, or the `ELSE` value if there is none, or `NULL` if there is no `ELSE`.
+///
+/// Branches are evaluated lazily. The condition of a branch is only evaluated on the rows that no
+/// earlier branch has taken, and its value only on the rows that it takes, so that an expensive
+/// value is not computed for every row, and a value like `a / b` guarded by `b <> 0` does not fail.
+pub struct CaseExpression {
+    branches: Vec<Branch>,
+    else_value: Option<Box<ExprNode>>,
+    return_type: DataType,
+}
+
+impl CaseExpression {
+    /// Create a `CASE` of `(condition, value)` branches. The values are cast into a common type,
+    /// which is the first of their types that all others can be implicitly cast into.
+    pub fn new(
+        branches: Vec<(ExprNode, ExprNode)>,
+        else_value: Option<ExprNode>,
+    ) -> Result<Self, CaseError> {
+        if branches.is_empty() {
+            return Err(CaseError::NoBranch);
+        }
+        if let Some((condition, _)) = branches
+            .iter()
+            .find(|(condition, _)| condition.return_type() != &DataType::Bool)
+        {
+            return Err(CaseError::NotBool(condition.return_type().clone()));
+        }
+        let values = branches.iter().map(|(_, value)| value).chain(&else_value);
+        let return_type = common_type(values.map(ExprNode::return_type).collect())?;
+        let branches = branches
+            .into_iter()
+            .map(|(condition, value)| Branch::When {
+                condition,
+                value: cast_node(value, &return_type),
+            })
+            .collect();
+        Ok(Self {
+            branches,
+            else_value: else_value.map(|value| Box::new(cast_node(value, &return_type))),
+            return_type,
+        })
+    }
+
+    /// `if(condition, then, otherwise)`, i.e. `CASE WHEN condition THEN then ELSE otherwise END`
+    pub fn if_else(
+        condition: ExprNode,
+        then: ExprNode,
+        otherwise: ExprNode,
+    ) -> Result<Self, CaseError> {
+        Self::new(vec![(condition, then)], Some(otherwise))
+    }
+
+    /// `coalesce(v1, v2, ...)`, i.e. the first value that is not `NULL`. A value is only
+    /// evaluated on the rows where all earlier values are `NULL`.
+    ///
+    /// Unlike [`Coalesce`](super::Coalesce), which is a kernel on the results of its inputs,
+    /// the values are expressions evaluated by this expression.
+    pub fn coalesce(values: Vec<ExprNode>) -> Result<Self, CaseError> {
+        if values.is_empty() {
+            return Err(CaseError::NoBranch);
+        }
+        let return_type = common_type(values.iter().map(ExprNode::return_type).collect())?;
+        let branches = values
+            .into_iter()
+            .map(|value| Branch::NotNull(cast_node(value, &return_type)))
+            .collect();
+        Ok(Self {
+            branches,
+            else_value: None,
+            return_type,
+        })
+    }
+
+    /// Data type of the result
+    pub fn return_type(&self) -> &DataType {
+        &self.return_type
+    }
+
+    /// Evaluate the expression on `data`, producing `num_rows` rows.
+    pub fn eval(&self, data: &[&ArrayImpl], num_rows: usize) -> Result<ArrayImpl> {
+        // the rows not taken by any branch yet
+        let mut rows = (0..num_rows).collect::<Vec<_>>();
+        // the results of branches, and where the result of each row is
+        let mut results = vec![];
+        let mut positions = vec![None; num_rows];
+        let mut take = |results: &mut Vec<ArrayImpl>, taken: &[usize], values: ArrayImpl| {
+            for (position, &row) in taken.iter().enumerate() {
+                positions[row] = Some((results.len(), position));
+            }
+            results.push(values);
+        };
+
+        for branch in &self.branches {
+            if rows.is_empty() {
+                break;
+            }
+            let inputs = take_rows(data, &rows, num_rows);
+            let inputs = inputs.iter().collect::<Vec<_>>();
+            match branch {
+                Branch::When { condition, value } => {
+                    let condition: BoolArray = condition.eval(&inputs, rows.len())?.try_into()?;
+                    let (_, taken, rest) = split_rows(&rows, |i| condition.get(i) == Some(true));
+                    if !taken.is_empty() {
+                        let inputs = take_rows(data, &taken, num_rows);
+                        let inputs = inputs.iter().collect::<Vec<_>>();
+                        let values = value.eval(&inputs, taken.len())?;
+                        take(&mut results, &taken, values);
+                    }
+                    rows = rest;
+                }
+                Branch::NotNull(value) => {
+                    let values = value.eval(&inputs, rows.len())?;
+                    let (found, taken, rest) = split_rows(&rows, |i| values.get(i).is_some());
+                    take(&mut results, &taken, values.take(&found));
+                    rows = rest;
+                }
+            }
+        }
+        if let (Some(value), false) = (&self.else_value, rows.is_empty()) {
+            let inputs = take_rows(data, &rows, num_rows);
+            let inputs = inputs.iter().collect::<Vec<_>>();
+            let values = value.eval(&inputs, rows.len())?;
+            take(&mut results, &rows, values);
+        }
+
+        let mut builder = ArrayBuilderImpl::new(&self.return_type, num_rows);
+        for position in positions {
+            builder.push(position.and_then(|(result, i)| results[result].get(i)));
+        }
+        Ok(builder.finish())
+    }
+}
+
+impl Expression for CaseExpression {
+    /// Evaluate the expression on `data`, which must have the same length. An expression without
+    /// inputs produces one row.
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        let num_rows = data.first().map_or(1, |array| array.len());
+        if data.iter().any(|array| array.len() != num_rows) {
+            return Err(anyhow!("Inputs have different lengths"));
+        }
+        self.eval(data, num_rows)
+    }
+}
+
+/// The first of `types` that all of them can be implicitly cast into.
+fn common_type(types: Vec<&DataType>) -> Result<DataType, CaseError> {
+    types
+        .iter()
+        .find(|to| {
+            types
+                .iter()
+                .all(|from| can_cast(from, to, CastContext::Implicit))
+        })
+        .map(|to| (*to).clone())
+        .ok_or_else(|| CaseError::NoCommonType(types.into_iter().cloned().collect()))
+}
+
+fn cast_node(node: ExprNode, to: &DataType) -> ExprNode {
+    if node.return_type() == to {
+        return node;
+    }
+    ExprNode::call(Box::new(Cast::new(to.clone())), vec![node], to.clone())
+}
+
+/// Split `rows` by `pred` on their positions in `rows`, into the positions and the rows for which
+/// `pred` is true, and the other rows.
+fn split_rows(
+    rows: &[usize],
+    pred: impl Fn(usize) -> bool,
+) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
+    let (mut positions, mut taken, mut rest) = (vec![], vec![], vec![]);
+    for (i, &row) in rows.iter().enumerate() {
+        if pred(i) {
+            positions.push(i);
+            taken.push(row);
+        } else {
+            rest.push(row);
+        }
+    }
+    (positions, taken, rest)
+}
+
+/// The `rows` of each input, or the inputs themselves if all of their `num_rows` rows are taken.
+fn take_rows(data: &[&ArrayImpl], rows: &[usize], num_rows: usize) -> Vec<ArrayImpl> {
+    data.iter()
+        .map(|array| {
+            if rows.len() == num_rows {
+                (*array).clone()
+            } else {
+                array.take(rows)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExpressionFunc, I16Array, I32Array, I64Array, ScalarImpl, StringArray};
+
+    fn a() -> ExprNode {
+        ExprNode::input_ref(0, DataType::Int32)
+    }
+
+    fn b() -> ExprNode {
+        ExprNode::input_ref(1, DataType::Int32)
+    }
+
+    fn int(v: i32) -> ExprNode {
+        ExprNode::literal(ScalarImpl::Int32(v))
+    }
+
+    fn data() -> [ArrayImpl; 2] {
+        [
+            I32Array::from_slice(&[Some(6), Some(5), None, Some(-4)]).into(),
+            I32Array::from_slice(&[Some(2), Some(0), Some(1), Some(0)]).into(),
+        ]
+    }
+
+    fn eval(expr: &dyn Expression) -> Vec<Option<i32>> {
+        let data = data();
+        let result: I32Array = expr
+            .eval_expr(&data.iter().collect::<Vec<_>>())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        result.iter().collect()
+    }
+
+    #[test]
+    fn test_case() {
+        let binary = |f, l, r| ExprNode::binary(f, l, r).unwrap();
+        // CASE WHEN b <> 0 THEN a / b WHEN a < 0 THEN -1 ELSE 0 END
+        let case = CaseExpression::new(
+            vec![
+                (
+                    binary(ExpressionFunc::CmpNe, b(), int(0)),
+                    binary(ExpressionFunc::Divide, a(), b()),
+                ),
+                (binary(ExpressionFunc::CmpLt, a(), int(0)), int(-1)),
+            ],
+            Some(int(0)),
+        )
+        .unwrap();
+        assert_eq!(eval(&case), [Some(3), Some(0), None, Some(-1)]);
+
+        // without the guard, the division fails on the rows where `b` is 0
+        let unguarded = CaseExpression::new(
+            vec![(
+                binary(ExpressionFunc::CmpGt, a(), int(0)),
+                binary(ExpressionFunc::Divide, a(), b()),
+            )],
+            None,
+        )
+        .unwrap();
+        let data = data();
+        assert!(unguarded
+            .eval_expr(&data.iter().collect::<Vec<_>>())
+            .is_err());
+
+        // a `NULL` condition does not take the row, and there is no `ELSE`
+        let case = CaseExpression::new(
+            vec![(binary(ExpressionFunc::CmpGt, a(), int(5)), b())],
+            None,
+        )
+        .unwrap();
+        assert_eq!(eval(&case), [Some(2), None, None, None]);
+
+        // nested in other expressions
+        let plus = binary(ExpressionFunc::Add, case.into(), int(1));
+        assert_eq!(eval(&plus), [Some(3), None, None, None]);
+    }
+
+    #[test]
+    fn test_case_types() {
+        let condition = || ExprNode::binary(ExpressionFunc::CmpEq, a(), b()).unwrap();
+        let case = CaseExpression::new(
+            vec![(condition(), ExprNode::literal(ScalarImpl::Int16(1)))],
+            Some(ExprNode::literal(ScalarImpl::Int64(i64::MAX))),
+        )
+        .unwrap();
+        assert_eq!(case.return_type(), &DataType::Int64);
+        let data = data();
+        let result: I64Array = case
+            .eval_expr(&data.iter().collect::<Vec<_>>())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(result.get(0), Some(i64::MAX));
+
+        let string = ExprNode::literal(ScalarImpl::String("x".to_string()));
+        assert_eq!(
+            CaseExpression::new(vec![(condition(), string)], Some(int(1))).err(),
+            Some(CaseError::NoCommonType(vec![
+                DataType::String,
+                DataType::Int32
+            ]))
+        );
+        assert_eq!(
+            CaseExpression::new(vec![(a(), int(1))], None).err(),
+            Some(CaseError::NotBool(DataType::Int32))
+        );
+        assert_eq!(
+            CaseExpression::new(vec![], Some(int(1))).err(),
+            Some(CaseError::NoBranch)
+        );
+    }
+
+    #[test]
+    fn test_if_coalesce() {
+        let positive = ExprNode::binary(ExpressionFunc::CmpGt, a(), int(0)).unwrap();
+        let expr = CaseExpression::if_else(positive, a(), b()).unwrap();
+        assert_eq!(eval(&expr), [Some(6), Some(5), Some(1), Some(0)]);
+
+        // `10 / b` is only evaluated where `a` is `NULL`
+        let divide = ExprNode::binary(ExpressionFunc::Divide, int(10), b()).unwrap();
+        let expr = CaseExpression::coalesce(vec![a(), divide, int(0)]).unwrap();
+        assert_eq!(eval(&expr), [Some(6), Some(5), Some(10), Some(-4)]);
+
+        let strings = StringArray::from_slice(&[None, Some("b"), None]).into();
+        let shorts = I16Array::from_slice(&[Some(1), Some(2), None]).into();
+        let expr = CaseExpression::coalesce(vec![
+            ExprNode::input_ref(1, DataType::Int16),
+            ExprNode::null(DataType::Int32),
+            ExprNode::literal(ScalarImpl::Int32(-1)),
+        ])
+        .unwrap();
+        let result: I32Array = expr
+            .eval_expr(&[&strings, &shorts])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(1), Some(2), Some(-1)]
+        );
+        assert!(CaseExpression::coalesce(vec![]).is_err());
+    }
+}
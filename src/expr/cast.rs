@@ -0,0 +1,855 @@
+use std::fmt::{self, Write};
+use std::num::{IntErrorKind, ParseIntError};
+
+use anyhow::{anyhow, bail, Result};
+
+use super::arithmetic::ArithmeticError;
+use super::binary::{decode, encode, BinaryFormat};
+use super::vectorize::{push_result, ErrorPolicy};
+use crate::{
+    Array, ArrayBuilder, ArrayImpl, BinaryArray, BoolArray, DataType, Date, Date32Array, Datum,
+    Decimal, DecimalArray, DecimalArrayBuilder, DecimalError, Expression, F32Array, F64Array,
+    I16Array, I32Array, I64Array, Interval, IntervalArray, StringArray, StringArrayBuilder,
+    StructArray, Time, Time64Array, Timestamp, TimestampArray, MICROS_PER_DAY,
+};
+
+/// The context in which a cast is applied, from the most to the least restrictive one.
+///
+/// A cast allowed in a context is also allowed in all the contexts after it, e.g. an implicit
+/// cast can also be applied explicitly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CastContext {
+    /// Applied automatically wherever a value of the target type is expected, e.g. from `int16`
+    /// to `int32`.
+    Implicit,
+    /// Applied automatically when a value is assigned to a column of the target type, e.g. from
+    /// `int64` to `int32`, which may fail.
+    Assignment,
+    /// Only applied by an explicit `CAST`, e.g. from `string` to `int32`.
+    Explicit,
+}
+
+/// Position of a numeric type in the order of implicit casts, e.g. `int64` is implicitly cast to
+/// `decimal`, which is implicitly cast to `float32`.
+fn numeric_rank(data_type: &DataType) -> Option<u8> {
+    match data_type {
+        DataType::Int16 => Some(0),
+        DataType::Int32 => Some(1),
+        DataType::Int64 => Some(2),
+        DataType::Decimal { .. } => Some(3),
+        DataType::Float32 => Some(4),
+        DataType::Float64 => Some(5),
+        _ => None,
+    }
+}
+
+/// The most restrictive context in which `from` can be cast into `to`, or `None` if there is no
+/// cast between the two types. This is the table of all casts supported by [`Cast`]:
+///
+/// * Numeric types are cast implicitly in the order of `int16`, `int32`, `int64`, `decimal`,
+///   `float32` and `float64`, and by assignment in the opposite order.
+/// * All types but lists and structs are cast into `string` by assignment, and parsed from
+///   `string` explicitly.
+/// * `bool` and integers are cast into each other explicitly.
+/// * `date32` is cast into `timestamp`, and `time64` into `interval` implicitly, and back by
+///   assignment. `timestamp` is also cast into `time64` by assignment.
+/// * Lists are cast by their items, and structs by their fields if they have as many fields.
+pub fn cast_context(from: &DataType, to: &DataType) -> Option<CastContext> {
+    use CastContext::*;
+
+    if from == to {
+        return Some(Implicit);
+    }
+    if let (Some(from), Some(to)) = (numeric_rank(from), numeric_rank(to)) {
+        return Some(if from <= to { Implicit } else { Assignment });
+    }
+    match (from, to) {
+        (DataType::List(from), DataType::List(to)) => cast_context(from, to),
+        (DataType::Struct(from), DataType::Struct(to)) if from.len() == to.len() => from
+            .iter()
+            .zip(to)
+            .try_fold(Implicit, |context, (from, to)| {
+                Some(context.max(cast_context(from.data_type(), to.data_type())?))
+            }),
+        (DataType::List(_) | DataType::Struct(_), _)
+        | (_, DataType::List(_) | DataType::Struct(_)) => None,
+        (_, DataType::String) => Some(Assignment),
+        (DataType::String, _) => Some(Explicit),
+        (DataType::Bool, DataType::Int16 | DataType::Int32 | DataType::Int64)
+        | (DataType::Int16 | DataType::Int32 | DataType::Int64, DataType::Bool) => Some(Explicit),
+        (DataType::Date32, DataType::Timestamp { .. })
+        | (DataType::Timestamp { .. }, DataType::Timestamp { .. })
+        | (DataType::Time64, DataType::Interval) => Some(Implicit),
+        (DataType::Timestamp { .. }, DataType::Date32 | DataType::Time64)
+        | (DataType::Interval, DataType::Time64) => Some(Assignment),
+        _ => None,
+    }
+}
+
+/// Whether `from` can be cast into `to` in `context`, e.g. whether the planner may insert a cast
+/// automatically with [`CastContext::Implicit`].
+pub fn can_cast(from: &DataType, to: &DataType, context: CastContext) -> bool {
+    cast_context(from, to).is_some_and(|c| c <= context)
+}
+
+/// Conversion between numeric types, which returns an error if the value is out of the range of
+/// the target type. Floats are rounded to the nearest integer, with ties to even.
+pub trait NumericCast<T> {
+    fn numeric_cast(self) -> Result<T, ArithmeticError>;
+}
+
+impl<T> NumericCast<T> for T {
+    fn numeric_cast(self) -> Result<T, ArithmeticError> {
+        Ok(self)
+    }
+}
+
+macro_rules! impl_int_to_int {
+    ($($from:ty => $to:ty: $name:literal),*) => {
+        $(
+            impl NumericCast<$to> for $from {
+                fn numeric_cast(self) -> Result<$to, ArithmeticError> {
+                    <$to>::try_from(self).map_err(|_| ArithmeticError::Overflow($name))
+                }
+            }
+        )*
+    };
+}
+
+impl_int_to_int! {
+    i16 => i32: "integer", i16 => i64: "bigint",
+    i32 => i16: "smallint", i32 => i64: "bigint",
+    i64 => i16: "smallint", i64 => i32: "integer",
+    i128 => i16: "smallint", i128 => i32: "integer", i128 => i64: "bigint"
+}
+
+macro_rules! impl_to_float {
+    ($($from:ty => $to:ty),*) => {
+        $(
+            impl NumericCast<$to> for $from {
+                fn numeric_cast(self) -> Result<$to, ArithmeticError> {
+                    Ok(self as $to)
+                }
+            }
+        )*
+    };
+}
+
+impl_to_float! { i16 => f32, i16 => f64, i32 => f32, i32 => f64, i64 => f32, i64 => f64, f32 => f64 }
+
+impl NumericCast<f32> for f64 {
+    fn numeric_cast(self) -> Result<f32, ArithmeticError> {
+        let v = self as f32;
+        if v.is_infinite() && self.is_finite() {
+            return Err(ArithmeticError::Overflow("real"));
+        }
+        Ok(v)
+    }
+}
+
+macro_rules! impl_float_to_int {
+    ($($from:ty => $to:ty: $name:literal),*) => {
+        $(
+            impl NumericCast<$to> for $from {
+                fn numeric_cast(self) -> Result<$to, ArithmeticError> {
+                    let v = self.round_ties_even();
+                    // `MIN` is a power of two, so that both bounds are exact
+                    if v.is_nan() || v < <$to>::MIN as $from || v >= -(<$to>::MIN as $from) {
+                        return Err(ArithmeticError::Overflow($name));
+                    }
+                    Ok(v as $to)
+                }
+            }
+        )*
+    };
+}
+
+impl_float_to_int! {
+    f32 => i16: "smallint", f32 => i32: "integer", f32 => i64: "bigint",
+    f64 => i16: "smallint", f64 => i32: "integer", f64 => i64: "bigint"
+}
+
+/// Conversion of a number into a [`Decimal`] of at most `scale` fractional digits.
+trait ToDecimal {
+    fn to_decimal(self, scale: u8) -> Result<Decimal>;
+}
+
+macro_rules! impl_int_to_decimal {
+    ($($t:ty),*) => {
+        $(
+            impl ToDecimal for $t {
+                fn to_decimal(self, _scale: u8) -> Result<Decimal> {
+                    Ok(self.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_int_to_decimal! { i16, i32, i64 }
+
+macro_rules! impl_float_to_decimal {
+    ($($t:ty),*) => {
+        $(
+            impl ToDecimal for $t {
+                fn to_decimal(self, scale: u8) -> Result<Decimal> {
+                    if !self.is_finite() {
+                        bail!("cannot convert {} to decimal", self);
+                    }
+                    Ok(format!("{:.*}", scale as usize, self).parse()?)
+                }
+            }
+        )*
+    };
+}
+
+impl_float_to_decimal! { f32, f64 }
+
+/// Parsing of a string that is cast into another type. Leading and trailing whitespace is
+/// ignored.
+trait ParseCast: Sized {
+    fn parse_cast(s: &str) -> Result<Self>;
+}
+
+macro_rules! impl_parse_int {
+    ($($t:ty: $name:literal),*) => {
+        $(
+            impl ParseCast for $t {
+                fn parse_cast(s: &str) -> Result<Self> {
+                    s.trim().parse().map_err(|e: ParseIntError| match e.kind() {
+                        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                            ArithmeticError::Overflow($name).into()
+                        }
+                        _ => anyhow!("invalid input syntax for type {}: {:?}", $name, s),
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_parse_int! { i16: "smallint", i32: "integer", i64: "bigint" }
+
+macro_rules! impl_parse_float {
+    ($($t:ty: $name:literal),*) => {
+        $(
+            /// `Infinity`, `-Infinity` and `NaN` are accepted in any case.
+            impl ParseCast for $t {
+                fn parse_cast(s: &str) -> Result<Self> {
+                    let trimmed = s.trim();
+                    let v: $t = trimmed
+                        .parse()
+                        .map_err(|_| anyhow!("invalid input syntax for type {}: {:?}", $name, s))?;
+                    if v.is_infinite() && !trimmed.to_ascii_lowercase().contains("inf") {
+                        return Err(ArithmeticError::Overflow($name).into());
+                    }
+                    Ok(v)
+                }
+            }
+        )*
+    };
+}
+
+impl_parse_float! { f32: "real", f64: "double precision" }
+
+macro_rules! impl_parse_from_str {
+    ($($t:ty),*) => {
+        $(
+            impl ParseCast for $t {
+                fn parse_cast(s: &str) -> Result<Self> {
+                    Ok(s.trim().parse()?)
+                }
+            }
+        )*
+    };
+}
+
+impl_parse_from_str! { Decimal, Date, Time, Timestamp, Interval }
+
+/// `true`, `yes`, `on` and `1` are `TRUE`, and `false`, `no`, `off` and `0` are `FALSE`, in any
+/// case. Unique prefixes such as `t` and `n` are accepted as well.
+impl ParseCast for bool {
+    fn parse_cast(s: &str) -> Result<Self> {
+        let lower = s.trim().to_ascii_lowercase();
+        let is_prefix = |word: &str| !lower.is_empty() && word.starts_with(&lower);
+        match lower.as_str() {
+            "1" | "on" => Ok(true),
+            "0" | "of" | "off" => Ok(false),
+            _ if is_prefix("true") || is_prefix("yes") => Ok(true),
+            _ if is_prefix("false") || is_prefix("no") => Ok(false),
+            _ => bail!("invalid input syntax for type boolean: {:?}", s),
+        }
+    }
+}
+
+/// A string of the form `\x0a1b` is decoded from hex digits, and any other string is taken as
+/// its UTF-8 bytes.
+impl ParseCast for Vec<u8> {
+    fn parse_cast(s: &str) -> Result<Self> {
+        match s.strip_prefix("\\x") {
+            Some(hex) => Ok(decode(BinaryFormat::Hex, hex)?),
+            None => Ok(s.as_bytes().to_vec()),
+        }
+    }
+}
+
+/// Cast every item of `array` into an array of type `to` with `f`, which is called on non-null
+/// items only.
+fn cast_each<A: Array, O: Array>(
+    array: &A,
+    to: &DataType,
+    policy: &ErrorPolicy,
+    f: impl for<'a> Fn(A::RefItem<'a>) -> Result<O::OwnedItem>,
+) -> Result<ArrayImpl> {
+    let mut builder = O::Builder::with_type(to, array.len());
+    for (row, item) in array.iter().enumerate() {
+        match item {
+            Some(v) => push_result(&mut builder, policy, row, f(v), || vec![format!("{:?}", v)])?,
+            None => builder.push(None),
+        }
+    }
+    Ok(builder.finish().into())
+}
+
+/// Format every item of `array` into a string with `f`.
+fn format_each<A: Array>(
+    array: &A,
+    f: impl for<'a> Fn(&mut String, A::RefItem<'a>) -> fmt::Result,
+) -> ArrayImpl {
+    let mut builder = StringArrayBuilder::with_capacity(array.len());
+    let mut buf = String::new();
+    for item in array.iter() {
+        match item {
+            Some(v) => {
+                buf.clear();
+                f(&mut buf, v).unwrap();
+                builder.push(Some(&buf));
+            }
+            None => builder.push(None),
+        }
+    }
+    builder.finish().into()
+}
+
+fn format_float(buf: &mut String, v: f64) -> fmt::Result {
+    match v {
+        f64::INFINITY => buf.write_str("Infinity"),
+        f64::NEG_INFINITY => buf.write_str("-Infinity"),
+        v => write!(buf, "{}", v),
+    }
+}
+
+/// Casts an array into another type, which may be any type supported by [`cast_context`].
+///
+/// Values that cannot be represented in the target type, such as `70000` as `int16` or `abc` as
+/// `int32`, fail the cast, or are cast into `NULL` with [`ErrorPolicy::Null`]. The items of lists
+/// are cast as a whole, so that the rows of such errors are the positions of the items.
+pub struct Cast {
+    to: DataType,
+    policy: ErrorPolicy,
+}
+
+impl Cast {
+    /// Create a cast into `to`, which fails on the first value that cannot be cast.
+    pub fn new(to: DataType) -> Self {
+        Self {
+            to,
+            policy: ErrorPolicy::Fail,
+        }
+    }
+
+    /// Set the policy for values that cannot be cast.
+    pub fn with_error_policy(mut self, policy: ErrorPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Cast the array into the target type.
+    pub fn eval_batch(&self, array: &ArrayImpl) -> Result<ArrayImpl> {
+        let (from, to, policy) = (array.data_type(), &self.to, &self.policy);
+        if from == *to {
+            return Ok(array.clone());
+        }
+        if cast_context(&from, to).is_none() {
+            bail!("Cannot cast {} to {}", from, to);
+        }
+        if let &DataType::Decimal { precision, scale } = to {
+            DecimalArrayBuilder::with_precision_scale(0, precision, scale)?;
+        }
+        match (array, to) {
+            (_, DataType::String) => self.format(array),
+            (ArrayImpl::String(a), _) => self.parse(a),
+            (ArrayImpl::Int16(a), DataType::Bool) => {
+                cast_each::<_, BoolArray>(a, to, policy, |v| Ok(v != 0))
+            }
+            (ArrayImpl::Int32(a), DataType::Bool) => {
+                cast_each::<_, BoolArray>(a, to, policy, |v| Ok(v != 0))
+            }
+            (ArrayImpl::Int64(a), DataType::Bool) => {
+                cast_each::<_, BoolArray>(a, to, policy, |v| Ok(v != 0))
+            }
+            (ArrayImpl::Int16(a), _) => self.cast_numeric(a),
+            (ArrayImpl::Int32(a), _) => self.cast_numeric(a),
+            (ArrayImpl::Int64(a), _) => self.cast_numeric(a),
+            (ArrayImpl::Float32(a), _) => self.cast_numeric(a),
+            (ArrayImpl::Float64(a), _) => self.cast_numeric(a),
+            (ArrayImpl::Decimal(a), _) => self.cast_decimal(a),
+            (ArrayImpl::Bool(a), DataType::Int16) => {
+                cast_each::<_, I16Array>(a, to, policy, |v| Ok(v.into()))
+            }
+            (ArrayImpl::Bool(a), DataType::Int32) => {
+                cast_each::<_, I32Array>(a, to, policy, |v| Ok(v.into()))
+            }
+            (ArrayImpl::Bool(a), DataType::Int64) => {
+                cast_each::<_, I64Array>(a, to, policy, |v| Ok(v.into()))
+            }
+            (ArrayImpl::Date32(a), DataType::Timestamp { .. }) => {
+                cast_each::<_, TimestampArray>(a, to, policy, |v| {
+                    Timestamp::from_date_time(v, Time::default())
+                        .ok_or_else(|| ArithmeticError::Overflow("timestamp").into())
+                })
+            }
+            (ArrayImpl::Timestamp(a), DataType::Timestamp { .. }) => {
+                cast_each::<_, TimestampArray>(a, to, policy, Ok)
+            }
+            (ArrayImpl::Timestamp(a), DataType::Date32) => {
+                cast_each::<_, Date32Array>(a, to, policy, |v| Ok(v.date()))
+            }
+            (ArrayImpl::Timestamp(a), DataType::Time64) => {
+                cast_each::<_, Time64Array>(a, to, policy, |v| Ok(v.time()))
+            }
+            (ArrayImpl::Time64(a), DataType::Interval) => {
+                cast_each::<_, IntervalArray>(a, to, policy, |v| {
+                    Ok(Interval::new(0, 0, v.micros()))
+                })
+            }
+            (ArrayImpl::Interval(a), DataType::Time64) => {
+                cast_each::<_, Time64Array>(a, to, policy, |v| {
+                    Ok(Time::from_micros(v.micros().rem_euclid(MICROS_PER_DAY)).unwrap())
+                })
+            }
+            (ArrayImpl::List(a), DataType::List(value_type)) => {
+                let values = Self::new(value_type.as_ref().clone())
+                    .with_error_policy(policy.clone())
+                    .eval_batch(&a.flat_values())?;
+                Ok(a.with_flat_values(values).into())
+            }
+            (ArrayImpl::Struct(a), DataType::Struct(fields)) => {
+                let children = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, field)| {
+                        let child = Self::new(field.data_type().clone())
+                            .with_error_policy(policy.clone())
+                            .eval_batch(&a.child(idx))?;
+                        Ok(child)
+                    })
+                    .collect::<Result<_>>()?;
+                let bitmap = a.null_bitmap().to_bitvec();
+                Ok(StructArray::new(fields.clone(), children, a.len(), Some(bitmap)).into())
+            }
+            _ => bail!("Cannot cast {} to {}", from, to),
+        }
+    }
+
+    fn cast_numeric<A: Array>(&self, array: &A) -> Result<ArrayImpl>
+    where
+        for<'a> A::RefItem<'a>: NumericCast<i16>
+            + NumericCast<i32>
+            + NumericCast<i64>
+            + NumericCast<f32>
+            + NumericCast<f64>
+            + ToDecimal,
+    {
+        let (to, policy) = (&self.to, &self.policy);
+        match to {
+            DataType::Int16 => {
+                cast_each::<_, I16Array>(array, to, policy, |v| Ok(v.numeric_cast()?))
+            }
+            DataType::Int32 => {
+                cast_each::<_, I32Array>(array, to, policy, |v| Ok(v.numeric_cast()?))
+            }
+            DataType::Int64 => {
+                cast_each::<_, I64Array>(array, to, policy, |v| Ok(v.numeric_cast()?))
+            }
+            DataType::Float32 => {
+                cast_each::<_, F32Array>(array, to, policy, |v| Ok(v.numeric_cast()?))
+            }
+            DataType::Float64 => {
+                cast_each::<_, F64Array>(array, to, policy, |v| Ok(v.numeric_cast()?))
+            }
+            &DataType::Decimal { precision, scale } => {
+                cast_each::<_, DecimalArray>(array, to, policy, |v| {
+                    Ok(v.to_decimal(scale)?
+                        .rescale_with_precision(precision, scale)?)
+                })
+            }
+            _ => bail!("Cannot cast {} to {}", array.data_type(), to),
+        }
+    }
+
+    fn cast_decimal(&self, array: &DecimalArray) -> Result<ArrayImpl> {
+        let (to, policy) = (&self.to, &self.policy);
+        let integral = |v: Decimal| Ok::<_, DecimalError>(v.rescale(0)?.mantissa());
+        match to {
+            DataType::Int16 => {
+                cast_each::<_, I16Array>(array, to, policy, |v| Ok(integral(v)?.numeric_cast()?))
+            }
+            DataType::Int32 => {
+                cast_each::<_, I32Array>(array, to, policy, |v| Ok(integral(v)?.numeric_cast()?))
+            }
+            DataType::Int64 => {
+                cast_each::<_, I64Array>(array, to, policy, |v| Ok(integral(v)?.numeric_cast()?))
+            }
+            DataType::Float32 => {
+                cast_each::<_, F32Array>(array, to, policy, |v| Ok(v.to_string().parse()?))
+            }
+            DataType::Float64 => {
+                cast_each::<_, F64Array>(array, to, policy, |v| Ok(v.to_string().parse()?))
+            }
+            &DataType::Decimal { precision, scale } => {
+                cast_each::<_, DecimalArray>(array, to, policy, |v| {
+                    Ok(v.rescale_with_precision(precision, scale)?)
+                })
+            }
+            _ => bail!("Cannot cast {} to {}", array.data_type(), to),
+        }
+    }
+
+    fn parse(&self, array: &StringArray) -> Result<ArrayImpl> {
+        let (to, policy) = (&self.to, &self.policy);
+        match to {
+            DataType::Int16 => cast_each::<_, I16Array>(array, to, policy, ParseCast::parse_cast),
+            DataType::Int32 => cast_each::<_, I32Array>(array, to, policy, ParseCast::parse_cast),
+            DataType::Int64 => cast_each::<_, I64Array>(array, to, policy, ParseCast::parse_cast),
+            DataType::Float32 => cast_each::<_, F32Array>(array, to, policy, ParseCast::parse_cast),
+            DataType::Float64 => cast_each::<_, F64Array>(array, to, policy, ParseCast::parse_cast),
+            DataType::Bool => cast_each::<_, BoolArray>(array, to, policy, ParseCast::parse_cast),
+            DataType::Binary => {
+                cast_each::<_, BinaryArray>(array, to, policy, ParseCast::parse_cast)
+            }
+            &DataType::Decimal { precision, scale } => {
+                cast_each::<_, DecimalArray>(array, to, policy, |s| {
+                    Ok(Decimal::parse_cast(s)?.rescale_with_precision(precision, scale)?)
+                })
+            }
+            DataType::Date32 => {
+                cast_each::<_, Date32Array>(array, to, policy, ParseCast::parse_cast)
+            }
+            DataType::Time64 => {
+                cast_each::<_, Time64Array>(array, to, policy, ParseCast::parse_cast)
+            }
+            DataType::Timestamp { .. } => {
+                cast_each::<_, TimestampArray>(array, to, policy, ParseCast::parse_cast)
+            }
+            DataType::Interval => {
+                cast_each::<_, IntervalArray>(array, to, policy, ParseCast::parse_cast)
+            }
+            _ => bail!("Cannot cast string to {}", to),
+        }
+    }
+
+    /// Format the array into strings. Floats are formatted as in Postgres, and bytes as hex
+    /// digits prefixed with `\x`.
+    fn format(&self, array: &ArrayImpl) -> Result<ArrayImpl> {
+        Ok(match array {
+            ArrayImpl::Int16(a) => format_each(a, |buf, v| write!(buf, "{}", v)),
+            ArrayImpl::Int32(a) => format_each(a, |buf, v| write!(buf, "{}", v)),
+            ArrayImpl::Int64(a) => format_each(a, |buf, v| write!(buf, "{}", v)),
+            ArrayImpl::Float32(a) => format_each(a, |buf, v| format_float(buf, v.into())),
+            ArrayImpl::Float64(a) => format_each(a, format_float),
+            ArrayImpl::Bool(a) => format_each(a, |buf, v| write!(buf, "{}", v)),
+            ArrayImpl::Binary(a) => format_each(a, |buf, v| {
+                write!(buf, "\\x{}", encode(BinaryFormat::Hex, v))
+            }),
+            ArrayImpl::Decimal(a) => format_each(a, |buf, v| write!(buf, "{}", v)),
+            ArrayImpl::Date32(a) => format_each(a, |buf, v| write!(buf, "{}", v)),
+            ArrayImpl::Time64(a) => format_each(a, |buf, v| write!(buf, "{}", v)),
+            ArrayImpl::Timestamp(a) => format_each(a, |buf, v| write!(buf, "{}", v)),
+            ArrayImpl::Interval(a) => format_each(a, |buf, v| write!(buf, "{}", v)),
+            other => bail!("Cannot cast {} to string", other.data_type()),
+        })
+    }
+}
+
+impl Expression for Cast {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        if data.len() != 1 {
+            return Err(anyhow!("Except one input for Cast"));
+        }
+        self.eval_batch(data[0])
+    }
+
+    /// A constant is cast only once.
+    fn eval_datums(&self, data: &[Datum]) -> Result<ArrayImpl> {
+        if data.len() != 1 {
+            return Err(anyhow!("Except one input for Cast"));
+        }
+        self.eval_batch(data[0].as_array())
+    }
+}
+
+/// Cast `array` into `to`, failing on the first value that cannot be cast. See [`Cast`].
+pub fn cast(array: &ArrayImpl, to: &DataType) -> Result<ArrayImpl> {
+    Cast::new(to.clone()).eval_batch(array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArrayBuilderImpl, Field, ListArray, RowErrors, ScalarRefImpl};
+
+    fn sample_types() -> Vec<DataType> {
+        macro_rules! all_types {
+            ([], $({ $Abc:ident, $abc:ident, $AbcArray:ty, $AbcArrayBuilder:ty, $Owned:ty, $Ref:ty }),*) => {
+                [$(stringify!($abc)),*]
+            };
+        }
+        crate::macros::for_all_variants! { all_types }
+            .iter()
+            .map(|name| match *name {
+                "list" => DataType::List(Box::new(DataType::Int32)),
+                "struct" => DataType::Struct(vec![Field::new("a", DataType::Int32)]),
+                name => name.parse().unwrap(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cast_all_pairs() {
+        let types = sample_types();
+        assert_eq!(types.len(), 15);
+        for from in &types {
+            let mut builder = ArrayBuilderImpl::new(from, 2);
+            builder.push(None);
+            builder.push(None);
+            let array = builder.finish();
+            for to in &types {
+                let result = cast(&array, to);
+                assert_eq!(
+                    result.is_ok(),
+                    cast_context(from, to).is_some(),
+                    "cast from {} to {}",
+                    from,
+                    to
+                );
+                if let Ok(result) = result {
+                    assert_eq!(result.data_type(), *to);
+                    assert!(result.get(1).is_none());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cast_context() {
+        let decimal = DataType::Decimal {
+            precision: 10,
+            scale: 2,
+        };
+        assert_eq!(
+            cast_context(&DataType::Int16, &DataType::Int64),
+            Some(CastContext::Implicit)
+        );
+        assert_eq!(
+            cast_context(&decimal, &DataType::Int32),
+            Some(CastContext::Assignment)
+        );
+        assert_eq!(
+            cast_context(&DataType::String, &DataType::Date32),
+            Some(CastContext::Explicit)
+        );
+        assert!(can_cast(&DataType::Int64, &decimal, CastContext::Implicit));
+        assert!(can_cast(
+            &DataType::Float64,
+            &DataType::String,
+            CastContext::Explicit
+        ));
+        assert!(!can_cast(
+            &DataType::Float64,
+            &DataType::Int32,
+            CastContext::Implicit
+        ));
+        assert!(!can_cast(
+            &DataType::Bool,
+            &DataType::Int32,
+            CastContext::Assignment
+        ));
+        assert_eq!(cast_context(&DataType::Binary, &DataType::Int32), None);
+        assert_eq!(
+            cast_context(
+                &DataType::List(Box::new(DataType::Int32)),
+                &DataType::List(Box::new(DataType::String))
+            ),
+            Some(CastContext::Assignment)
+        );
+    }
+
+    #[test]
+    fn test_cast_numeric() {
+        let ints: ArrayImpl = I32Array::from_slice(&[Some(1), Some(-32768), None]).into();
+        let result = cast(&ints, &DataType::Int16).unwrap();
+        assert_eq!(result.get(1), Some(ScalarRefImpl::Int16(i16::MIN)));
+        let overflow: ArrayImpl = I32Array::from_slice(&[Some(70000)]).into();
+        assert_eq!(
+            cast(&overflow, &DataType::Int16).err().unwrap().to_string(),
+            "smallint out of range in row 0 with inputs (70000)"
+        );
+
+        let floats: ArrayImpl =
+            F64Array::from_slice(&[Some(2.5), Some(3.5), Some(-0.5), Some(1e10)]).into();
+        let result: I32Array = Cast::new(DataType::Int32)
+            .with_error_policy(ErrorPolicy::Null(RowErrors::new()))
+            .eval_batch(&floats)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(2), Some(4), Some(0), None]
+        );
+        let nan: ArrayImpl = F64Array::from_slice(&[Some(f64::NAN)]).into();
+        assert!(cast(&nan, &DataType::Int64).is_err());
+        let huge: ArrayImpl = F64Array::from_slice(&[Some(1e300)]).into();
+        assert!(cast(&huge, &DataType::Float32).is_err());
+
+        let decimal = DataType::Decimal {
+            precision: 5,
+            scale: 2,
+        };
+        let result = cast(&floats.slice(0, 3), &decimal).unwrap();
+        assert_eq!(
+            result.get(0),
+            Some(ScalarRefImpl::Decimal("2.50".parse().unwrap()))
+        );
+        assert!(cast(&floats, &decimal).is_err());
+        let result = cast(&result, &DataType::Int64).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Int64(3)));
+        assert_eq!(result.get(2), Some(ScalarRefImpl::Int64(-1)));
+    }
+
+    #[test]
+    fn test_cast_string() {
+        let strings: ArrayImpl =
+            StringArray::from_slice(&[Some(" 12 "), Some("abc"), Some("99999999999"), None]).into();
+        let errors = RowErrors::new();
+        let result: I32Array = Cast::new(DataType::Int32)
+            .with_error_policy(ErrorPolicy::Null(errors.clone()))
+            .eval_batch(&strings)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(12), None, None, None]
+        );
+        let errors = errors.take();
+        assert_eq!(
+            errors[0].to_string(),
+            "invalid input syntax for type integer: \"abc\" in row 1 with inputs (\"abc\")"
+        );
+        assert_eq!(errors[1].source.to_string(), "integer out of range");
+        assert!(cast(&strings, &DataType::Int32).is_err());
+
+        let bools: ArrayImpl =
+            StringArray::from_slice(&[Some("t"), Some("No"), Some(" on "), Some("0")]).into();
+        let result: BoolArray = cast(&bools, &DataType::Bool).unwrap().try_into().unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(true), Some(false), Some(true), Some(false)]
+        );
+        let ambiguous: ArrayImpl = StringArray::from_slice(&[Some("o")]).into();
+        assert!(cast(&ambiguous, &DataType::Bool).is_err());
+
+        let floats: ArrayImpl =
+            F64Array::from_slice(&[Some(f64::NEG_INFINITY), Some(f64::NAN), Some(0.25)]).into();
+        let formatted = cast(&floats, &DataType::String).unwrap();
+        let result: StringArray = formatted.clone().try_into().unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some("-Infinity"), Some("NaN"), Some("0.25")]
+        );
+        let parsed: F64Array = cast(&formatted, &DataType::Float64)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(parsed.get(0), Some(f64::NEG_INFINITY));
+        assert!(parsed.get(1).unwrap().is_nan());
+
+        let bytes: ArrayImpl = BinaryArray::from_slice(&[Some(&[0x0a, 0xff][..])]).into();
+        let formatted = cast(&bytes, &DataType::String).unwrap();
+        assert_eq!(formatted.get(0), Some(ScalarRefImpl::String("\\x0aff")));
+        let parsed = cast(&formatted, &DataType::Binary).unwrap();
+        assert_eq!(parsed.get(0), Some(ScalarRefImpl::Binary(&[0x0a, 0xff])));
+    }
+
+    #[test]
+    fn test_cast_temporal() {
+        let dates: ArrayImpl = Date32Array::from_slice(&[
+            Some("2021-05-19".parse().unwrap()),
+            Some(Date::from_days(200_000_000)),
+        ])
+        .into();
+        let to = DataType::Timestamp { tz: None };
+        let result = cast(&dates.slice(0, 1), &to).unwrap();
+        assert_eq!(
+            result.get(0),
+            Some(ScalarRefImpl::Timestamp("2021-05-19".parse().unwrap()))
+        );
+        assert_eq!(
+            cast(&dates, &to).err().unwrap().to_string(),
+            "timestamp out of range in row 1 with inputs (Date(200000000))"
+        );
+
+        let dates: ArrayImpl =
+            Date32Array::from_slice(&[Some(Date::from_days(-800_000)), Some(Date::from_days(0))])
+                .into();
+        let formatted = cast(&dates, &DataType::String).unwrap();
+        assert_eq!(
+            formatted.get(0),
+            Some(ScalarRefImpl::String("0222-09-04 BC"))
+        );
+        let parsed = cast(&formatted, &DataType::Date32).unwrap();
+        assert_eq!(parsed.get(0), dates.get(0));
+        assert_eq!(parsed.get(1), dates.get(1));
+    }
+
+    #[test]
+    fn test_cast_nested() {
+        let mut builder = ArrayBuilderImpl::new(&DataType::List(Box::new(DataType::Int32)), 3);
+        let lists = [
+            Some(I32Array::from_slice(&[Some(1), Some(2)])),
+            None,
+            Some(I32Array::from_slice(&[Some(3), None])),
+        ];
+        for list in &lists {
+            let list = list
+                .clone()
+                .map(|values| crate::ListValue::new(values.into()));
+            builder.push(list.as_ref().map(|l| ScalarRefImpl::List(l.as_list_ref())));
+        }
+        let array: ListArray = builder.finish().try_into().unwrap();
+        let array: ArrayImpl = array.slice(1, 2).into();
+        let result: ListArray = cast(&array, &DataType::List(Box::new(DataType::String)))
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert!(result.get(0).is_none());
+        let list = result.get(1).unwrap();
+        assert_eq!(list.get(0), Some(ScalarRefImpl::String("3")));
+        assert!(list.get(1).is_none());
+
+        let structs: ArrayImpl = StructArray::new(
+            vec![Field::new("a", DataType::Int32)],
+            vec![I32Array::from_slice(&[Some(1), None]).into()],
+            2,
+            None,
+        )
+        .into();
+        let to = DataType::Struct(vec![Field::new("b", DataType::Float64).with_nullable(false)]);
+        let result = cast(&structs, &to).unwrap();
+        assert_eq!(result.data_type(), to);
+        let result: StructArray = result.try_into().unwrap();
+        assert_eq!(result.child(0).get(0), Some(ScalarRefImpl::Float64(1.0)));
+    }
+}
@@ -1,81 +1,410 @@
+use std::hash::{Hash, Hasher};
 use std::{cmp::Ordering, marker::PhantomData};
 
-use crate::{Array, BoolArray};
+use crate::macros::for_all_variants;
+use crate::{
+    Array, BoolArray, DataType, Date, Decimal, Interval, ListRef, ScalarRefImpl, StructRef, Time,
+    Timestamp,
+};
 
 use super::vectorize::BinaryExprFunc;
+use super::{can_cast, CastContext};
 
-/// Return if `i1 < i2`. Note that `i1` and `i2` could be different types. This
-/// function will automatically cast them into `C` type.
+/// A total order of the values of comparison functions.
 ///
-/// * `I1`: left input type.
-/// * `I2`: right input type.
-/// * `C`: cast type.
-pub struct ExprCmpLe<I1: Array, I2: Array, C: Array>(pub PhantomData<(I1, I2, C)>);
-
-impl<I1: Array, I2: Array, C: Array> BinaryExprFunc<I1, I2, BoolArray> for ExprCmpLe<I2, I2, C>
-where
-    for<'a> I1::RefItem<'a>: Into<C::RefItem<'a>>,
-    for<'a> I2::RefItem<'a>: Into<C::RefItem<'a>>,
-    for<'a> C::RefItem<'a>: PartialOrd,
-{
-    fn eval<'a>(&self, i1: I1::RefItem<'a>, i2: I2::RefItem<'a>) -> bool {
-        i1.into().partial_cmp(&i2.into()).unwrap() == Ordering::Less
+/// It is the same as [`Ord`] for all types but floats, where `NaN` is equal to itself and greater
+/// than any other value as in Postgres, so that comparisons never fail.
+pub trait TotalOrd {
+    fn total_order(&self, other: &Self) -> Ordering;
+}
+
+macro_rules! impl_total_ord {
+    ($($t:ty),*) => {
+        $(
+            impl TotalOrd for $t {
+                fn total_order(&self, other: &Self) -> Ordering {
+                    self.cmp(other)
+                }
+            }
+        )*
+    };
+}
+
+impl_total_ord! { i16, i32, i64, bool, &str, &[u8], Decimal, Date, Time, Timestamp, Interval }
+
+macro_rules! impl_total_ord_float {
+    ($($t:ty),*) => {
+        $(
+            /// `-0.0` is equal to `0.0`, and `NaN` is equal to itself and greater than any other
+            /// value, including the infinity.
+            impl TotalOrd for $t {
+                fn total_order(&self, other: &Self) -> Ordering {
+                    match (self.is_nan(), other.is_nan()) {
+                        (true, true) => Ordering::Equal,
+                        (true, false) => Ordering::Greater,
+                        (false, true) => Ordering::Less,
+                        (false, false) => self.partial_cmp(other).unwrap(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_total_ord_float! { f32, f64 }
+
+/// Compare two optional values, where `NULL` is equal to itself and greater than any other value.
+fn total_order_nullable(a: Option<ScalarRefImpl<'_>>, b: Option<ScalarRefImpl<'_>>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.total_order(&b),
+        (a, b) => b.is_some().cmp(&a.is_some()),
     }
 }
 
-/// Return if `i1 > i2`. Note that `i1` and `i2` could be different types. This
-/// function will automatically cast them into `C` type.
-///
-/// * `I1`: left input type.
-/// * `I2`: right input type.
-/// * `C`: cast type.
-pub struct ExprCmpGe<I1: Array, I2: Array, C: Array>(pub PhantomData<(I1, I2, C)>);
-
-impl<I1: Array, I2: Array, C: Array> BinaryExprFunc<I1, I2, BoolArray> for ExprCmpGe<I2, I2, C>
-where
-    for<'a> I1::RefItem<'a>: Into<C::RefItem<'a>>,
-    for<'a> I2::RefItem<'a>: Into<C::RefItem<'a>>,
-    for<'a> C::RefItem<'a>: PartialOrd,
-{
-    fn eval<'a>(&self, i1: I1::RefItem<'a>, i2: I2::RefItem<'a>) -> bool {
-        i1.into().partial_cmp(&i2.into()).unwrap() == Ordering::Greater
+/// Compare two sequences of optional values item by item. A sequence that is a prefix of the other
+/// is less than it.
+fn total_order_items<'a, 'b>(
+    mut a: impl Iterator<Item = Option<ScalarRefImpl<'a>>>,
+    mut b: impl Iterator<Item = Option<ScalarRefImpl<'b>>>,
+) -> Ordering {
+    loop {
+        match (a.next(), b.next()) {
+            (Some(a), Some(b)) => match total_order_nullable(a, b) {
+                Ordering::Equal => continue,
+                ordering => return ordering,
+            },
+            (a, b) => return a.is_some().cmp(&b.is_some()),
+        }
     }
 }
 
-/// Return if `i1 == i2`. Note that `i1` and `i2` could be different types. This
-/// function will automatically cast them into `C` type.
-///
-/// * `I1`: left input type.
-/// * `I2`: right input type.
-/// * `C`: cast type.
-pub struct ExprCmpEq<I1: Array, I2: Array, C: Array>(pub PhantomData<(I1, I2, C)>);
-
-impl<I1: Array, I2: Array, C: Array> BinaryExprFunc<I1, I2, BoolArray> for ExprCmpEq<I2, I2, C>
-where
-    for<'a> I1::RefItem<'a>: Into<C::RefItem<'a>>,
-    for<'a> I2::RefItem<'a>: Into<C::RefItem<'a>>,
-    for<'a> C::RefItem<'a>: PartialEq,
-{
-    fn eval<'a>(&self, i1: I1::RefItem<'a>, i2: I2::RefItem<'a>) -> bool {
-        i1.into().eq(&i2.into())
+/// Lists are compared item by item, where `NULL` is greater than any other item as in Postgres.
+impl TotalOrd for ListRef<'_> {
+    fn total_order(&self, other: &Self) -> Ordering {
+        total_order_items(self.iter(), other.iter())
+    }
+}
+
+/// Structs are compared field by field, where `NULL` is greater than any other value as in
+/// Postgres.
+impl TotalOrd for StructRef<'_> {
+    fn total_order(&self, other: &Self) -> Ordering {
+        total_order_items(self.iter(), other.iter())
+    }
+}
+
+/// Implements [`TotalOrd`] and [`TotalHash`] for [`ScalarRefImpl`]
+macro_rules! impl_total_ord_scalar_ref {
+    ([], $({ $Abc:ident, $abc:ident, $AbcArray:ty, $AbcArrayBuilder:ty, $Owned:ty, $Ref:ty }),*) => {
+        /// Values of the same type are compared by the [`TotalOrd`] of the type.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the values are of different types.
+        impl TotalOrd for ScalarRefImpl<'_> {
+            fn total_order(&self, other: &Self) -> Ordering {
+                match (self, other) {
+                    $(
+                        (Self::$Abc(a), Self::$Abc(b)) => a.total_order(b),
+                    )*
+                    (a, b) => panic!("cannot compare {} with {}", a.identifier(), b.identifier()),
+                }
+            }
+        }
+
+        impl TotalHash for ScalarRefImpl<'_> {
+            fn total_hash<H: Hasher>(&self, state: &mut H) {
+                match self {
+                    $(
+                        Self::$Abc(v) => v.total_hash(state),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+for_all_variants! { impl_total_ord_scalar_ref }
+
+/// Whether values of `a` and `b` can be compared by the [`TotalOrd`] of [`ScalarRefImpl`], i.e. the
+/// types are the same except for the precision and scale of decimals and the timezone of
+/// timestamps, which do not change how values are compared.
+pub(super) fn is_comparable(a: &DataType, b: &DataType) -> bool {
+    match (a, b) {
+        (DataType::Decimal { .. }, DataType::Decimal { .. })
+        | (DataType::Timestamp { .. }, DataType::Timestamp { .. }) => true,
+        (DataType::List(a), DataType::List(b)) => is_comparable(a, b),
+        (DataType::Struct(a), DataType::Struct(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b)
+                    .all(|(a, b)| is_comparable(a.data_type(), b.data_type()))
+        }
+        (a, b) => a == b,
+    }
+}
+
+/// The type that inputs of `types` are compared as, which is the first type if all types are
+/// comparable with it, or else the first of them that all others can be implicitly cast into.
+pub(super) fn comparison_type(types: &[DataType]) -> Option<&DataType> {
+    let first = types.first()?;
+    if types.iter().all(|t| is_comparable(t, first)) {
+        return Some(first);
+    }
+    types.iter().find(|to| {
+        types
+            .iter()
+            .all(|from| can_cast(from, to, CastContext::Implicit))
+    })
+}
+
+/// A hash consistent with [`TotalOrd`], i.e. values equal in the total order have the same hash,
+/// so that they can be looked up in a hash table.
+pub trait TotalHash {
+    fn total_hash<H: Hasher>(&self, state: &mut H);
+}
+
+macro_rules! impl_total_hash {
+    ($($t:ty),*) => {
+        $(
+            impl TotalHash for $t {
+                fn total_hash<H: Hasher>(&self, state: &mut H) {
+                    self.hash(state)
+                }
+            }
+        )*
+    };
+}
+
+impl_total_hash! { i16, i32, i64, bool, &str, &[u8], Decimal, Date, Time, Timestamp, Interval }
+
+macro_rules! impl_total_hash_float {
+    ($($t:ty),*) => {
+        $(
+            /// All `NaN`s have the same hash, and so do `-0.0` and `0.0`.
+            impl TotalHash for $t {
+                fn total_hash<H: Hasher>(&self, state: &mut H) {
+                    let value = if self.is_nan() {
+                        <$t>::NAN
+                    } else if *self == 0.0 {
+                        0.0
+                    } else {
+                        *self
+                    };
+                    value.to_bits().hash(state)
+                }
+            }
+        )*
+    };
+}
+
+impl_total_hash_float! { f32, f64 }
+
+/// Hash a sequence of optional values item by item.
+fn total_hash_items<'a, H: Hasher>(
+    items: impl Iterator<Item = Option<ScalarRefImpl<'a>>>,
+    state: &mut H,
+) {
+    for item in items {
+        item.is_some().hash(state);
+        if let Some(item) = item {
+            item.total_hash(state);
+        }
     }
 }
 
-/// Return if `i1 > i2`. Note that `i1` and `i2` could be different types. This
-/// function will automatically cast them into `C` type.
+impl TotalHash for ListRef<'_> {
+    fn total_hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        total_hash_items(self.iter(), state);
+    }
+}
+
+impl TotalHash for StructRef<'_> {
+    fn total_hash<H: Hasher>(&self, state: &mut H) {
+        total_hash_items(self.iter(), state);
+    }
+}
+
+/// Conversion of an input of comparison or arithmetic functions into the type it is computed as.
 ///
-/// * `I1`: left input type.
-/// * `I2`: right input type.
-/// * `C`: cast type.
-pub struct ExprCmpNe<I1: Array, I2: Array, C: Array>(pub PhantomData<(I1, I2, C)>);
-
-impl<I1: Array, I2: Array, C: Array> BinaryExprFunc<I1, I2, BoolArray> for ExprCmpNe<I2, I2, C>
-where
-    for<'a> I1::RefItem<'a>: Into<C::RefItem<'a>>,
-    for<'a> I2::RefItem<'a>: Into<C::RefItem<'a>>,
-    for<'a> C::RefItem<'a>: PartialEq,
-{
-    fn eval<'a>(&self, i1: I1::RefItem<'a>, i2: I2::RefItem<'a>) -> bool {
-        !i1.into().eq(&i2.into())
+/// It is the same as [`Into`], but additionally converts `bigint` into `double precision` as in
+/// Postgres, even though it may lose precision.
+pub trait CmpCast<T> {
+    fn cmp_cast(self) -> T;
+}
+
+impl<T> CmpCast<T> for T {
+    fn cmp_cast(self) -> T {
+        self
+    }
+}
+
+macro_rules! impl_cmp_cast {
+    ($($from:ty => $to:ty),*) => {
+        $(
+            impl CmpCast<$to> for $from {
+                fn cmp_cast(self) -> $to {
+                    self.into()
+                }
+            }
+        )*
+    };
+}
+
+impl_cmp_cast! {
+    i16 => i32, i16 => i64, i32 => i64,
+    i16 => f32, i16 => f64, i32 => f64, f32 => f64,
+    i16 => Decimal, i32 => Decimal, i64 => Decimal,
+    Date => Timestamp
+}
+
+impl CmpCast<f64> for i64 {
+    fn cmp_cast(self) -> f64 {
+        self as f64
+    }
+}
+
+macro_rules! impl_cmp_func {
+    ($($Func:ident, $doc:literal, $($ordering:ident)|+);*) => {
+        $(
+            #[doc = concat!("Return if `", $doc, "`. Note that `i1` and `i2` could be different types. This")]
+            /// function will automatically cast them into `C` type.
+            ///
+            /// * `I1`: left input type.
+            /// * `I2`: right input type.
+            /// * `C`: cast type.
+            pub struct $Func<I1: Array, I2: Array, C: Array>(pub PhantomData<(I1, I2, C)>);
+
+            impl<I1: Array, I2: Array, C: Array> BinaryExprFunc<I1, I2, BoolArray> for $Func<I1, I2, C>
+            where
+                for<'a> I1::RefItem<'a>: CmpCast<C::RefItem<'a>>,
+                for<'a> I2::RefItem<'a>: CmpCast<C::RefItem<'a>>,
+                for<'a> C::RefItem<'a>: TotalOrd,
+            {
+                fn eval<'a>(&self, i1: I1::RefItem<'a>, i2: I2::RefItem<'a>) -> bool {
+                    matches!(i1.cmp_cast().total_order(&i2.cmp_cast()), $(Ordering::$ordering)|+)
+                }
+            }
+        )*
+    };
+}
+
+impl_cmp_func! {
+    ExprCmpLt, "i1 < i2", Less;
+    ExprCmpLe, "i1 <= i2", Less | Equal;
+    ExprCmpGt, "i1 > i2", Greater;
+    ExprCmpGe, "i1 >= i2", Greater | Equal;
+    ExprCmpEq, "i1 = i2", Equal;
+    ExprCmpNe, "i1 <> i2", Less | Greater
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+
+    use crate::{BinaryExpression, Expression, F64Array, I16Array, I32Array, I64Array, ListValue};
+
+    #[test]
+    fn test_float_total_order() {
+        assert_eq!(f64::NAN.total_order(&f64::NAN), Ordering::Equal);
+        assert_eq!(f32::NAN.total_order(&f32::INFINITY), Ordering::Greater);
+        assert_eq!((-0.0f64).total_order(&0.0), Ordering::Equal);
+        assert_eq!(1.0f64.total_order(&f64::NAN), Ordering::Less);
+
+        let left = F64Array::from_slice(&[Some(f64::NAN), Some(f64::NAN), Some(1.0), Some(2.0)]);
+        let right = F64Array::from_slice(&[
+            Some(f64::NAN),
+            Some(f64::INFINITY),
+            Some(f64::NAN),
+            Some(2.0),
+        ]);
+        let eval = |expr: &dyn Expression| -> Vec<_> {
+            let result: BoolArray = expr
+                .eval_expr(&[&left.clone().into(), &right.clone().into()])
+                .unwrap()
+                .try_into()
+                .unwrap();
+            result.iter().map(Option::unwrap).collect()
+        };
+        macro_rules! cmp {
+            ($Func:ident) => {
+                &BinaryExpression::<F64Array, F64Array, BoolArray, _>::new($Func::<_, _, F64Array>(
+                    PhantomData,
+                ))
+            };
+        }
+        assert_eq!(eval(cmp!(ExprCmpLt)), [false, false, true, false]);
+        assert_eq!(eval(cmp!(ExprCmpLe)), [true, false, true, true]);
+        assert_eq!(eval(cmp!(ExprCmpGt)), [false, true, false, false]);
+        assert_eq!(eval(cmp!(ExprCmpGe)), [true, true, false, true]);
+        assert_eq!(eval(cmp!(ExprCmpEq)), [true, false, false, true]);
+        assert_eq!(eval(cmp!(ExprCmpNe)), [false, true, true, false]);
+    }
+
+    #[test]
+    fn test_mixed_types() {
+        let func = ExprCmpLt::<I16Array, I64Array, I64Array>(PhantomData);
+        assert!(BinaryExprFunc::<I16Array, I64Array, BoolArray>::eval(
+            &func,
+            -1,
+            i64::MAX
+        ));
+
+        let func = ExprCmpEq::<I64Array, F64Array, F64Array>(PhantomData);
+        assert!(BinaryExprFunc::<I64Array, F64Array, BoolArray>::eval(
+            &func,
+            1 << 53,
+            2f64.powi(53)
+        ));
+        assert!(!BinaryExprFunc::<I64Array, F64Array, BoolArray>::eval(
+            &func,
+            3,
+            f64::NAN
+        ));
+    }
+
+    #[test]
+    fn test_scalar_total_order() {
+        let hash = |v: ScalarRefImpl<'_>| {
+            let mut state = DefaultHasher::new();
+            v.total_hash(&mut state);
+            state.finish()
+        };
+        let (nan, neg_zero) = (
+            ScalarRefImpl::Float64(f64::NAN),
+            ScalarRefImpl::Float64(-0.0),
+        );
+        assert_eq!(hash(nan), hash(ScalarRefImpl::Float64(-f64::NAN)));
+        assert_eq!(hash(neg_zero), hash(ScalarRefImpl::Float64(0.0)));
+        let (d1, d2) = ("1.5".parse().unwrap(), "1.500".parse().unwrap());
+        assert_eq!(
+            ScalarRefImpl::Decimal(d1).total_order(&ScalarRefImpl::Decimal(d2)),
+            Ordering::Equal
+        );
+        assert_eq!(
+            hash(ScalarRefImpl::Decimal(d1)),
+            hash(ScalarRefImpl::Decimal(d2))
+        );
+
+        let list = |items: &[Option<i32>]| ListValue::new(I32Array::from_slice(items).into());
+        let lists = [
+            list(&[Some(1)]),
+            list(&[Some(1), Some(2)]),
+            list(&[Some(1), None]),
+            list(&[Some(2)]),
+        ];
+        for (i, a) in lists.iter().enumerate() {
+            for (j, b) in lists.iter().enumerate() {
+                let order = a.as_list_ref().total_order(&b.as_list_ref());
+                assert_eq!(order, i.cmp(&j));
+            }
+        }
+        let null_list = list(&[Some(1), None]);
+        assert_eq!(
+            hash(ScalarRefImpl::List(lists[2].as_list_ref())),
+            hash(ScalarRefImpl::List(null_list.as_list_ref()))
+        );
     }
 }
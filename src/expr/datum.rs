@@ -0,0 +1,80 @@
+use crate::{ArrayBuilderImpl, ArrayImpl, DataType, ScalarRefImpl};
+
+/// An input of an expression, which is either an array with one item for each row, or a constant
+/// that stands for the same item in every row.
+///
+/// A constant is stored as an array of length one, so that vectorized expressions can read it as
+/// the concrete array type without materializing it for the whole batch.
+#[derive(Clone)]
+pub struct Datum {
+    array: ArrayImpl,
+    is_scalar: bool,
+}
+
+impl Datum {
+    /// Create an input of one item for each row.
+    pub fn array(array: ArrayImpl) -> Self {
+        Self {
+            array,
+            is_scalar: false,
+        }
+    }
+
+    /// Create a constant input of `value`, which is `NULL` if `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is not of `data_type`.
+    pub fn scalar(value: Option<ScalarRefImpl<'_>>, data_type: &DataType) -> Self {
+        let mut builder = ArrayBuilderImpl::new(data_type, 1);
+        builder.push(value);
+        Self {
+            array: builder.finish(),
+            is_scalar: true,
+        }
+    }
+
+    /// Whether the input is a constant
+    pub fn is_scalar(&self) -> bool {
+        self.is_scalar
+    }
+
+    /// Number of rows of an array input, or `None` for a constant.
+    pub fn num_rows(&self) -> Option<usize> {
+        (!self.is_scalar).then(|| self.array.len())
+    }
+
+    /// Data type of the input
+    pub fn data_type(&self) -> DataType {
+        self.array.data_type()
+    }
+
+    /// Get the item of the row at `idx`, which is the same item for every row of a constant.
+    pub fn get(&self, idx: usize) -> Option<ScalarRefImpl<'_>> {
+        self.array.get(if self.is_scalar { 0 } else { idx })
+    }
+
+    /// The underlying array, which has only one item for a constant.
+    pub fn as_array(&self) -> &ArrayImpl {
+        &self.array
+    }
+
+    /// Get the input as an array of `len` rows, repeating a constant if necessary.
+    pub fn to_array(&self, len: usize) -> ArrayImpl {
+        if !self.is_scalar {
+            return self.array.clone();
+        }
+        let mut builder = ArrayBuilderImpl::new(&self.array.data_type(), len);
+        let value = self.array.get(0);
+        for _ in 0..len {
+            builder.push(value);
+        }
+        builder.finish()
+    }
+}
+
+impl From<ArrayImpl> for Datum {
+    fn from(array: ArrayImpl) -> Self {
+        Self::array(array)
+    }
+}
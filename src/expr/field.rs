@@ -0,0 +1,132 @@
+use anyhow::{anyhow, Result};
+
+use crate::{Array, ArrayBuilderImpl, ArrayImpl, Expression, StructArray};
+
+/// Returns `field(s, name)`, i.e. the field of `name` of each struct. The result is `NULL` for
+/// null structs.
+pub struct GetField {
+    name: String,
+}
+
+impl GetField {
+    /// Create the projection of the field of `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+
+    /// Evaluate the field of each struct, returning an error if there is no such field. The
+    /// result shares the buffers with `array` if no struct is null.
+    pub fn eval_batch(&self, array: &StructArray) -> Result<ArrayImpl> {
+        let idx = array
+            .field_index(&self.name)
+            .ok_or_else(|| anyhow!("No field {} in {}", self.name, array.data_type()))?;
+        let child = array.child(idx);
+        if array.iter().all(|item| item.is_some()) {
+            return Ok(child);
+        }
+        let mut builder = ArrayBuilderImpl::new(&child.data_type(), child.len());
+        for (row, item) in array.iter().enumerate() {
+            builder.push(item.and_then(|_| child.get(row)));
+        }
+        Ok(builder.finish())
+    }
+}
+
+impl Expression for GetField {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        if data.len() != 1 {
+            return Err(anyhow!("Except one input for GetField"));
+        }
+        self.eval_batch(data[0].try_into()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitvec::prelude::*;
+
+    use super::*;
+    use crate::{
+        ArrayBuilder, DataType, Field, I64Array, ScalarImpl, ScalarRef, ScalarRefImpl, StringArray,
+        StructArrayBuilder, StructValue,
+    };
+
+    fn people() -> StructArray {
+        StructArray::new(
+            vec![
+                Field::new("id", DataType::Int64),
+                Field::new("name", DataType::String),
+            ],
+            vec![
+                I64Array::from_slice(&[Some(1), Some(2), None]).into(),
+                StringArray::from_slice(&[Some("a"), Some("b"), Some("c")]).into(),
+            ],
+            3,
+            Some(bitvec![1, 0, 1]),
+        )
+    }
+
+    #[test]
+    fn test_struct_array() {
+        let array = people();
+        assert_eq!(
+            array.data_type(),
+            "struct(id int64, name string)".parse().unwrap()
+        );
+        assert!(array.get(1).is_none());
+        let person = array.get(2).unwrap();
+        assert_eq!(person.field(0), None);
+        assert_eq!(
+            person.field_by_name("name"),
+            Some(Some(ScalarRefImpl::String("c")))
+        );
+        assert_eq!(person.field_by_name("age"), None);
+
+        let value = StructValue::new(
+            vec![
+                Field::new("id", DataType::Int64),
+                Field::new("name", DataType::String),
+            ],
+            vec![None, Some(ScalarImpl::String("c".to_string()))],
+        );
+        assert_eq!(person.to_owned_scalar(), value);
+
+        let mut builder = StructArrayBuilder::with_capacity(3);
+        builder.push(None);
+        builder.push(Some(value.as_struct_ref()));
+        builder.push(array.get(0));
+        let rebuilt = builder.finish();
+        assert_eq!(rebuilt.data_type(), array.data_type());
+        assert!(rebuilt.get(0).is_none());
+        assert_eq!(rebuilt.get(1), Some(person));
+        assert_eq!(rebuilt.slice(2, 1).get(0), array.get(0));
+
+        let empty = StructArray::new(vec![], vec![], 2, None);
+        assert_eq!(empty.len(), 2);
+        assert_eq!(empty.data_type(), DataType::Struct(vec![]));
+        assert!(empty.get(1).is_some());
+    }
+
+    #[test]
+    fn test_get_field() {
+        let array: ArrayImpl = people().into();
+        let result: StringArray = GetField::new("name")
+            .eval_expr(&[&array])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some("a"), None, Some("c")]
+        );
+
+        let result: I64Array = GetField::new("id")
+            .eval_expr(&[&array.slice(2, 1)])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(result.iter().collect::<Vec<_>>(), [None]);
+
+        assert!(GetField::new("age").eval_expr(&[&array]).is_err());
+    }
+}
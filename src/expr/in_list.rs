@@ -0,0 +1,254 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+
+use anyhow::{anyhow, Result};
+
+use super::cmp::{comparison_type, is_comparable, TotalHash};
+use super::{cast, Datum, Expression, TotalOrd};
+use crate::{
+    ArrayBuilder, ArrayImpl, BoolArray, BoolArrayBuilder, DataType, ScalarImpl, ScalarRefImpl,
+};
+
+/// `i1 IN (v1, v2, ...)` or `i1 NOT IN (v1, v2, ...)` of constant values.
+///
+/// The values are put into a hash table once when the expression is built, so that each row is
+/// looked up in constant time however long the list is. Values are equal if they are equal in
+/// [`TotalOrd`], e.g. `NaN` is in a list containing `NaN`.
+///
+/// As in SQL, the result is `NULL` if the input is `NULL`, or if the input is not found and the
+/// list contains a `NULL`, since the `NULL` might stand for the input. Therefore `x NOT IN (1,
+/// NULL)` is never `TRUE`.
+pub struct InListExpression {
+    data_type: DataType,
+    /// The type that the input and the values are compared as
+    compare_type: DataType,
+    /// The non-null values, bucketed by their [`TotalHash`]
+    values: HashMap<u64, Vec<ScalarImpl>>,
+    hasher: RandomState,
+    contains_null: bool,
+    negated: bool,
+}
+
+impl InListExpression {
+    /// `i1 IN (values)`, where `i1` is of `data_type`. If the values are of other types, the input
+    /// and the values are cast into the first of their types that all others can be implicitly
+    /// cast into, so that `int_column IN (1.5)` is compared as floats and never matches. An error
+    /// is returned if there is no such type.
+    pub fn new(data_type: DataType, values: Vec<Option<ScalarImpl>>) -> Result<Self> {
+        let types = std::iter::once(data_type.clone())
+            .chain(values.iter().flatten().map(ScalarImpl::data_type))
+            .collect::<Vec<_>>();
+        let compare_type = comparison_type(&types).cloned().ok_or_else(|| {
+            anyhow!(
+                "IN cannot compare {} with {:?}",
+                data_type,
+                types[1..]
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+            )
+        })?;
+        let mut expr = Self {
+            data_type,
+            compare_type,
+            values: HashMap::new(),
+            hasher: RandomState::new(),
+            contains_null: false,
+            negated: false,
+        };
+        for value in values {
+            match value {
+                Some(value) => expr.insert(value)?,
+                None => expr.contains_null = true,
+            }
+        }
+        Ok(expr)
+    }
+
+    /// `i1 NOT IN (values)`. See [`InListExpression::new`].
+    pub fn not_in(data_type: DataType, values: Vec<Option<ScalarImpl>>) -> Result<Self> {
+        let mut expr = Self::new(data_type, values)?;
+        expr.negated = true;
+        Ok(expr)
+    }
+
+    fn hash(&self, value: ScalarRefImpl<'_>) -> u64 {
+        let mut state = self.hasher.build_hasher();
+        value.total_hash(&mut state);
+        state.finish()
+    }
+
+    fn insert(&mut self, value: ScalarImpl) -> Result<()> {
+        let value = if is_comparable(&value.data_type(), &self.compare_type) {
+            value
+        } else {
+            let array = Datum::scalar(Some(value.as_scalar_ref()), &value.data_type());
+            let array = cast(array.as_array(), &self.compare_type)?;
+            array.get(0).unwrap().to_owned_scalar()
+        };
+        if !self.contains(value.as_scalar_ref()) {
+            let hash = self.hash(value.as_scalar_ref());
+            self.values.entry(hash).or_default().push(value);
+        }
+        Ok(())
+    }
+
+    fn contains(&self, value: ScalarRefImpl<'_>) -> bool {
+        self.values.get(&self.hash(value)).is_some_and(|bucket| {
+            bucket
+                .iter()
+                .any(|v| v.as_scalar_ref().total_order(&value).is_eq())
+        })
+    }
+
+    /// Evaluate the predicate on an array of the type given on creation.
+    pub fn eval_batch(&self, array: &ArrayImpl) -> Result<BoolArray> {
+        if !is_comparable(&array.data_type(), &self.data_type) {
+            return Err(anyhow!(
+                "Input of InListExpression should be of type {}, get {}",
+                self.data_type,
+                array.data_type()
+            ));
+        }
+        let cast_array;
+        let array = if is_comparable(&self.data_type, &self.compare_type) {
+            array
+        } else {
+            cast_array = cast(array, &self.compare_type)?;
+            &cast_array
+        };
+        let mut builder = BoolArrayBuilder::with_capacity(array.len());
+        for row in 0..array.len() {
+            let found = match array.get(row) {
+                Some(value) if self.contains(value) => Some(true),
+                Some(_) if !self.contains_null => Some(false),
+                _ => None,
+            };
+            builder.push(found.map(|found| found != self.negated));
+        }
+        Ok(builder.finish())
+    }
+}
+
+impl Expression for InListExpression {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        match data {
+            [i1] => Ok(self.eval_batch(i1)?.into()),
+            _ => Err(anyhow!("Except 1 input(s) for InListExpression")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Array, Decimal, DecimalArray, F64Array, I16Array, I32Array, ListArray, ListValue,
+        StringArray,
+    };
+
+    fn eval(expr: &InListExpression, array: impl Into<ArrayImpl>) -> Vec<Option<bool>> {
+        expr.eval_batch(&array.into()).unwrap().iter().collect()
+    }
+
+    #[test]
+    fn test_in_list() {
+        let strings = StringArray::from_slice(&[Some("a"), Some("c"), Some("x"), None]);
+        let values = ["a", "b", "c"].map(|s| Some(ScalarImpl::String(s.to_string())));
+        let expr = InListExpression::new(DataType::String, values.to_vec()).unwrap();
+        assert_eq!(
+            eval(&expr, strings.clone()),
+            [Some(true), Some(true), Some(false), None]
+        );
+        let expr = InListExpression::not_in(DataType::String, values.to_vec()).unwrap();
+        assert_eq!(
+            eval(&expr, strings.clone()),
+            [Some(false), Some(false), Some(true), None]
+        );
+
+        // a `NULL` in the list turns a miss into `NULL`
+        let mut with_null = values.to_vec();
+        with_null.push(None);
+        let expr = InListExpression::new(DataType::String, with_null.clone()).unwrap();
+        assert_eq!(
+            eval(&expr, strings.clone()),
+            [Some(true), Some(true), None, None]
+        );
+        let expr = InListExpression::not_in(DataType::String, with_null).unwrap();
+        assert_eq!(eval(&expr, strings), [Some(false), Some(false), None, None]);
+
+        let expr = InListExpression::new(DataType::Int32, vec![]).unwrap();
+        assert!(expr
+            .eval_batch(&I16Array::from_slice(&[Some(1)]).into())
+            .is_err());
+    }
+
+    #[test]
+    fn test_in_list_types() {
+        // the input and the values are compared as a common type
+        let expr = InListExpression::new(
+            DataType::Int16,
+            vec![Some(ScalarImpl::Int64(1)), Some(ScalarImpl::Int32(3))],
+        )
+        .unwrap();
+        assert_eq!(
+            eval(&expr, I16Array::from_slice(&[Some(1), Some(2), Some(3)])),
+            [Some(true), Some(false), Some(true)]
+        );
+        let expr =
+            InListExpression::new(DataType::Int16, vec![Some(ScalarImpl::Int32(70000))]).unwrap();
+        assert_eq!(
+            eval(&expr, I16Array::from_slice(&[Some(4464)])),
+            [Some(false)]
+        );
+        let expr =
+            InListExpression::new(DataType::Int32, vec![Some(ScalarImpl::Float64(1.5))]).unwrap();
+        assert_eq!(
+            eval(&expr, I32Array::from_slice(&[Some(1), Some(2)])),
+            [Some(false), Some(false)]
+        );
+        assert!(InListExpression::new(
+            DataType::Int32,
+            vec![Some(ScalarImpl::String("1".to_string()))]
+        )
+        .is_err());
+
+        let expr = InListExpression::new(
+            DataType::Float64,
+            vec![
+                Some(ScalarImpl::Float64(f64::NAN)),
+                Some(ScalarImpl::Float64(0.0)),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            eval(
+                &expr,
+                F64Array::from_slice(&[Some(f64::NAN), Some(-0.0), Some(1.0)])
+            ),
+            [Some(true), Some(true), Some(false)]
+        );
+
+        let dec = |s: &str| s.parse::<Decimal>().unwrap();
+        let data_type = DataType::Decimal {
+            precision: 10,
+            scale: 2,
+        };
+        let expr =
+            InListExpression::new(data_type, vec![Some(ScalarImpl::Decimal(dec("1.5")))]).unwrap();
+        let decimals = DecimalArray::from_slice(&[Some(dec("1.50")), Some(dec("1.05"))]);
+        assert_eq!(eval(&expr, decimals), [Some(true), Some(false)]);
+
+        let list = |items: &[Option<i32>]| ListValue::new(I32Array::from_slice(items).into());
+        let expr = InListExpression::new(
+            DataType::List(Box::new(DataType::Int32)),
+            vec![Some(ScalarImpl::List(list(&[Some(1), None])))],
+        )
+        .unwrap();
+        let lists = [list(&[Some(1), None]), list(&[Some(1)])];
+        let lists =
+            ListArray::from_slice(&[Some(lists[0].as_list_ref()), Some(lists[1].as_list_ref())]);
+        assert_eq!(eval(&expr, lists), [Some(true), Some(false)]);
+    }
+}
@@ -0,0 +1,432 @@
+use std::cell::RefCell;
+
+use anyhow::{bail, Result};
+use regex::Regex;
+use thiserror::Error;
+
+use crate::{BoolArray, StringArray};
+
+use super::vectorize::{TryBinaryExprFunc, TryTernaryExprFunc};
+
+/// Returned when a pattern of [`LikePattern`] cannot be compiled.
+#[derive(Error, Debug)]
+pub enum PatternError {
+    #[error("LIKE pattern must not end with escape character")]
+    TrailingEscape,
+    #[error("invalid escape string: {0:?}")]
+    InvalidEscape(String),
+    #[error("invalid regular expression: {0}")]
+    Regex(#[from] regex::Error),
+}
+
+/// The flavour of a pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LikeKind {
+    /// `LIKE`, where `%` matches any string and `_` any character
+    Like,
+    /// `ILIKE`, which is `LIKE` ignoring the case of characters
+    ILike,
+    /// `SIMILAR TO`, which is `LIKE` extended with the operators of regular expressions, e.g.
+    /// `|`, `*`, `+`, `?`, `{m,n}`, `(...)` and `[...]`
+    SimilarTo,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token {
+    Literal(char),
+    /// `_`
+    AnyChar,
+    /// `%`
+    AnyString,
+}
+
+enum Matcher {
+    Exact(String),
+    Prefix(String),
+    Suffix(String),
+    Contains(String),
+    Wildcard {
+        tokens: Vec<Token>,
+        ignore_case: bool,
+    },
+    Regex(Regex),
+}
+
+/// A compiled `LIKE`, `ILIKE` or `SIMILAR TO` pattern, which always matches the whole string.
+///
+/// `LIKE` patterns which are a literal, a prefix (`abc%`), a suffix (`%abc`) or a substring
+/// (`%abc%`) are matched by plain string search instead of the general matcher.
+pub struct LikePattern {
+    matcher: Matcher,
+}
+
+impl LikePattern {
+    /// Compile `pattern` of `kind`. A character following `escape` stands for itself, which is
+    /// `\` in SQL unless specified otherwise.
+    pub fn new(pattern: &str, kind: LikeKind, escape: Option<char>) -> Result<Self, PatternError> {
+        let matcher = match kind {
+            LikeKind::Like => Self::literal_matcher(tokenize(pattern, escape)?),
+            LikeKind::ILike => Matcher::Wildcard {
+                tokens: tokenize(pattern, escape)?,
+                ignore_case: true,
+            },
+            LikeKind::SimilarTo => Matcher::Regex(Regex::new(&similar_to_regex(pattern, escape)?)?),
+        };
+        Ok(Self { matcher })
+    }
+
+    /// Use plain string search if all wildcards are `%` at the start or end of the pattern.
+    fn literal_matcher(tokens: Vec<Token>) -> Matcher {
+        let starts = tokens.first() == Some(&Token::AnyString);
+        let ends = tokens.len() > 1 && tokens.last() == Some(&Token::AnyString);
+        let inner = &tokens[starts as usize..tokens.len() - ends as usize];
+        let literal = inner
+            .iter()
+            .map(|token| match token {
+                Token::Literal(c) => Some(c),
+                _ => None,
+            })
+            .collect::<Option<String>>();
+        match (literal, starts, ends) {
+            (Some(s), false, false) => Matcher::Exact(s),
+            (Some(s), false, true) => Matcher::Prefix(s),
+            (Some(s), true, false) => Matcher::Suffix(s),
+            (Some(s), true, true) => Matcher::Contains(s),
+            (None, _, _) => Matcher::Wildcard {
+                tokens,
+                ignore_case: false,
+            },
+        }
+    }
+
+    /// Returns if the whole of `s` matches the pattern.
+    pub fn is_match(&self, s: &str) -> bool {
+        match &self.matcher {
+            Matcher::Exact(p) => s == p,
+            Matcher::Prefix(p) => s.starts_with(p.as_str()),
+            Matcher::Suffix(p) => s.ends_with(p.as_str()),
+            Matcher::Contains(p) => s.contains(p.as_str()),
+            Matcher::Wildcard {
+                tokens,
+                ignore_case,
+            } => wildcard_match(tokens, s, *ignore_case),
+            Matcher::Regex(regex) => regex.is_match(s),
+        }
+    }
+}
+
+/// Split a `LIKE` pattern into tokens. Consecutive `%` are merged, since they match the same
+/// strings as one.
+fn tokenize(pattern: &str, escape: Option<char>) -> Result<Vec<Token>, PatternError> {
+    let mut tokens = vec![];
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if Some(c) == escape => {
+                Token::Literal(chars.next().ok_or(PatternError::TrailingEscape)?)
+            }
+            '%' if tokens.last() == Some(&Token::AnyString) => continue,
+            '%' => Token::AnyString,
+            '_' => Token::AnyChar,
+            c => Token::Literal(c),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Match `s` against `tokens`, backtracking to the last `%` on a mismatch. Earlier `%` never
+/// need to be revisited, so that it takes `O(len(s) * len(tokens))` time at most.
+fn wildcard_match(tokens: &[Token], s: &str, ignore_case: bool) -> bool {
+    let eq = |c: char, p: char| c == p || (ignore_case && c.to_lowercase().eq(p.to_lowercase()));
+    let (mut t, mut i) = (0, 0);
+    // the token after the last `%`, and the position where the string it matches ends
+    let mut backtrack = None;
+    loop {
+        let next = s[i..].chars().next();
+        match (tokens.get(t), next) {
+            (Some(Token::AnyString), _) => {
+                t += 1;
+                backtrack = Some((t, i));
+                continue;
+            }
+            (Some(Token::AnyChar), Some(c)) => {
+                (t, i) = (t + 1, i + c.len_utf8());
+                continue;
+            }
+            (Some(&Token::Literal(p)), Some(c)) if eq(c, p) => {
+                (t, i) = (t + 1, i + c.len_utf8());
+                continue;
+            }
+            (None, None) => return true,
+            _ => {}
+        }
+        // let the last `%` match one more character
+        match backtrack {
+            Some((bt, bi)) if bi < s.len() => {
+                let bi = bi + s[bi..].chars().next().unwrap().len_utf8();
+                backtrack = Some((bt, bi));
+                (t, i) = (bt, bi);
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// Translate a `SIMILAR TO` pattern into a regular expression of the whole string, as Postgres
+/// does. `.`, `^`, `$` and `\` are not special in `SIMILAR TO`, and are escaped.
+fn similar_to_regex(pattern: &str, escape: Option<char>) -> Result<String, PatternError> {
+    let mut regex = String::with_capacity(pattern.len() + 10);
+    regex.push_str("(?s)^(?:");
+    let mut chars = pattern.chars();
+    let mut in_brackets = false;
+    while let Some(c) = chars.next() {
+        match c {
+            c if Some(c) == escape => {
+                let c = chars.next().ok_or(PatternError::TrailingEscape)?;
+                regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+            }
+            _ if in_brackets => match c {
+                ']' => {
+                    in_brackets = false;
+                    regex.push(c);
+                }
+                // a character class like `[:alpha:]`, which is copied as is
+                '[' if chars.clone().next() == Some(':') => {
+                    regex.push(c);
+                    while let Some(c) = chars.next() {
+                        regex.push(c);
+                        if c == ':' && chars.clone().next() == Some(']') {
+                            regex.push(chars.next().unwrap());
+                            break;
+                        }
+                    }
+                }
+                // special in the brackets of `regex`, e.g. `&&` is an intersection
+                '\\' | '[' | '&' | '~' => {
+                    regex.push('\\');
+                    regex.push(c);
+                }
+                c => regex.push(c),
+            },
+            '[' => {
+                in_brackets = true;
+                regex.push(c);
+                if chars.clone().next() == Some('^') {
+                    regex.push(chars.next().unwrap());
+                }
+                // a `]` at the start of brackets does not end them
+                if chars.clone().next() == Some(']') {
+                    chars.next();
+                    regex.push_str("\\]");
+                }
+            }
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            '.' | '^' | '$' | '\\' => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            c => regex.push(c),
+        }
+    }
+    regex.push_str(")$");
+    Ok(regex)
+}
+
+/// Returns `i1 LIKE i2`, or `i1 LIKE i2 ESCAPE i3` with an escape string of at most one
+/// character, and similarly for `ILIKE` and `SIMILAR TO`. Fails if the pattern is invalid.
+///
+/// The last compiled pattern is kept, so that a constant pattern is compiled only once for each
+/// expression rather than for every row.
+pub struct ExprLike {
+    kind: LikeKind,
+    negated: bool,
+    compiled: RefCell<Option<(String, Option<char>, LikePattern)>>,
+}
+
+impl ExprLike {
+    /// `i1 LIKE i2`, `i1 ILIKE i2` or `i1 SIMILAR TO i2`
+    pub fn new(kind: LikeKind) -> Self {
+        Self {
+            kind,
+            negated: false,
+            compiled: RefCell::new(None),
+        }
+    }
+
+    /// `i1 NOT LIKE i2`, `i1 NOT ILIKE i2` or `i1 NOT SIMILAR TO i2`
+    pub fn negated(kind: LikeKind) -> Self {
+        Self {
+            negated: true,
+            ..Self::new(kind)
+        }
+    }
+
+    fn is_match(&self, s: &str, pattern: &str, escape: Option<char>) -> Result<bool> {
+        let mut compiled = self.compiled.borrow_mut();
+        match &*compiled {
+            Some((p, e, _)) if p == pattern && *e == escape => {}
+            _ => {
+                let compiled_pattern = LikePattern::new(pattern, self.kind, escape)?;
+                *compiled = Some((pattern.to_string(), escape, compiled_pattern));
+            }
+        }
+        let (_, _, compiled) = compiled.as_ref().unwrap();
+        Ok(compiled.is_match(s) != self.negated)
+    }
+}
+
+impl TryBinaryExprFunc<StringArray, StringArray, BoolArray> for ExprLike {
+    fn eval(&self, i1: &str, i2: &str) -> Result<bool> {
+        self.is_match(i1, i2, Some('\\'))
+    }
+}
+
+impl TryTernaryExprFunc<StringArray, StringArray, StringArray, BoolArray> for ExprLike {
+    fn eval(&self, i1: &str, i2: &str, i3: &str) -> Result<bool> {
+        let mut chars = i3.chars();
+        let escape = chars.next();
+        if chars.next().is_some() {
+            bail!(PatternError::InvalidEscape(i3.to_string()));
+        }
+        self.is_match(i1, i2, escape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::vectorize::TryBinaryExpression;
+    use crate::{Array, ArrayImpl, DataType, Datum, Expression, ScalarRefImpl};
+
+    fn like(s: &str, pattern: &str) -> bool {
+        LikePattern::new(pattern, LikeKind::Like, Some('\\'))
+            .unwrap()
+            .is_match(s)
+    }
+
+    #[test]
+    fn test_like() {
+        let matcher = |pattern| match LikePattern::new(pattern, LikeKind::Like, Some('\\'))
+            .unwrap()
+            .matcher
+        {
+            Matcher::Exact(_) => "exact",
+            Matcher::Prefix(_) => "prefix",
+            Matcher::Suffix(_) => "suffix",
+            Matcher::Contains(_) => "contains",
+            Matcher::Wildcard { .. } => "wildcard",
+            Matcher::Regex(_) => "regex",
+        };
+        assert_eq!(matcher("abc"), "exact");
+        assert_eq!(matcher("abc%%"), "prefix");
+        assert_eq!(matcher("%abc"), "suffix");
+        assert_eq!(matcher("%abc%"), "contains");
+        assert_eq!(matcher("a\\%c%"), "prefix");
+        assert_eq!(matcher("a_c"), "wildcard");
+
+        assert!(like("abc", "abc"));
+        assert!(!like("abcd", "abc"));
+        assert!(like("abcd", "ab%"));
+        assert!(like("xabc", "%bc"));
+        assert!(like("xabcx", "%ab%"));
+        assert!(like("", "%"));
+        assert!(like("a%c", "a\\%c"));
+        assert!(!like("abc", "a\\%c"));
+        assert!(like("héllo", "h_llo"));
+        assert!(!like("hllo", "h_llo"));
+        assert!(like("abcbcd", "a%bc_"));
+        assert!(like("axxbyyc", "a%b%c"));
+        assert!(!like("axxbyy", "a%b%c"));
+        assert!(like("a_b", "a\\_b"));
+        assert!(!like("aXb", "a\\_b"));
+        assert!(matches!(
+            LikePattern::new("ab\\", LikeKind::Like, Some('\\')),
+            Err(PatternError::TrailingEscape)
+        ));
+        let no_escape = LikePattern::new("a\\%", LikeKind::Like, None).unwrap();
+        assert!(no_escape.is_match("a\\bc"));
+        let custom = LikePattern::new("a#%%", LikeKind::Like, Some('#')).unwrap();
+        assert!(custom.is_match("a%bc"));
+        assert!(!custom.is_match("abc"));
+
+        let ilike = LikePattern::new("%ÉLL_", LikeKind::ILike, Some('\\')).unwrap();
+        assert!(ilike.is_match("Héllo"));
+        assert!(!ilike.is_match("Hello"));
+    }
+
+    #[test]
+    fn test_similar_to() {
+        let similar = |s: &str, pattern: &str| {
+            LikePattern::new(pattern, LikeKind::SimilarTo, Some('\\'))
+                .unwrap()
+                .is_match(s)
+        };
+        assert!(similar("abc", "abc"));
+        assert!(similar("abc", "a%"));
+        assert!(!similar("abc", "b%"));
+        assert!(similar("abc", "%(b|d)%"));
+        assert!(!similar("abc", "%(e|d)%"));
+        assert!(similar("aaab", "a+b"));
+        assert!(similar("a.c", "a.c"));
+        assert!(!similar("abc", "a.c"));
+        assert!(similar("a$", "a$"));
+        assert!(similar("b1", "[a-c][0-9]"));
+        assert!(similar("]", "[]a]"));
+        assert!(similar("x", "[^]a]"));
+        assert!(similar("&", "[a&&b]"));
+        assert!(similar("x1", "[[:alpha:]][[:digit:]]"));
+        assert!(similar("a%", "a\\%"));
+        assert!(!similar("ab", "a\\%"));
+        assert!(similar("a\nb", "a%b"));
+        assert!(matches!(
+            LikePattern::new("a(b", LikeKind::SimilarTo, None),
+            Err(PatternError::Regex(_))
+        ));
+    }
+
+    #[test]
+    fn test_like_expression() {
+        let strings: ArrayImpl =
+            StringArray::from_slice(&[Some("apple"), Some("banana"), None, Some("cherry")]).into();
+        let pattern = Datum::scalar(Some(ScalarRefImpl::String("%an%")), &DataType::String);
+        let expr = TryBinaryExpression::<StringArray, StringArray, BoolArray, _>::new(
+            ExprLike::negated(LikeKind::Like),
+        );
+        let result: BoolArray =
+            Expression::eval_datums(&expr, &[Datum::array(strings.clone()), pattern])
+                .unwrap()
+                .try_into()
+                .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(true), Some(false), None, Some(true)]
+        );
+
+        // patterns which differ by row
+        let patterns: ArrayImpl =
+            StringArray::from_slice(&[Some("A%"), Some("b%"), Some("%"), Some("c\\")]).into();
+        let expr = TryBinaryExpression::<StringArray, StringArray, BoolArray, _>::new(
+            ExprLike::new(LikeKind::ILike),
+        );
+        let error = expr.eval_expr(&[&strings, &patterns]).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "LIKE pattern must not end with escape character in row 3 with inputs (\"cherry\", \"c\\\\\")"
+        );
+        let result = expr
+            .eval_expr(&[&strings.slice(0, 3), &patterns.slice(0, 3)])
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Bool(true)));
+        assert_eq!(result.get(1), Some(ScalarRefImpl::Bool(true)));
+        assert!(result.get(2).is_none());
+
+        let func = ExprLike::new(LikeKind::Like);
+        let eval = |s, p, e| TryTernaryExprFunc::eval(&func, s, p, e);
+        assert!(eval("10%", "10!%", "!").unwrap());
+        assert!(!eval("100", "10!%", "!").unwrap());
+        assert!(eval("a\\b", "a\\b", "").unwrap());
+        assert!(eval("a", "a", "!!").is_err());
+    }
+}
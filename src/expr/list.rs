@@ -0,0 +1,244 @@
+use anyhow::{anyhow, Result};
+
+use crate::{
+    Array, ArrayBuilder, ArrayBuilderImpl, ArrayImpl, BoolArray, BoolArrayBuilder, Expression,
+    I32Array, I32ArrayBuilder, ListArray,
+};
+
+/// Returns the number of items of each list.
+pub struct ArrayLength;
+
+impl ArrayLength {
+    /// Evaluate the length of each list.
+    pub fn eval_batch(&self, array: &ListArray) -> I32Array {
+        let mut builder = I32ArrayBuilder::with_capacity(array.len());
+        for list in array.iter() {
+            builder.push(list.map(|list| list.len() as i32));
+        }
+        builder.finish()
+    }
+}
+
+impl Expression for ArrayLength {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        if data.len() != 1 {
+            return Err(anyhow!("Except one input for ArrayLength"));
+        }
+        Ok(self.eval_batch(data[0].try_into()?).into())
+    }
+}
+
+/// Returns `array_contains(list, value)` for a list and a value of the item type.
+///
+/// The result is `true` if the list contains the value. Otherwise, it is `NULL` if the list, the
+/// value or any item of the list is `NULL`, and `false` if none is.
+pub struct ArrayContains;
+
+impl ArrayContains {
+    /// Evaluate whether each list contains the value in the same row.
+    pub fn eval_batch(&self, lists: &ListArray, values: &ArrayImpl) -> Result<BoolArray> {
+        if lists.value_type() != values.data_type() {
+            return Err(anyhow!(
+                "Cannot search {} in {}",
+                values.data_type(),
+                lists.data_type()
+            ));
+        }
+        assert_eq!(lists.len(), values.len(), "array length mismatch");
+        let mut builder = BoolArrayBuilder::with_capacity(lists.len());
+        for (idx, list) in lists.iter().enumerate() {
+            let result = match (list, values.get(idx)) {
+                (Some(list), Some(value)) => {
+                    let mut has_null = false;
+                    let mut found = false;
+                    for item in list.iter() {
+                        match item {
+                            Some(item) if item == value => {
+                                found = true;
+                                break;
+                            }
+                            Some(_) => {}
+                            None => has_null = true,
+                        }
+                    }
+                    (found || !has_null).then_some(found)
+                }
+                _ => None,
+            };
+            builder.push(result);
+        }
+        Ok(builder.finish())
+    }
+}
+
+impl Expression for ArrayContains {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        if data.len() != 2 {
+            return Err(anyhow!("Except two inputs for ArrayContains"));
+        }
+        Ok(self.eval_batch(data[0].try_into()?, data[1])?.into())
+    }
+}
+
+/// Returns `list[idx]`, i.e. the item at the 1-based position `idx` of each list. The result is
+/// `NULL` if the position is out of the bounds of the list.
+pub struct ArrayElement;
+
+impl ArrayElement {
+    /// Evaluate the item of each list at the position in the same row.
+    pub fn eval_batch(&self, lists: &ListArray, indices: &I32Array) -> ArrayImpl {
+        assert_eq!(lists.len(), indices.len(), "array length mismatch");
+        let mut builder = ArrayBuilderImpl::new(&lists.value_type(), lists.len());
+        for (list, idx) in lists.iter().zip(indices.iter()) {
+            let item = match (list, idx) {
+                (Some(list), Some(idx)) if idx >= 1 && idx as usize <= list.len() => {
+                    list.get(idx as usize - 1)
+                }
+                _ => None,
+            };
+            builder.push(item);
+        }
+        builder.finish()
+    }
+}
+
+impl Expression for ArrayElement {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        if data.len() != 2 {
+            return Err(anyhow!("Except two inputs for ArrayElement"));
+        }
+        Ok(self.eval_batch(data[0].try_into()?, data[1].try_into()?))
+    }
+}
+
+/// Expands each list into one row per item.
+///
+/// Unlike other expressions, the output does not have the same length as the input. Null and
+/// empty lists produce no rows.
+pub struct Unnest;
+
+impl Unnest {
+    /// Evaluate the items of all lists, together with the index of the list each item comes
+    /// from. The items share the buffers with `lists`.
+    pub fn eval_batch(&self, lists: &ListArray) -> (ArrayImpl, Vec<usize>) {
+        let mut rows = Vec::new();
+        for (idx, list) in lists.iter().enumerate() {
+            let len = list.map_or(0, |list| list.len());
+            rows.extend(std::iter::repeat_n(idx, len));
+        }
+        (lists.flat_values(), rows)
+    }
+}
+
+impl Expression for Unnest {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        if data.len() != 1 {
+            return Err(anyhow!("Except one input for Unnest"));
+        }
+        Ok(self.eval_batch(data[0].try_into()?).0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DataType, ListArrayBuilder, ListValue, ScalarRef, ScalarRefImpl, StringArray};
+
+    fn tags() -> ListArray {
+        let list = |items: &[Option<&str>]| ListValue::new(StringArray::from_slice(items).into());
+        let lists = [
+            Some(list(&[Some("a"), Some("b")])),
+            None,
+            Some(list(&[])),
+            Some(list(&[Some("c"), None, Some("a")])),
+        ];
+        let mut builder = ArrayBuilderImpl::new(&"list(string)".parse().unwrap(), 4);
+        for list in &lists {
+            builder.push(list.as_ref().map(|l| ScalarRefImpl::List(l.as_list_ref())));
+        }
+        builder.finish().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_list_array() {
+        let lists = tags();
+        assert_eq!(
+            lists.data_type(),
+            DataType::List(Box::new(DataType::String))
+        );
+        let sliced = lists.slice(1, 3);
+        assert!(sliced.get(0).is_none());
+        let list = sliced.get(2).unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.get(0), Some(ScalarRefImpl::String("c")));
+        assert_eq!(list.get(1), None);
+        assert_eq!(
+            list.to_owned_scalar(),
+            ListValue::new(StringArray::from_slice(&[Some("c"), None, Some("a")]).into())
+        );
+
+        let rebuilt = ListArray::from_slice(&sliced.iter().collect::<Vec<_>>());
+        assert_eq!(rebuilt.data_type(), lists.data_type());
+        assert!(rebuilt.iter().eq(sliced.iter()));
+
+        // a list of another item type is rejected
+        let ints = ListValue::new(crate::I32Array::from_slice(&[Some(1)]).into());
+        let mut builder = ListArrayBuilder::with_type(&lists.data_type(), 1);
+        assert_eq!(
+            builder
+                .try_push(Some(ints.as_list_ref()))
+                .unwrap_err()
+                .to_string(),
+            "List item type mismatch: expected string, get int32"
+        );
+        assert_eq!(builder.finish().len(), 0);
+    }
+
+    #[test]
+    fn test_list_functions() {
+        let lists: ArrayImpl = tags().into();
+
+        let result: I32Array = ArrayLength
+            .eval_expr(&[&lists])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(2), None, Some(0), Some(3)]
+        );
+
+        let values = StringArray::from_slice(&[Some("b"), Some("a"), Some("a"), Some("b")]);
+        let result: BoolArray = ArrayContains
+            .eval_expr(&[&lists, &values.into()])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(true), None, Some(false), None]
+        );
+        assert!(ArrayContains
+            .eval_expr(&[&lists, &I32Array::from_slice(&[None; 4]).into()])
+            .is_err());
+
+        let indices = I32Array::from_slice(&[Some(2), Some(1), Some(1), Some(3)]);
+        let result: StringArray = ArrayElement
+            .eval_expr(&[&lists, &indices.into()])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some("b"), None, None, Some("a")]
+        );
+
+        let (values, rows) = Unnest.eval_batch(&tags().slice(1, 3));
+        let values: StringArray = values.try_into().unwrap();
+        assert_eq!(
+            values.iter().collect::<Vec<_>>(),
+            [Some("c"), None, Some("a")]
+        );
+        assert_eq!(rows, [2, 2, 2]);
+    }
+}
@@ -0,0 +1,323 @@
+use anyhow::{anyhow, Result};
+use bitvec::slice::BitSlice;
+use bitvec::vec::BitVec;
+
+use crate::{Array, ArrayImpl, BoolArray, Datum, Expression};
+
+const WORD_BITS: usize = usize::BITS as usize;
+
+/// The value and validity of `WORD_BITS` rows.
+type Word = (usize, usize);
+
+/// Copies `bits` into words, so that bit `i` is bit `i % WORD_BITS` of word `i / WORD_BITS`.
+fn to_words(bits: &BitSlice) -> Vec<usize> {
+    let mut bits = BitVec::from_bitslice(bits);
+    bits.force_align();
+    bits.into_vec()
+}
+
+fn from_words(words: Vec<usize>, len: usize) -> BitVec {
+    let mut bits = BitVec::from_vec(words);
+    bits.truncate(len);
+    bits
+}
+
+/// A boolean input packed into words, so that logic functions are evaluated on `WORD_BITS` rows at
+/// a time instead of row by row. The value of a `NULL` row is always `false`.
+struct Words {
+    values: Vec<usize>,
+    validity: Vec<usize>,
+}
+
+impl Words {
+    fn from_array(array: &BoolArray) -> Self {
+        let validity = to_words(array.null_bitmap());
+        let mut values = to_words(array.values());
+        values.iter_mut().zip(&validity).for_each(|(v, m)| *v &= m);
+        Self { values, validity }
+    }
+
+    /// Packs an input of `len` rows, repeating a constant if necessary.
+    fn from_datum(datum: &Datum, len: usize) -> Result<Self> {
+        let array: &BoolArray = datum.as_array().try_into()?;
+        if !datum.is_scalar() {
+            return Ok(Self::from_array(array));
+        }
+        let fill = |bit: bool| vec![if bit { !0 } else { 0 }; len.div_ceil(WORD_BITS)];
+        let value = array.get(0);
+        Ok(Self {
+            values: fill(value == Some(true)),
+            validity: fill(value.is_some()),
+        })
+    }
+
+    fn into_array(self, len: usize) -> BoolArray {
+        BoolArray::from_bits(from_words(self.values, len), from_words(self.validity, len))
+    }
+
+    fn map(&self, kernel: fn(Word) -> Word) -> Self {
+        let (values, validity) = self
+            .values
+            .iter()
+            .zip(&self.validity)
+            .map(|(&v, &m)| kernel((v, m)))
+            .unzip();
+        Self { values, validity }
+    }
+
+    fn zip(&self, other: &Self, kernel: fn(Word, Word) -> Word) -> Self {
+        let (values, validity) = (self.values.iter().zip(&self.validity))
+            .zip(other.values.iter().zip(&other.validity))
+            .map(|((&v1, &m1), (&v2, &m2))| kernel((v1, m1), (v2, m2)))
+            .unzip();
+        Self { values, validity }
+    }
+}
+
+/// `FALSE` if any input is `FALSE`, even if the other one is `NULL`.
+fn and((v1, m1): Word, (v2, m2): Word) -> Word {
+    let falses = (m1 & !v1) | (m2 & !v2);
+    (v1 & v2, (m1 & m2) | falses)
+}
+
+/// `TRUE` if any input is `TRUE`, even if the other one is `NULL`.
+fn or((v1, m1): Word, (v2, m2): Word) -> Word {
+    (v1 | v2, (m1 & m2) | v1 | v2)
+}
+
+fn xor((v1, m1): Word, (v2, m2): Word) -> Word {
+    let valid = m1 & m2;
+    ((v1 ^ v2) & valid, valid)
+}
+
+fn not((v, m): Word) -> Word {
+    (!v & m, m)
+}
+
+fn is_true((v, _): Word) -> Word {
+    (v, !0)
+}
+
+fn is_false((v, m): Word) -> Word {
+    (!v & m, !0)
+}
+
+fn is_unknown((_, m): Word) -> Word {
+    (!m, !0)
+}
+
+/// A logic function on one [`BoolArray`], i.e. `NOT a`, `a IS TRUE`, `a IS FALSE` or
+/// `a IS UNKNOWN`. Only `NOT` may return `NULL`.
+pub struct LogicUnaryExpression {
+    kernel: fn(Word) -> Word,
+}
+
+impl LogicUnaryExpression {
+    /// `NOT a`
+    pub fn not() -> Self {
+        Self { kernel: not }
+    }
+
+    /// `a IS TRUE`
+    pub fn is_true() -> Self {
+        Self { kernel: is_true }
+    }
+
+    /// `a IS FALSE`
+    pub fn is_false() -> Self {
+        Self { kernel: is_false }
+    }
+
+    /// `a IS UNKNOWN`, i.e. `a IS NULL`
+    pub fn is_unknown() -> Self {
+        Self { kernel: is_unknown }
+    }
+
+    /// Evaluate the function on an array.
+    pub fn eval_batch(&self, input: &BoolArray) -> BoolArray {
+        Words::from_array(input)
+            .map(self.kernel)
+            .into_array(input.len())
+    }
+}
+
+impl Expression for LogicUnaryExpression {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        match data {
+            [i1] => Ok(self.eval_batch((*i1).try_into()?).into()),
+            _ => Err(anyhow!("Except 1 input(s) for LogicUnaryExpression")),
+        }
+    }
+
+    fn eval_datums(&self, data: &[Datum]) -> Result<ArrayImpl> {
+        match data {
+            [i1] => {
+                let len = i1.num_rows().unwrap_or(1);
+                Ok(Words::from_datum(i1, len)?
+                    .map(self.kernel)
+                    .into_array(len)
+                    .into())
+            }
+            _ => Err(anyhow!("Except 1 input(s) for LogicUnaryExpression")),
+        }
+    }
+}
+
+/// A logic function on two [`BoolArray`]s, i.e. `a AND b`, `a OR b` or `a XOR b`.
+pub struct LogicBinaryExpression {
+    kernel: fn(Word, Word) -> Word,
+}
+
+impl LogicBinaryExpression {
+    /// `a AND b`
+    pub fn and() -> Self {
+        Self { kernel: and }
+    }
+
+    /// `a OR b`
+    pub fn or() -> Self {
+        Self { kernel: or }
+    }
+
+    /// `a XOR b`, which is `NULL` if any input is `NULL`
+    pub fn xor() -> Self {
+        Self { kernel: xor }
+    }
+
+    /// Evaluate the function on two inputs, of which some may be constants.
+    pub fn eval_datums(&self, i1: &Datum, i2: &Datum) -> Result<ArrayImpl> {
+        let len = match (i1.num_rows(), i2.num_rows()) {
+            (Some(l1), Some(l2)) if l1 != l2 => {
+                return Err(anyhow!("Inputs have different lengths"));
+            }
+            (l1, l2) => l1.or(l2).unwrap_or(1),
+        };
+        let (i1, i2) = (Words::from_datum(i1, len)?, Words::from_datum(i2, len)?);
+        Ok(i1.zip(&i2, self.kernel).into_array(len).into())
+    }
+
+    /// Evaluate the function on two arrays of the same length.
+    pub fn eval_batch(&self, i1: &BoolArray, i2: &BoolArray) -> Result<BoolArray> {
+        if i1.len() != i2.len() {
+            return Err(anyhow!("Inputs have different lengths"));
+        }
+        let (w1, w2) = (Words::from_array(i1), Words::from_array(i2));
+        Ok(w1.zip(&w2, self.kernel).into_array(i1.len()))
+    }
+}
+
+impl Expression for LogicBinaryExpression {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        match data {
+            [i1, i2] => Ok(self
+                .eval_batch((*i1).try_into()?, (*i2).try_into()?)?
+                .into()),
+            _ => Err(anyhow!("Except 2 input(s) for LogicBinaryExpression")),
+        }
+    }
+
+    fn eval_datums(&self, data: &[Datum]) -> Result<ArrayImpl> {
+        match data {
+            [i1, i2] => self.eval_datums(i1, i2),
+            _ => Err(anyhow!("Except 2 input(s) for LogicBinaryExpression")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DataType, ScalarRefImpl};
+
+    /// All nine combinations of `TRUE`, `FALSE` and `NULL`.
+    fn truth_table() -> (BoolArray, BoolArray) {
+        let values = [Some(true), Some(false), None];
+        let (left, right): (Vec<_>, Vec<_>) = values
+            .iter()
+            .flat_map(|&l| values.iter().map(move |&r| (l, r)))
+            .unzip();
+        (BoolArray::from_slice(&left), BoolArray::from_slice(&right))
+    }
+
+    #[test]
+    fn test_kleene_logic() {
+        let (left, right) = truth_table();
+        let (t, f, n) = (Some(true), Some(false), None);
+        let eval = |expr: LogicBinaryExpression| -> Vec<_> {
+            expr.eval_batch(&left, &right).unwrap().iter().collect()
+        };
+        assert_eq!(
+            eval(LogicBinaryExpression::and()),
+            [t, f, n, f, f, f, n, f, n]
+        );
+        assert_eq!(
+            eval(LogicBinaryExpression::or()),
+            [t, t, t, t, f, n, t, n, n]
+        );
+        assert_eq!(
+            eval(LogicBinaryExpression::xor()),
+            [f, t, n, t, f, n, n, n, n]
+        );
+
+        let input = BoolArray::from_slice(&[t, f, n]);
+        let eval =
+            |expr: LogicUnaryExpression| -> Vec<_> { expr.eval_batch(&input).iter().collect() };
+        assert_eq!(eval(LogicUnaryExpression::not()), [f, t, n]);
+        assert_eq!(eval(LogicUnaryExpression::is_true()), [t, f, f]);
+        assert_eq!(eval(LogicUnaryExpression::is_false()), [f, t, f]);
+        assert_eq!(eval(LogicUnaryExpression::is_unknown()), [f, f, t]);
+    }
+
+    #[test]
+    fn test_unaligned_slices() {
+        // slices starting in the middle of a word, and spanning several words
+        let item = |i: usize| match i % 7 {
+            0 | 3 => None,
+            r => Some(r % 2 == 0),
+        };
+        let array = BoolArray::from_slice(&(0..300).map(item).collect::<Vec<_>>());
+        let (left, right) = (array.slice(5, 200), array.slice(61, 200));
+        let result = LogicBinaryExpression::and()
+            .eval_batch(&left, &right)
+            .unwrap();
+        for (i, value) in result.iter().enumerate() {
+            let expected = match (item(i + 5), item(i + 61)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            };
+            assert_eq!(value, expected, "row {}", i);
+        }
+
+        let result = LogicUnaryExpression::not().eval_batch(&right);
+        assert_eq!(result.len(), 200);
+        assert!((0..200).all(|i| result.get(i) == item(i + 61).map(|v| !v)));
+    }
+
+    #[test]
+    fn test_constants() {
+        let array = Datum::array(BoolArray::from_slice(&[Some(true), None, Some(false)]).into());
+        let null = Datum::scalar(None, &DataType::Bool);
+        let result = LogicBinaryExpression::or()
+            .eval_datums(&null, &array)
+            .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Bool(true)));
+        assert!(result.get(1).is_none());
+        assert!(result.get(2).is_none());
+
+        let falsy = Datum::scalar(Some(ScalarRefImpl::Bool(false)), &DataType::Bool);
+        let result = LogicBinaryExpression::and()
+            .eval_datums(&array, &falsy)
+            .unwrap();
+        assert!((0..3).all(|i| result.get(i) == Some(ScalarRefImpl::Bool(false))));
+
+        let result = Expression::eval_datums(&LogicUnaryExpression::is_unknown(), &[null]).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Bool(true)));
+
+        let ints = Datum::scalar(Some(ScalarRefImpl::Int32(1)), &DataType::Int32);
+        assert!(LogicBinaryExpression::xor()
+            .eval_datums(&array, &ints)
+            .is_err());
+    }
+}
@@ -2,75 +2,865 @@
 
 use std::marker::PhantomData;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use thiserror::Error;
 
-use crate::{ArrayImpl, BoolArray, I32Array, StringArray};
+use crate::macros::{for_all_arith_combinations, for_all_cmp_combinations};
+use crate::{
+    ArrayImpl, BinaryArray, BoolArray, ColumnRef, DataChunk, DataType, Date32Array, DecimalArray,
+    F32Array, F64Array, I16Array, I32Array, I64Array, IntervalArray, StringArray, Time64Array,
+    TimestampArray,
+};
 
 use crate::expr::{
-    cmp::{ExprCmpEq, ExprCmpGe, ExprCmpLe, ExprCmpNe},
-    string::ExprStrContains,
-    vectorize::BinaryExpression,
+    arithmetic::{ExprAdd, ExprDivide, ExprModulo, ExprMultiply, ExprNegate, ExprSubtract},
+    binary::{ExprDecode, ExprEncode},
+    cmp::{ExprCmpEq, ExprCmpGe, ExprCmpGt, ExprCmpLe, ExprCmpLt, ExprCmpNe},
+    like::ExprLike,
+    null::{ExprIsDistinctFrom, ExprIsNotDistinctFrom, ExprNullIf},
+    regexp::{ExprRegexpCount, ExprRegexpIsMatch, ExprRegexpReplace, RegexpListExpression},
+    string::{
+        ExprCharLength, ExprEndsWith, ExprLPad, ExprLTrim, ExprLower, ExprPosition, ExprRPad,
+        ExprRTrim, ExprRepeat, ExprReplace, ExprReverse, ExprSplitPart, ExprStartsWith,
+        ExprStrContains, ExprStrSubstr, ExprTrim, ExprUpper,
+    },
+    temporal::{ExprAddInterval, ExprDateTrunc, ExprExtract, ExprTimestampSub},
 };
 
+mod arithmetic;
+mod between;
+mod binary;
+mod case;
+mod cast;
 mod cmp;
+mod datum;
+mod field;
+mod in_list;
+mod like;
+mod list;
+mod logic;
+mod null;
+mod regexp;
 mod string;
+mod temporal;
+mod tree;
 mod vectorize;
 
+pub use arithmetic::{ArithmeticError, Numeric};
+pub use between::BetweenExpression;
+pub use binary::{
+    decode, encode, length, substr, BinaryFormat, DecodeError, ExprBinaryLength, ExprSubstr,
+};
+pub use case::{CaseError, CaseExpression};
+pub use cast::{can_cast, cast, cast_context, Cast, CastContext, NumericCast};
+pub use cmp::{TotalHash, TotalOrd};
+pub use datum::Datum;
+pub use field::GetField;
+pub use in_list::InListExpression;
+pub use like::{LikeKind, LikePattern, PatternError};
+pub use list::{ArrayContains, ArrayElement, ArrayLength, Unnest};
+pub use logic::{LogicBinaryExpression, LogicUnaryExpression};
+pub use null::{Coalesce, IsNullExpression};
+pub use regexp::RegexpError;
+pub use string::{Concat, ConcatWs};
+pub use temporal::{date_trunc, extract, DateTimeField};
+pub use tree::{ExprNode, FunctionCall, InputRef, Literal};
+pub use vectorize::{
+    BinaryExprFunc, BinaryExpression, BinaryStringExprFunc, BinaryStringExpression, ErrorPolicy,
+    NullableBinaryExprFunc, NullableBinaryExpression, NullableTernaryExprFunc,
+    NullableTernaryExpression, NullableUnaryExprFunc, NullableUnaryExpression, RowError, RowErrors,
+    TernaryExprFunc, TernaryExpression, TernaryStringExprFunc, TernaryStringExpression,
+    TryBinaryExprFunc, TryBinaryExpression, TryTernaryExprFunc, TryTernaryExpression,
+    TryUnaryExprFunc, TryUnaryExpression, UnaryExprFunc, UnaryExpression, UnaryStringExprFunc,
+    UnaryStringExpression,
+};
+
 /// A trait over all expressions -- unary, binary, etc.
 pub trait Expression {
     /// Evaluate an expression with run-time number of [`ArrayImpl`]s.
     fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl>;
+
+    /// Evaluate an expression on the columns of `chunk` referenced by `inputs`. Invisible rows
+    /// are evaluated as well, so that the result has [`DataChunk::num_rows`] rows.
+    fn eval_chunk(&self, chunk: &DataChunk, inputs: &[ColumnRef]) -> Result<ArrayImpl> {
+        let data = inputs
+            .iter()
+            .map(|input| {
+                chunk
+                    .column(input)
+                    .ok_or_else(|| anyhow!("No column {:?} in {}", input, chunk.schema()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.eval_expr(&data)
+    }
+
+    /// Evaluate an expression with run-time number of [`Datum`]s, of which some may be constants.
+    /// The result has as many rows as the arrays, or one row if all inputs are constants.
+    ///
+    /// By default, constants are expanded to the length of the arrays before calling
+    /// [`Expression::eval_expr`]. Vectorized expressions read them without expanding.
+    fn eval_datums(&self, data: &[Datum]) -> Result<ArrayImpl> {
+        let len = data.iter().find_map(Datum::num_rows).unwrap_or(1);
+        let arrays = data.iter().map(|d| d.to_array(len)).collect::<Vec<_>>();
+        self.eval_expr(&arrays.iter().collect::<Vec<_>>())
+    }
+}
+
+/// Dispatches `nullif` to a [`NullableBinaryExpression`] over the concrete array types of `$i1`
+/// and `$i2`, whose result is of the type of `$i1`. Evaluates to `None` if the two types cannot be
+/// compared.
+macro_rules! impl_nullif_dispatch {
+    ([$i1:ident, $i2:ident], $({ $Left:ident, $LeftArray:ty, $Right:ident, $RightArray:ty, $CastArray:ty }),*) => {
+        match ($i1, $i2) {
+            $(
+                (DataType::$Left { .. }, DataType::$Right { .. }) => Some(Box::new(
+                    NullableBinaryExpression::<$LeftArray, $RightArray, $LeftArray, _>::new(
+                        ExprNullIf::<_, _, $CastArray>(PhantomData),
+                    )
+                    .with_return_type($i1.clone()),
+                ) as Box<dyn Expression>),
+            )*
+            _ => None,
+        }
+    };
+}
+
+/// Evaluates to the result type of arithmetic on `$i1` and `$i2`, or `None` if there is none.
+macro_rules! impl_arith_return_type {
+    ([$i1:ident, $i2:ident], $({ $Left:ident, $LeftArray:ty, $Right:ident, $RightArray:ty, $Cast:ident, $CastArray:ty }),*) => {
+        match ($i1, $i2) {
+            $(
+                (DataType::$Left, DataType::$Right) => Some(DataType::$Cast),
+            )*
+            _ => None,
+        }
+    };
 }
 
 /// All supported expression functions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ExpressionFunc {
+    /// `a < b`
+    CmpLt,
+    /// `a <= b`
     CmpLe,
+    /// `a > b`
+    CmpGt,
+    /// `a >= b`
     CmpGe,
+    /// `a = b`
     CmpEq,
+    /// `a <> b`
     CmpNe,
     StrContains,
+    /// Date or timestamp plus interval
+    AddInterval,
+    /// Difference of two dates or timestamps as an interval
+    TimestampSub,
+    /// `extract(field, ts)`
+    Extract,
+    /// `date_trunc(field, ts)`
+    DateTrunc,
+    /// `substr(s, start[, count])` of strings or bytes
+    Substr,
+    /// `encode(bytes, format)`
+    Encode,
+    /// `decode(string, format)`
+    Decode,
+    /// `a + b`
+    Add,
+    /// `a - b`
+    Subtract,
+    /// `a * b`
+    Multiply,
+    /// `a / b`, truncated towards zero for integers
+    Divide,
+    /// `a % b`, with the sign of `a`
+    Modulo,
+    /// `-a`
+    Negate,
+    /// `a IS DISTINCT FROM b`
+    IsDistinctFrom,
+    /// `a IS NOT DISTINCT FROM b`
+    IsNotDistinctFrom,
+    /// `nullif(a, b)`
+    NullIf,
+    /// `a AND b` in three-valued logic
+    And,
+    /// `a OR b` in three-valued logic
+    Or,
+    /// `a XOR b`
+    Xor,
+    /// `NOT a` in three-valued logic
+    Not,
+    /// `a IS TRUE`
+    IsTrue,
+    /// `a IS FALSE`
+    IsFalse,
+    /// `a IS UNKNOWN`
+    IsUnknown,
+    /// `a IS NULL`
+    IsNull,
+    /// `a IS NOT NULL`
+    IsNotNull,
+    /// `length(s)`, in characters for strings or in bytes for bytes
+    Length,
+    /// `char_length(s)`
+    CharLength,
+    /// `upper(s)`
+    Upper,
+    /// `lower(s)`
+    Lower,
+    /// `btrim(s[, chars])`
+    Trim,
+    /// `ltrim(s[, chars])`
+    LTrim,
+    /// `rtrim(s[, chars])`
+    RTrim,
+    /// `replace(s, from, to)`
+    Replace,
+    /// `starts_with(s, prefix)`
+    StartsWith,
+    /// `ends_with(s, suffix)`
+    EndsWith,
+    /// `strpos(s, substring)`, i.e. `position(substring in s)`
+    Position,
+    /// `split_part(s, delimiter, n)`
+    SplitPart,
+    /// `repeat(s, n)`
+    Repeat,
+    /// `reverse(s)`
+    Reverse,
+    /// `lpad(s, len[, fill])`
+    LPad,
+    /// `rpad(s, len[, fill])`
+    RPad,
+    /// `s LIKE pattern [ESCAPE escape]`
+    Like,
+    /// `s NOT LIKE pattern [ESCAPE escape]`
+    NotLike,
+    /// `s ILIKE pattern [ESCAPE escape]`
+    ILike,
+    /// `s NOT ILIKE pattern [ESCAPE escape]`
+    NotILike,
+    /// `s SIMILAR TO pattern [ESCAPE escape]`
+    SimilarTo,
+    /// `s NOT SIMILAR TO pattern [ESCAPE escape]`
+    NotSimilarTo,
+    /// `s ~ pattern`
+    RegexpIsMatch,
+    /// `regexp_match(s, pattern[, flags])`
+    RegexpMatch,
+    /// `regexp_replace(s, pattern, replacement[, flags])`
+    RegexpReplace,
+    /// `regexp_count(s, pattern[, flags])`
+    RegexpCount,
+    /// `regexp_split_to_array(s, pattern[, flags])`
+    RegexpSplitToArray,
+}
+
+impl ExpressionFunc {
+    /// Data type of the result of the function on inputs of the given types, which must be
+    /// supported by [`build_unary_expression`], [`build_binary_expression`] or
+    /// [`build_ternary_expression`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number or types of the inputs are not supported by the function.
+    pub fn return_type(&self, inputs: &[&DataType]) -> DataType {
+        match self {
+            Self::CmpLt
+            | Self::CmpLe
+            | Self::CmpGt
+            | Self::CmpGe
+            | Self::CmpEq
+            | Self::CmpNe
+            | Self::StrContains
+            | Self::IsDistinctFrom
+            | Self::IsNotDistinctFrom
+            | Self::And
+            | Self::Or
+            | Self::Xor
+            | Self::Not
+            | Self::IsTrue
+            | Self::IsFalse
+            | Self::IsUnknown
+            | Self::IsNull
+            | Self::IsNotNull
+            | Self::StartsWith
+            | Self::EndsWith
+            | Self::Like
+            | Self::NotLike
+            | Self::ILike
+            | Self::NotILike
+            | Self::SimilarTo
+            | Self::NotSimilarTo
+            | Self::RegexpIsMatch => DataType::Bool,
+            Self::AddInterval | Self::DateTrunc => inputs
+                .iter()
+                .find(|t| matches!(t, DataType::Timestamp { .. }))
+                .map_or(DataType::Timestamp { tz: None }, |t| (*t).clone()),
+            Self::TimestampSub => DataType::Interval,
+            Self::Extract => DataType::Float64,
+            Self::Substr => inputs[0].clone(),
+            Self::Encode => inputs[1].clone(),
+            Self::Decode => DataType::Binary,
+            Self::Add | Self::Subtract | Self::Multiply | Self::Divide | Self::Modulo => {
+                let (i1, i2) = (inputs[0], inputs[1]);
+                for_all_arith_combinations! { impl_arith_return_type, i1, i2 }
+                    .expect("unsupported types of arithmetic")
+            }
+            Self::Negate | Self::NullIf => inputs[0].clone(),
+            Self::Length | Self::CharLength | Self::Position | Self::RegexpCount => DataType::Int32,
+            Self::Upper
+            | Self::Lower
+            | Self::Trim
+            | Self::LTrim
+            | Self::RTrim
+            | Self::Replace
+            | Self::SplitPart
+            | Self::Repeat
+            | Self::Reverse
+            | Self::LPad
+            | Self::RPad
+            | Self::RegexpReplace => DataType::String,
+            Self::RegexpMatch | Self::RegexpSplitToArray => {
+                DataType::List(Box::new(DataType::String))
+            }
+        }
+    }
+}
+
+impl ExpressionFunc {
+    /// The kernel of a pattern matching function, e.g. `NOT ILIKE`.
+    fn like_func(&self) -> Option<ExprLike> {
+        let (kind, negated) = match self {
+            Self::Like => (LikeKind::Like, false),
+            Self::NotLike => (LikeKind::Like, true),
+            Self::ILike => (LikeKind::ILike, false),
+            Self::NotILike => (LikeKind::ILike, true),
+            Self::SimilarTo => (LikeKind::SimilarTo, false),
+            Self::NotSimilarTo => (LikeKind::SimilarTo, true),
+            _ => return None,
+        };
+        Some(if negated {
+            ExprLike::negated(kind)
+        } else {
+            ExprLike::new(kind)
+        })
+    }
+}
+
+/// Returned by [`build_unary_expression`], [`build_binary_expression`] and
+/// [`build_ternary_expression`] if a function is not implemented for the given input types.
+#[derive(Error, Debug)]
+#[error("Unsupported function {0:?} on input types {1:?}")]
+pub struct UnsupportedFunction(ExpressionFunc, Vec<DataType>);
+
+/// Dispatches a comparison function to `$Expression`, e.g. a [`BinaryExpression`], over the
+/// concrete array types of `$i1` and `$i2`. Evaluates to `None` if the two types cannot be
+/// compared.
+macro_rules! impl_cmp_dispatch {
+    ([$i1:ident, $i2:ident, $Expression:ident, $Func:ident], $({ $Left:ident, $LeftArray:ty, $Right:ident, $RightArray:ty, $CastArray:ty }),*) => {
+        match ($i1, $i2) {
+            $(
+                (DataType::$Left { .. }, DataType::$Right { .. }) => Some(Box::new(
+                    $Expression::<$LeftArray, $RightArray, BoolArray, _>::new(
+                        $Func::<_, _, $CastArray>(PhantomData),
+                    ),
+                ) as Box<dyn Expression>),
+            )*
+            _ => None,
+        }
+    };
+}
+
+/// Dispatches an arithmetic function to a [`TryBinaryExpression`] over the concrete array types of
+/// `$i1` and `$i2`. Evaluates to `None` if the function does not apply to the two types.
+macro_rules! impl_arith_dispatch {
+    ([$i1:ident, $i2:ident, $Func:ident], $({ $Left:ident, $LeftArray:ty, $Right:ident, $RightArray:ty, $Cast:ident, $CastArray:ty }),*) => {
+        match ($i1, $i2) {
+            $(
+                (DataType::$Left, DataType::$Right) => Some(Box::new(
+                    TryBinaryExpression::<$LeftArray, $RightArray, $CastArray, _>::new(
+                        $Func::<_, _, $CastArray>(PhantomData),
+                    ),
+                ) as Box<dyn Expression>),
+            )*
+            _ => None,
+        }
+    };
+}
+
+/// Build unary expression with runtime information.
+///
+/// `i1` is the type of the input.
+pub fn build_unary_expression(
+    f: ExpressionFunc,
+    i1: &DataType,
+) -> Result<Box<dyn Expression>, UnsupportedFunction> {
+    let expr = match f {
+        ExpressionFunc::Negate => match i1 {
+            DataType::Int16 => Some(Box::new(TryUnaryExpression::<I16Array, I16Array, _>::new(
+                ExprNegate(PhantomData),
+            )) as Box<dyn Expression>),
+            DataType::Int32 => Some(Box::new(TryUnaryExpression::<I32Array, I32Array, _>::new(
+                ExprNegate(PhantomData),
+            )) as Box<dyn Expression>),
+            DataType::Int64 => Some(Box::new(TryUnaryExpression::<I64Array, I64Array, _>::new(
+                ExprNegate(PhantomData),
+            )) as Box<dyn Expression>),
+            DataType::Float32 => Some(Box::new(TryUnaryExpression::<F32Array, F32Array, _>::new(
+                ExprNegate(PhantomData),
+            )) as Box<dyn Expression>),
+            DataType::Float64 => Some(Box::new(TryUnaryExpression::<F64Array, F64Array, _>::new(
+                ExprNegate(PhantomData),
+            )) as Box<dyn Expression>),
+            _ => None,
+        },
+        ExpressionFunc::Not => {
+            matches!(i1, DataType::Bool).then(|| Box::new(LogicUnaryExpression::not()) as _)
+        }
+        ExpressionFunc::IsTrue => {
+            matches!(i1, DataType::Bool).then(|| Box::new(LogicUnaryExpression::is_true()) as _)
+        }
+        ExpressionFunc::IsFalse => {
+            matches!(i1, DataType::Bool).then(|| Box::new(LogicUnaryExpression::is_false()) as _)
+        }
+        ExpressionFunc::IsUnknown => {
+            matches!(i1, DataType::Bool).then(|| Box::new(LogicUnaryExpression::is_unknown()) as _)
+        }
+        ExpressionFunc::IsNull => Some(Box::new(IsNullExpression::is_null()) as _),
+        ExpressionFunc::IsNotNull => Some(Box::new(IsNullExpression::is_not_null()) as _),
+        ExpressionFunc::Length => match i1 {
+            DataType::String => Some(Box::new(UnaryExpression::<StringArray, I32Array, _>::new(
+                ExprCharLength,
+            )) as Box<dyn Expression>),
+            DataType::Binary => Some(Box::new(UnaryExpression::<BinaryArray, I32Array, _>::new(
+                ExprBinaryLength,
+            )) as Box<dyn Expression>),
+            _ => None,
+        },
+        ExpressionFunc::CharLength => matches!(i1, DataType::String).then(|| {
+            Box::new(UnaryExpression::<StringArray, I32Array, _>::new(
+                ExprCharLength,
+            )) as _
+        }),
+        ExpressionFunc::Upper => matches!(i1, DataType::String)
+            .then(|| Box::new(UnaryStringExpression::<StringArray, _>::new(ExprUpper)) as _),
+        ExpressionFunc::Lower => matches!(i1, DataType::String)
+            .then(|| Box::new(UnaryStringExpression::<StringArray, _>::new(ExprLower)) as _),
+        ExpressionFunc::Trim => matches!(i1, DataType::String)
+            .then(|| Box::new(UnaryStringExpression::<StringArray, _>::new(ExprTrim)) as _),
+        ExpressionFunc::LTrim => matches!(i1, DataType::String)
+            .then(|| Box::new(UnaryStringExpression::<StringArray, _>::new(ExprLTrim)) as _),
+        ExpressionFunc::RTrim => matches!(i1, DataType::String)
+            .then(|| Box::new(UnaryStringExpression::<StringArray, _>::new(ExprRTrim)) as _),
+        ExpressionFunc::Reverse => matches!(i1, DataType::String)
+            .then(|| Box::new(UnaryStringExpression::<StringArray, _>::new(ExprReverse)) as _),
+        _ => None,
+    };
+    expr.ok_or_else(|| UnsupportedFunction(f, vec![i1.clone()]))
 }
 
-/// Build expression with runtime information.
-pub fn build_binary_expression(f: ExpressionFunc) -> Box<dyn Expression> {
-    match f {
+/// Build binary expression with runtime information.
+///
+/// `i1` and `i2` are the types of the left and right input. Inputs of different types are cast
+/// into a common type before being compared or computed, e.g. `Int16` and `Int64` are both
+/// compared as `Int64`, and `Int32 + Float32` is a `Float64`.
+pub fn build_binary_expression(
+    f: ExpressionFunc,
+    i1: &DataType,
+    i2: &DataType,
+) -> Result<Box<dyn Expression>, UnsupportedFunction> {
+    let expr = match f {
+        ExpressionFunc::CmpLt => {
+            for_all_cmp_combinations! { impl_cmp_dispatch, i1, i2, BinaryExpression, ExprCmpLt }
+        }
         ExpressionFunc::CmpLe => {
-            Box::new(BinaryExpression::<I32Array, I32Array, BoolArray, _>::new(
-                ExprCmpLe::<_, _, I32Array>(PhantomData),
-            ))
+            for_all_cmp_combinations! { impl_cmp_dispatch, i1, i2, BinaryExpression, ExprCmpLe }
+        }
+        ExpressionFunc::CmpGt => {
+            for_all_cmp_combinations! { impl_cmp_dispatch, i1, i2, BinaryExpression, ExprCmpGt }
         }
         ExpressionFunc::CmpGe => {
-            Box::new(BinaryExpression::<I32Array, I32Array, BoolArray, _>::new(
-                ExprCmpGe::<_, _, I32Array>(PhantomData),
-            ))
+            for_all_cmp_combinations! { impl_cmp_dispatch, i1, i2, BinaryExpression, ExprCmpGe }
         }
         ExpressionFunc::CmpEq => {
-            Box::new(BinaryExpression::<I32Array, I32Array, BoolArray, _>::new(
-                ExprCmpEq::<_, _, I32Array>(PhantomData),
-            ))
+            for_all_cmp_combinations! { impl_cmp_dispatch, i1, i2, BinaryExpression, ExprCmpEq }
         }
         ExpressionFunc::CmpNe => {
-            Box::new(BinaryExpression::<I32Array, I32Array, BoolArray, _>::new(
-                ExprCmpNe::<_, _, I32Array>(PhantomData),
-            ))
+            for_all_cmp_combinations! { impl_cmp_dispatch, i1, i2, BinaryExpression, ExprCmpNe }
         }
-        ExpressionFunc::StrContains => {
-            Box::new(
-                BinaryExpression::<StringArray, StringArray, BoolArray, _>::new(ExprStrContains),
+        ExpressionFunc::StrContains => match (i1, i2) {
+            (DataType::String, DataType::String) => {
+                Some(Box::new(
+                    BinaryExpression::<StringArray, StringArray, BoolArray, _>::new(
+                        ExprStrContains,
+                    ),
+                ) as Box<dyn Expression>)
+            }
+            _ => None,
+        },
+        ExpressionFunc::AddInterval => match (i1, i2) {
+            (DataType::Date32, DataType::Interval) => Some(Box::new(TryBinaryExpression::<
+                Date32Array,
+                IntervalArray,
+                TimestampArray,
+                _,
+            >::new(
+                ExprAddInterval(PhantomData)
+            )) as Box<dyn Expression>),
+            (DataType::Timestamp { .. }, DataType::Interval) => Some(Box::new(
+                TryBinaryExpression::<TimestampArray, IntervalArray, TimestampArray, _>::new(
+                    ExprAddInterval(PhantomData),
+                )
+                .with_return_type(i1.clone()),
             )
+                as Box<dyn Expression>),
+            _ => None,
+        },
+        ExpressionFunc::TimestampSub => match (i1, i2) {
+            (DataType::Timestamp { .. }, DataType::Timestamp { .. }) => {
+                Some(Box::new(TryBinaryExpression::<
+                    TimestampArray,
+                    TimestampArray,
+                    IntervalArray,
+                    _,
+                >::new(ExprTimestampSub(PhantomData)))
+                    as Box<dyn Expression>)
+            }
+            (DataType::Date32, DataType::Timestamp { .. }) => {
+                Some(Box::new(TryBinaryExpression::<
+                    Date32Array,
+                    TimestampArray,
+                    IntervalArray,
+                    _,
+                >::new(ExprTimestampSub(PhantomData)))
+                    as Box<dyn Expression>)
+            }
+            (DataType::Timestamp { .. }, DataType::Date32) => {
+                Some(Box::new(TryBinaryExpression::<
+                    TimestampArray,
+                    Date32Array,
+                    IntervalArray,
+                    _,
+                >::new(ExprTimestampSub(PhantomData)))
+                    as Box<dyn Expression>)
+            }
+            _ => None,
+        },
+        ExpressionFunc::Extract => match (i1, i2) {
+            (DataType::String, DataType::Date32) => {
+                Some(Box::new(
+                    TryBinaryExpression::<StringArray, Date32Array, F64Array, _>::new(ExprExtract(
+                        PhantomData,
+                    )),
+                ) as Box<dyn Expression>)
+            }
+            (DataType::String, DataType::Timestamp { .. }) => {
+                Some(Box::new(TryBinaryExpression::<
+                    StringArray,
+                    TimestampArray,
+                    F64Array,
+                    _,
+                >::new(ExprExtract(PhantomData)))
+                    as Box<dyn Expression>)
+            }
+            _ => None,
+        },
+        ExpressionFunc::DateTrunc => match (i1, i2) {
+            (DataType::String, DataType::Date32) => Some(Box::new(TryBinaryExpression::<
+                StringArray,
+                Date32Array,
+                TimestampArray,
+                _,
+            >::new(ExprDateTrunc(
+                PhantomData,
+            ))) as Box<dyn Expression>),
+            (DataType::String, DataType::Timestamp { .. }) => Some(Box::new(
+                TryBinaryExpression::<StringArray, TimestampArray, TimestampArray, _>::new(
+                    ExprDateTrunc(PhantomData),
+                )
+                .with_return_type(i2.clone()),
+            )
+                as Box<dyn Expression>),
+            _ => None,
+        },
+        ExpressionFunc::Substr => match (i1, i2) {
+            (DataType::Binary, DataType::Int32) => {
+                Some(Box::new(
+                    BinaryExpression::<BinaryArray, I32Array, BinaryArray, _>::new(ExprSubstr),
+                ) as Box<dyn Expression>)
+            }
+            (DataType::String, DataType::Int32) => Some(Box::new(BinaryStringExpression::<
+                StringArray,
+                I32Array,
+                _,
+            >::new(ExprStrSubstr))
+                as Box<dyn Expression>),
+            _ => None,
+        },
+        ExpressionFunc::Encode => match (i1, i2) {
+            (DataType::Binary, DataType::String) => Some(Box::new(TryBinaryExpression::<
+                BinaryArray,
+                StringArray,
+                StringArray,
+                _,
+            >::new(ExprEncode))
+                as Box<dyn Expression>),
+            _ => None,
+        },
+        ExpressionFunc::Decode => match (i1, i2) {
+            (DataType::String, DataType::String) => Some(Box::new(TryBinaryExpression::<
+                StringArray,
+                StringArray,
+                BinaryArray,
+                _,
+            >::new(ExprDecode))
+                as Box<dyn Expression>),
+            _ => None,
+        },
+        ExpressionFunc::Add => for_all_arith_combinations! { impl_arith_dispatch, i1, i2, ExprAdd },
+        ExpressionFunc::Subtract => {
+            for_all_arith_combinations! { impl_arith_dispatch, i1, i2, ExprSubtract }
+        }
+        ExpressionFunc::Multiply => {
+            for_all_arith_combinations! { impl_arith_dispatch, i1, i2, ExprMultiply }
+        }
+        ExpressionFunc::Divide => {
+            for_all_arith_combinations! { impl_arith_dispatch, i1, i2, ExprDivide }
+        }
+        ExpressionFunc::Modulo => {
+            for_all_arith_combinations! { impl_arith_dispatch, i1, i2, ExprModulo }
+        }
+        ExpressionFunc::IsDistinctFrom => for_all_cmp_combinations! {
+            impl_cmp_dispatch, i1, i2, NullableBinaryExpression, ExprIsDistinctFrom
+        },
+        ExpressionFunc::IsNotDistinctFrom => for_all_cmp_combinations! {
+            impl_cmp_dispatch, i1, i2, NullableBinaryExpression, ExprIsNotDistinctFrom
+        },
+        ExpressionFunc::NullIf => for_all_cmp_combinations! { impl_nullif_dispatch, i1, i2 },
+        ExpressionFunc::And | ExpressionFunc::Or | ExpressionFunc::Xor => match (i1, i2) {
+            (DataType::Bool, DataType::Bool) => Some(Box::new(match f {
+                ExpressionFunc::And => LogicBinaryExpression::and(),
+                ExpressionFunc::Or => LogicBinaryExpression::or(),
+                _ => LogicBinaryExpression::xor(),
+            }) as Box<dyn Expression>),
+            _ => None,
+        },
+        ExpressionFunc::Trim
+        | ExpressionFunc::LTrim
+        | ExpressionFunc::RTrim
+        | ExpressionFunc::StartsWith
+        | ExpressionFunc::EndsWith
+        | ExpressionFunc::Position => match (i1, i2) {
+            (DataType::String, DataType::String) => Some(match f {
+                ExpressionFunc::Trim => {
+                    Box::new(BinaryStringExpression::<StringArray, StringArray, _>::new(
+                        ExprTrim,
+                    )) as Box<dyn Expression>
+                }
+                ExpressionFunc::LTrim => {
+                    Box::new(BinaryStringExpression::<StringArray, StringArray, _>::new(
+                        ExprLTrim,
+                    ))
+                }
+                ExpressionFunc::RTrim => {
+                    Box::new(BinaryStringExpression::<StringArray, StringArray, _>::new(
+                        ExprRTrim,
+                    ))
+                }
+                ExpressionFunc::StartsWith => Box::new(BinaryExpression::<
+                    StringArray,
+                    StringArray,
+                    BoolArray,
+                    _,
+                >::new(ExprStartsWith)),
+                ExpressionFunc::EndsWith => Box::new(BinaryExpression::<
+                    StringArray,
+                    StringArray,
+                    BoolArray,
+                    _,
+                >::new(ExprEndsWith)),
+                _ => Box::new(
+                    BinaryExpression::<StringArray, StringArray, I32Array, _>::new(ExprPosition),
+                ),
+            }),
+            _ => None,
+        },
+        ExpressionFunc::Repeat | ExpressionFunc::LPad | ExpressionFunc::RPad => match (i1, i2) {
+            (DataType::String, DataType::Int32) => Some(match f {
+                ExpressionFunc::Repeat => Box::new(
+                    BinaryStringExpression::<StringArray, I32Array, _>::new(ExprRepeat),
+                ) as Box<dyn Expression>,
+                ExpressionFunc::LPad => Box::new(
+                    BinaryStringExpression::<StringArray, I32Array, _>::new(ExprLPad),
+                ),
+                _ => Box::new(BinaryStringExpression::<StringArray, I32Array, _>::new(
+                    ExprRPad,
+                )),
+            }),
+            _ => None,
+        },
+        ExpressionFunc::Like
+        | ExpressionFunc::NotLike
+        | ExpressionFunc::ILike
+        | ExpressionFunc::NotILike
+        | ExpressionFunc::SimilarTo
+        | ExpressionFunc::NotSimilarTo => match (i1, i2) {
+            (DataType::String, DataType::String) => f.like_func().map(|func| {
+                Box::new(TryBinaryExpression::<StringArray, StringArray, BoolArray, _>::new(func))
+                    as Box<dyn Expression>
+            }),
+            _ => None,
+        },
+        ExpressionFunc::Negate
+        | ExpressionFunc::Not
+        | ExpressionFunc::IsTrue
+        | ExpressionFunc::IsFalse
+        | ExpressionFunc::IsUnknown
+        | ExpressionFunc::IsNull
+        | ExpressionFunc::IsNotNull
+        | ExpressionFunc::Length
+        | ExpressionFunc::CharLength
+        | ExpressionFunc::Upper
+        | ExpressionFunc::Lower
+        | ExpressionFunc::Reverse
+        | ExpressionFunc::Replace
+        | ExpressionFunc::SplitPart
+        | ExpressionFunc::RegexpIsMatch
+        | ExpressionFunc::RegexpMatch
+        | ExpressionFunc::RegexpReplace
+        | ExpressionFunc::RegexpCount
+        | ExpressionFunc::RegexpSplitToArray => None,
+    };
+    expr.ok_or_else(|| UnsupportedFunction(f, vec![i1.clone(), i2.clone()]))
+}
+
+/// Build ternary expression with runtime information.
+///
+/// `i1`, `i2` and `i3` are the types of the three inputs.
+pub fn build_ternary_expression(
+    f: ExpressionFunc,
+    i1: &DataType,
+    i2: &DataType,
+    i3: &DataType,
+) -> Result<Box<dyn Expression>, UnsupportedFunction> {
+    let expr = match (f, i1, i2, i3) {
+        (ExpressionFunc::Substr, DataType::Binary, DataType::Int32, DataType::Int32) => {
+            Some(Box::new(TryTernaryExpression::<
+                BinaryArray,
+                I32Array,
+                I32Array,
+                BinaryArray,
+                _,
+            >::new(ExprSubstr)) as Box<dyn Expression>)
+        }
+        (ExpressionFunc::Substr, DataType::String, DataType::Int32, DataType::Int32) => {
+            Some(Box::new(
+                TernaryStringExpression::<StringArray, I32Array, I32Array, _>::new(ExprStrSubstr),
+            ) as Box<dyn Expression>)
+        }
+        (ExpressionFunc::Replace, DataType::String, DataType::String, DataType::String) => {
+            Some(Box::new(TernaryStringExpression::<
+                StringArray,
+                StringArray,
+                StringArray,
+                _,
+            >::new(ExprReplace)) as Box<dyn Expression>)
+        }
+        (ExpressionFunc::SplitPart, DataType::String, DataType::String, DataType::Int32) => {
+            Some(Box::new(TernaryStringExpression::<
+                StringArray,
+                StringArray,
+                I32Array,
+                _,
+            >::new(ExprSplitPart)) as Box<dyn Expression>)
         }
+        (ExpressionFunc::LPad, DataType::String, DataType::Int32, DataType::String) => {
+            Some(Box::new(TernaryStringExpression::<
+                StringArray,
+                I32Array,
+                StringArray,
+                _,
+            >::new(ExprLPad)) as Box<dyn Expression>)
+        }
+        (ExpressionFunc::RPad, DataType::String, DataType::Int32, DataType::String) => {
+            Some(Box::new(TernaryStringExpression::<
+                StringArray,
+                I32Array,
+                StringArray,
+                _,
+            >::new(ExprRPad)) as Box<dyn Expression>)
+        }
+        (_, DataType::String, DataType::String, DataType::String) => f.like_func().map(|func| {
+            Box::new(TryTernaryExpression::<
+                StringArray,
+                StringArray,
+                StringArray,
+                BoolArray,
+                _,
+            >::new(func)) as Box<dyn Expression>
+        }),
+        _ => None,
+    };
+    expr.ok_or_else(|| UnsupportedFunction(f, vec![i1.clone(), i2.clone(), i3.clone()]))
+}
+
+/// Build a regular expression function with a constant `pattern` and `flags`, e.g. `"gi"`, as
+/// in Postgres. The pattern is compiled only once here, so that an invalid pattern is returned as
+/// an error instead of failing the evaluation.
+///
+/// The inputs of the expression are the strings to match, and also the replacements of
+/// [`ExpressionFunc::RegexpReplace`]. Only `RegexpReplace` supports the `g` flag, which replaces
+/// all matches instead of the first one.
+pub fn build_regexp_expression(
+    f: ExpressionFunc,
+    pattern: &str,
+    flags: &str,
+) -> Result<Box<dyn Expression>, RegexpError> {
+    let (regex, global) = regexp::compile(pattern, flags)?;
+    if global && f != ExpressionFunc::RegexpReplace {
+        return Err(RegexpError::GlobalFlag(f));
     }
+    Ok(match f {
+        ExpressionFunc::RegexpIsMatch => Box::new(
+            UnaryExpression::<StringArray, BoolArray, _>::new(ExprRegexpIsMatch(regex)),
+        ),
+        ExpressionFunc::RegexpCount => Box::new(UnaryExpression::<StringArray, I32Array, _>::new(
+            ExprRegexpCount(regex),
+        )),
+        ExpressionFunc::RegexpReplace => {
+            Box::new(BinaryStringExpression::<StringArray, StringArray, _>::new(
+                ExprRegexpReplace { regex, global },
+            ))
+        }
+        ExpressionFunc::RegexpMatch => Box::new(RegexpListExpression::regexp_match(regex)),
+        ExpressionFunc::RegexpSplitToArray => {
+            Box::new(RegexpListExpression::regexp_split_to_array(regex))
+        }
+        _ => return Err(UnsupportedFunction(f, vec![DataType::String]).into()),
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{Array, ScalarRefImpl};
+    use crate::{
+        Array, ArrayBuilder, ArrayBuilderImpl, ListValue, ScalarImpl, ScalarRefImpl,
+        TimestampArrayBuilder,
+    };
 
     use super::*;
 
     #[test]
     fn test_build_str_contains() {
-        let expr = build_binary_expression(ExpressionFunc::StrContains);
+        let expr = build_binary_expression(
+            ExpressionFunc::StrContains,
+            &DataType::String,
+            &DataType::String,
+        )
+        .unwrap();
 
         for _ in 0..10 {
             let result = expr
@@ -84,4 +874,672 @@ mod tests {
             assert!(result.get(2).is_none());
         }
     }
+
+    #[test]
+    fn test_build_string_functions() {
+        let strings: ArrayImpl = StringArray::from_slice(&[Some(" héllo "), None]).into();
+        let ints: ArrayImpl = I32Array::from_slice(&[Some(3), Some(1)]).into();
+
+        let expr = build_unary_expression(ExpressionFunc::Length, &DataType::String).unwrap();
+        let result = expr.eval_expr(&[&strings]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Int32(7)));
+        assert!(result.get(1).is_none());
+        let expr = build_unary_expression(ExpressionFunc::Length, &DataType::Binary).unwrap();
+        let bytes = BinaryArray::from_slice(&[Some(" héllo ".as_bytes())]).into();
+        let result = expr.eval_expr(&[&bytes]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Int32(8)));
+
+        let expr = build_unary_expression(ExpressionFunc::Upper, &DataType::String).unwrap();
+        let result = expr.eval_expr(&[&strings]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String(" HÉLLO ")));
+        assert_eq!(
+            ExpressionFunc::Upper.return_type(&[&DataType::String]),
+            DataType::String
+        );
+
+        let expr =
+            build_binary_expression(ExpressionFunc::LPad, &DataType::String, &DataType::Int32)
+                .unwrap();
+        let result = expr.eval_expr(&[&strings, &ints]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String(" hé")));
+        assert!(result.get(1).is_none());
+
+        let expr = build_ternary_expression(
+            ExpressionFunc::Substr,
+            &DataType::String,
+            &DataType::Int32,
+            &DataType::Int32,
+        )
+        .unwrap();
+        let result = expr.eval_expr(&[&strings, &ints, &ints]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String("éll")));
+
+        let split = ExprNode::ternary(
+            ExpressionFunc::SplitPart,
+            ExprNode::literal(ScalarImpl::String("a.b.c".to_string())),
+            ExprNode::literal(ScalarImpl::String(".".to_string())),
+            ExprNode::input_ref(0, DataType::Int32),
+        )
+        .unwrap();
+        assert_eq!(split.return_type(), &DataType::String);
+        let result = split.eval_expr(&[&ints]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String("c")));
+        assert_eq!(result.get(1), Some(ScalarRefImpl::String("a")));
+
+        assert!(build_unary_expression(ExpressionFunc::Reverse, &DataType::Binary).is_err());
+        assert!(build_binary_expression(
+            ExpressionFunc::Repeat,
+            &DataType::Int32,
+            &DataType::Int32
+        )
+        .is_err());
+        assert!(build_ternary_expression(
+            ExpressionFunc::Replace,
+            &DataType::String,
+            &DataType::String,
+            &DataType::Int32,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_build_like() {
+        let strings: ArrayImpl =
+            StringArray::from_slice(&[Some("abc"), Some("ABC"), Some("a%c"), None]).into();
+        let check = |func, pattern: &str, expected: &[Option<bool>]| {
+            let expr = ExprNode::binary(
+                func,
+                ExprNode::input_ref(0, DataType::String),
+                ExprNode::literal(ScalarImpl::String(pattern.to_string())),
+            )
+            .unwrap();
+            assert_eq!(expr.return_type(), &DataType::Bool);
+            let result: BoolArray = expr.eval_expr(&[&strings]).unwrap().try_into().unwrap();
+            assert_eq!(result.iter().collect::<Vec<_>>(), expected);
+        };
+        let (t, f, n) = (Some(true), Some(false), None);
+        check(ExpressionFunc::Like, "a%", &[t, f, t, n]);
+        check(ExpressionFunc::NotLike, "a%", &[f, t, f, n]);
+        check(ExpressionFunc::ILike, "a_c", &[t, t, t, n]);
+        check(ExpressionFunc::NotILike, "%b%", &[f, f, t, n]);
+        check(ExpressionFunc::SimilarTo, "(a|A)%", &[t, t, t, n]);
+        check(ExpressionFunc::NotSimilarTo, "[a-z]+", &[f, t, t, n]);
+
+        let escaped = ExprNode::ternary(
+            ExpressionFunc::Like,
+            ExprNode::input_ref(0, DataType::String),
+            ExprNode::literal(ScalarImpl::String("a!%c".to_string())),
+            ExprNode::literal(ScalarImpl::String("!".to_string())),
+        )
+        .unwrap();
+        let result = escaped.eval_expr(&[&strings]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Bool(false)));
+        assert_eq!(result.get(2), Some(ScalarRefImpl::Bool(true)));
+        assert!(
+            build_binary_expression(ExpressionFunc::Like, &DataType::String, &DataType::Int32)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_build_regexp() {
+        let strings: ArrayImpl =
+            StringArray::from_slice(&[Some("a1b22"), Some("xyz"), None]).into();
+        let expr = build_regexp_expression(ExpressionFunc::RegexpIsMatch, "\\d", "").unwrap();
+        let result: BoolArray = expr.eval_expr(&[&strings]).unwrap().try_into().unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(true), Some(false), None]
+        );
+
+        let expr = build_regexp_expression(ExpressionFunc::RegexpCount, "\\d+", "").unwrap();
+        let result = expr.eval_expr(&[&strings]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Int32(2)));
+
+        let expr = build_regexp_expression(ExpressionFunc::RegexpReplace, "\\d", "g").unwrap();
+        let replace = ExprNode::call(
+            expr,
+            vec![
+                ExprNode::input_ref(0, DataType::String),
+                ExprNode::literal(ScalarImpl::String("#".to_string())),
+            ],
+            ExpressionFunc::RegexpReplace.return_type(&[&DataType::String, &DataType::String]),
+        );
+        let result = replace.eval_expr(&[&strings]).unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::String("a#b##")));
+        assert_eq!(result.get(1), Some(ScalarRefImpl::String("xyz")));
+
+        let expr = build_regexp_expression(ExpressionFunc::RegexpSplitToArray, "\\d+", "").unwrap();
+        let result = expr.eval_expr(&[&strings]).unwrap();
+        assert_eq!(
+            result.data_type(),
+            ExpressionFunc::RegexpSplitToArray.return_type(&[&DataType::String])
+        );
+        assert_eq!(
+            result.get(0).map(|list| list.to_owned_scalar()),
+            Some(ScalarImpl::List(ListValue::new(
+                StringArray::from_slice(&[Some("a"), Some("b"), Some("")]).into()
+            )))
+        );
+
+        assert!(matches!(
+            build_regexp_expression(ExpressionFunc::RegexpMatch, "(", ""),
+            Err(RegexpError::Regex(_))
+        ));
+        assert!(matches!(
+            build_regexp_expression(ExpressionFunc::RegexpMatch, "a", "g"),
+            Err(RegexpError::GlobalFlag(ExpressionFunc::RegexpMatch))
+        ));
+        assert!(matches!(
+            build_regexp_expression(ExpressionFunc::Like, "a", ""),
+            Err(RegexpError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_cmp_mixed_types() {
+        let expr =
+            build_binary_expression(ExpressionFunc::CmpLe, &DataType::Int16, &DataType::Int64)
+                .unwrap();
+        let result = expr
+            .eval_expr(&[
+                &I16Array::from_slice(&[Some(1), Some(2), None]).into(),
+                &I64Array::from_slice(&[Some(i64::MAX), Some(0), Some(1)]).into(),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Bool(true));
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Bool(false));
+        assert!(result.get(2).is_none());
+
+        let expr = build_binary_expression(
+            ExpressionFunc::CmpEq,
+            &DataType::Float64,
+            &DataType::Float64,
+        )
+        .unwrap();
+        let result = expr
+            .eval_expr(&[
+                &F64Array::from_slice(&[Some(1.5), Some(2.0)]).into(),
+                &F64Array::from_slice(&[Some(1.5), Some(0.0)]).into(),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Bool(true));
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Bool(false));
+
+        // inputs must match the types the expression is built for
+        let expr =
+            build_binary_expression(ExpressionFunc::CmpNe, &DataType::Int32, &DataType::Float32)
+                .unwrap();
+        assert!(expr
+            .eval_expr(&[
+                &F32Array::from_slice(&[Some(1.0)]).into(),
+                &I32Array::from_slice(&[Some(1)]).into(),
+            ])
+            .is_err());
+    }
+
+    #[test]
+    fn test_build_cmp_numeric_matrix() {
+        macro_rules! primitive_types {
+            ([], $({ $Abc:ident, $abc:ident, $AbcArray:ty, $AbcArrayBuilder:ty, $Owned:ty, $Ref:ty }),*) => {
+                [$(DataType::$Abc),*]
+            };
+        }
+        let numeric = crate::macros::for_all_primitive_variants! { primitive_types }
+            .into_iter()
+            .filter(|t| {
+                matches!(
+                    t,
+                    DataType::Int16
+                        | DataType::Int32
+                        | DataType::Int64
+                        | DataType::Float32
+                        | DataType::Float64
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(numeric.len(), 5);
+
+        let array = |t: &DataType, values: &[Option<i16>]| {
+            let mut builder = ArrayBuilderImpl::new(t, values.len());
+            for v in values {
+                builder.push(v.map(|v| match t {
+                    DataType::Int16 => ScalarRefImpl::Int16(v),
+                    DataType::Int32 => ScalarRefImpl::Int32(v.into()),
+                    DataType::Int64 => ScalarRefImpl::Int64(v.into()),
+                    DataType::Float32 => ScalarRefImpl::Float32(v.into()),
+                    _ => ScalarRefImpl::Float64(v.into()),
+                }));
+            }
+            builder.finish()
+        };
+        let (t, f, n) = (Some(true), Some(false), None);
+        let cases = [
+            (ExpressionFunc::CmpLt, [t, f, f, n]),
+            (ExpressionFunc::CmpLe, [t, t, f, n]),
+            (ExpressionFunc::CmpGt, [f, f, t, n]),
+            (ExpressionFunc::CmpGe, [f, t, t, n]),
+            (ExpressionFunc::CmpEq, [f, t, f, n]),
+            (ExpressionFunc::CmpNe, [t, f, t, n]),
+        ];
+        for i1 in &numeric {
+            for i2 in &numeric {
+                let left = array(i1, &[Some(1), Some(2), Some(3), None]);
+                let right = array(i2, &[Some(2), Some(2), Some(2), Some(2)]);
+                for (func, expected) in &cases {
+                    let result: BoolArray = build_binary_expression(*func, i1, i2)
+                        .unwrap()
+                        .eval_expr(&[&left, &right])
+                        .unwrap()
+                        .try_into()
+                        .unwrap();
+                    assert_eq!(
+                        result.iter().collect::<Vec<_>>(),
+                        expected,
+                        "{:?} on {} and {}",
+                        func,
+                        i1,
+                        i2
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_build_cmp_decimal() {
+        let dec = |s: &str| s.parse::<crate::Decimal>().unwrap();
+        let expr = build_binary_expression(
+            ExpressionFunc::CmpEq,
+            &DataType::Int32,
+            &DataType::Decimal {
+                precision: 10,
+                scale: 2,
+            },
+        )
+        .unwrap();
+        let result = expr
+            .eval_expr(&[
+                &I32Array::from_slice(&[Some(1), Some(1), None]).into(),
+                &DecimalArray::from_slice(&[Some(dec("1.00")), Some(dec("1.01")), None]).into(),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Bool(true));
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Bool(false));
+        assert!(result.get(2).is_none());
+
+        let decimal = |precision, scale| DataType::Decimal { precision, scale };
+        let result = cast(
+            &I64Array::from_slice(&[Some(-7), None, Some(999)]).into(),
+            &decimal(5, 2),
+        )
+        .unwrap();
+        assert_eq!(result.data_type(), decimal(5, 2));
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Decimal(dec("-7")));
+        assert!(result.get(1).is_none());
+
+        // rounding when the scale is reduced
+        let result = cast(
+            &DecimalArray::from_slice(&[Some(dec("-1.25"))]).into(),
+            &decimal(3, 1),
+        )
+        .unwrap();
+        match result.get(0) {
+            Some(ScalarRefImpl::Decimal(d)) => assert_eq!(d.to_string(), "-1.3"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let hundred: ArrayImpl = I64Array::from_slice(&[Some(100)]).into();
+        assert!(cast(&hundred, &decimal(4, 2)).is_err());
+        assert!(cast(&hundred, &decimal(4, 5)).is_err());
+    }
+
+    #[test]
+    fn test_build_temporal() {
+        let ts = |s: &str| s.parse::<crate::Timestamp>().unwrap();
+        let expr = build_binary_expression(
+            ExpressionFunc::AddInterval,
+            &DataType::Date32,
+            &DataType::Interval,
+        )
+        .unwrap();
+        let result = expr
+            .eval_expr(&[
+                &Date32Array::from_slice(&[Some("2021-01-31".parse().unwrap()), None]).into(),
+                &IntervalArray::from_slice(&[Some("1 mon 1 hour".parse().unwrap()), None]).into(),
+            ])
+            .unwrap();
+        assert_eq!(result.data_type(), DataType::Timestamp { tz: None });
+        assert_eq!(
+            result.get(0).unwrap(),
+            ScalarRefImpl::Timestamp(ts("2021-02-28 01:00:00"))
+        );
+        assert!(result.get(1).is_none());
+
+        let timestamps: ArrayImpl = TimestampArray::from_slice(&[
+            Some(ts("2021-05-19 04:05:06")),
+            Some(ts("1999-12-31 23:59:59")),
+        ])
+        .into();
+        let expr = build_binary_expression(
+            ExpressionFunc::Extract,
+            &DataType::String,
+            &DataType::Timestamp { tz: None },
+        )
+        .unwrap();
+        let result = expr
+            .eval_expr(&[
+                &StringArray::from_slice(&[Some("year"), Some("MONTH")]).into(),
+                &timestamps,
+            ])
+            .unwrap();
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Float64(2021.0));
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Float64(12.0));
+
+        let expr = build_binary_expression(
+            ExpressionFunc::DateTrunc,
+            &DataType::String,
+            &DataType::Timestamp { tz: None },
+        )
+        .unwrap();
+        let truncated = expr
+            .eval_expr(&[
+                &StringArray::from_slice(&[Some("day"), Some("year")]).into(),
+                &timestamps,
+            ])
+            .unwrap();
+        assert_eq!(
+            truncated.get(1).unwrap(),
+            ScalarRefImpl::Timestamp(ts("1999-01-01"))
+        );
+
+        // the timezone tag of the input is kept
+        let utc = DataType::Timestamp {
+            tz: Some("UTC".to_string()),
+        };
+        let mut builder = TimestampArrayBuilder::with_tz(1, Some("UTC"));
+        builder.push(Some(ts("2021-05-19 04:05:06")));
+        let utc_timestamps: ArrayImpl = builder.finish().into();
+        let expr = build_binary_expression(ExpressionFunc::AddInterval, &utc, &DataType::Interval)
+            .unwrap();
+        let result = expr
+            .eval_expr(&[
+                &utc_timestamps,
+                &IntervalArray::from_slice(&[Some("1 day".parse().unwrap())]).into(),
+            ])
+            .unwrap();
+        assert_eq!(result.data_type(), utc);
+        assert_eq!(
+            ExpressionFunc::AddInterval.return_type(&[&utc, &DataType::Interval]),
+            utc
+        );
+        let expr =
+            build_binary_expression(ExpressionFunc::DateTrunc, &DataType::String, &utc).unwrap();
+        let result = expr
+            .eval_expr(&[
+                &StringArray::from_slice(&[Some("day")]).into(),
+                &utc_timestamps,
+            ])
+            .unwrap();
+        assert_eq!(result.data_type(), utc);
+        assert_eq!(
+            ExpressionFunc::DateTrunc.return_type(&[&DataType::String, &utc]),
+            utc
+        );
+
+        let expr = build_binary_expression(
+            ExpressionFunc::TimestampSub,
+            &DataType::Timestamp { tz: None },
+            &DataType::Timestamp { tz: None },
+        )
+        .unwrap();
+        let result = expr.eval_expr(&[&timestamps, &truncated]).unwrap();
+        assert_eq!(
+            result.get(0).unwrap(),
+            ScalarRefImpl::Interval("04:05:06".parse().unwrap())
+        );
+        assert_eq!(
+            result.get(1).unwrap(),
+            ScalarRefImpl::Interval("364 days 23:59:59".parse().unwrap())
+        );
+
+        let expr = build_binary_expression(
+            ExpressionFunc::CmpLe,
+            &DataType::Date32,
+            &DataType::Timestamp { tz: None },
+        )
+        .unwrap();
+        let result = expr
+            .eval_expr(&[
+                &Date32Array::from_slice(&[Some("2021-05-19".parse().unwrap())]).into(),
+                &timestamps.slice(0, 1),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Bool(true));
+    }
+
+    #[test]
+    fn test_build_arithmetic() {
+        let expr = build_binary_expression(ExpressionFunc::Add, &DataType::Int16, &DataType::Int32)
+            .unwrap();
+        let result = expr
+            .eval_expr(&[
+                &I16Array::from_slice(&[Some(1), Some(i16::MAX), None]).into(),
+                &I32Array::from_slice(&[Some(2), Some(i16::MAX as i32), Some(1)]).into(),
+            ])
+            .unwrap();
+        assert_eq!(result.data_type(), DataType::Int32);
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Int32(3));
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Int32(65534));
+        assert!(result.get(2).is_none());
+
+        let overflow = expr.eval_expr(&[
+            &I16Array::from_slice(&[Some(1)]).into(),
+            &I32Array::from_slice(&[Some(i32::MAX)]).into(),
+        ]);
+        assert_eq!(
+            overflow.err().unwrap().to_string(),
+            "integer out of range in row 0 with inputs (1, 2147483647)"
+        );
+
+        let expr =
+            build_binary_expression(ExpressionFunc::Divide, &DataType::Int64, &DataType::Int64)
+                .unwrap();
+        let ints: ArrayImpl = I64Array::from_slice(&[Some(7), Some(-7)]).into();
+        let result = expr
+            .eval_expr(&[&ints, &I64Array::from_slice(&[Some(2), Some(2)]).into()])
+            .unwrap();
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Int64(-3));
+        let by_zero = expr.eval_datums(&[
+            Datum::array(ints.clone()),
+            Datum::scalar(Some(ScalarRefImpl::Int64(0)), &DataType::Int64),
+        ]);
+        assert_eq!(
+            by_zero.err().unwrap().to_string(),
+            "division by zero in row 0 with inputs (7, 0)"
+        );
+
+        let expr =
+            build_binary_expression(ExpressionFunc::Modulo, &DataType::Int64, &DataType::Int16)
+                .unwrap();
+        let result = expr
+            .eval_expr(&[&ints, &I16Array::from_slice(&[Some(3), Some(3)]).into()])
+            .unwrap();
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Int64(-1));
+
+        let (i1, i2) = (&DataType::Int32, &DataType::Float32);
+        assert_eq!(
+            ExpressionFunc::Multiply.return_type(&[i1, i2]),
+            DataType::Float64
+        );
+        let expr = build_binary_expression(ExpressionFunc::Multiply, i1, i2).unwrap();
+        let result = expr
+            .eval_expr(&[
+                &I32Array::from_slice(&[Some(3)]).into(),
+                &F32Array::from_slice(&[Some(0.5)]).into(),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Float64(1.5));
+
+        let expr = build_unary_expression(ExpressionFunc::Negate, &DataType::Int32).unwrap();
+        let result = expr
+            .eval_expr(&[&I32Array::from_slice(&[Some(1), None]).into()])
+            .unwrap();
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Int32(-1));
+        assert!(result.get(1).is_none());
+        assert!(expr
+            .eval_expr(&[&I32Array::from_slice(&[Some(i32::MIN)]).into()])
+            .is_err());
+
+        assert!(build_unary_expression(ExpressionFunc::Negate, &DataType::String).is_err());
+        assert!(build_binary_expression(
+            ExpressionFunc::Subtract,
+            &DataType::Int64,
+            &DataType::String
+        )
+        .is_err());
+
+        // bigint is computed as double precision with floats
+        let expr = build_binary_expression(
+            ExpressionFunc::Subtract,
+            &DataType::Int64,
+            &DataType::Float64,
+        )
+        .unwrap();
+        assert_eq!(
+            ExpressionFunc::Subtract.return_type(&[&DataType::Int64, &DataType::Float64]),
+            DataType::Float64
+        );
+        let result = expr
+            .eval_expr(&[
+                &I64Array::from_slice(&[Some(3), Some(-4)]).into(),
+                &F64Array::from_slice(&[Some(0.5), Some(0.25)]).into(),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Float64(2.5));
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Float64(-4.25));
+        let expr = build_binary_expression(
+            ExpressionFunc::Multiply,
+            &DataType::Float32,
+            &DataType::Int64,
+        )
+        .unwrap();
+        let result = expr
+            .eval_expr(&[
+                &F32Array::from_slice(&[Some(0.5)]).into(),
+                &I64Array::from_slice(&[Some(3)]).into(),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Float64(1.5));
+    }
+
+    #[test]
+    fn test_build_null_aware() {
+        let expr = build_binary_expression(
+            ExpressionFunc::IsDistinctFrom,
+            &DataType::String,
+            &DataType::String,
+        )
+        .unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::array(StringArray::from_slice(&[Some("a"), None]).into()),
+                Datum::scalar(None, &DataType::String),
+            ])
+            .unwrap();
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Bool(true));
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Bool(false));
+
+        let expr =
+            build_binary_expression(ExpressionFunc::NullIf, &DataType::Int16, &DataType::Int32)
+                .unwrap();
+        let result = expr
+            .eval_expr(&[
+                &I16Array::from_slice(&[Some(1), Some(2)]).into(),
+                &I32Array::from_slice(&[Some(1), Some(1)]).into(),
+            ])
+            .unwrap();
+        assert_eq!(result.data_type(), DataType::Int16);
+        assert!(result.get(0).is_none());
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Int16(2));
+
+        // the precision and scale of the first input are kept
+        let decimal = DataType::Decimal {
+            precision: 10,
+            scale: 2,
+        };
+        let dec = |s: &str| Some(s.parse::<crate::Decimal>().unwrap());
+        let mut builder = crate::DecimalArrayBuilder::with_type(&decimal, 2);
+        builder.push(dec("1.5"));
+        builder.push(dec("2"));
+        let decimals: ArrayImpl = builder.finish().into();
+        let expr = build_binary_expression(ExpressionFunc::NullIf, &decimal, &decimal).unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::array(decimals),
+                Datum::scalar(
+                    dec("1.50").map(ScalarRefImpl::Decimal),
+                    &DataType::Decimal {
+                        precision: 3,
+                        scale: 2,
+                    },
+                ),
+            ])
+            .unwrap();
+        assert_eq!(result.data_type(), decimal);
+        assert_eq!(
+            ExpressionFunc::NullIf.return_type(&[&decimal, &decimal]),
+            decimal
+        );
+        assert!(result.get(0).is_none());
+        assert_eq!(
+            result.get(1).unwrap(),
+            ScalarRefImpl::Decimal(dec("2").unwrap())
+        );
+
+        let expr =
+            build_binary_expression(ExpressionFunc::And, &DataType::Bool, &DataType::Bool).unwrap();
+        let result = expr
+            .eval_datums(&[
+                Datum::array(BoolArray::from_slice(&[Some(true), Some(false)]).into()),
+                Datum::scalar(None, &DataType::Bool),
+            ])
+            .unwrap();
+        assert!(result.get(0).is_none());
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Bool(false));
+        assert!(
+            build_binary_expression(ExpressionFunc::Or, &DataType::Bool, &DataType::Int32).is_err()
+        );
+
+        let expr = build_unary_expression(ExpressionFunc::IsFalse, &DataType::Bool).unwrap();
+        let result = expr
+            .eval_expr(&[&BoolArray::from_slice(&[Some(false), None]).into()])
+            .unwrap();
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Bool(true));
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Bool(false));
+        assert!(build_unary_expression(ExpressionFunc::Not, &DataType::Int32).is_err());
+
+        let expr = build_unary_expression(ExpressionFunc::IsNotNull, &DataType::Int32).unwrap();
+        let result = expr
+            .eval_expr(&[&I32Array::from_slice(&[Some(1), None]).into()])
+            .unwrap();
+        assert_eq!(result.get(0).unwrap(), ScalarRefImpl::Bool(true));
+        assert_eq!(result.get(1).unwrap(), ScalarRefImpl::Bool(false));
+    }
+
+    #[test]
+    fn test_build_unsupported() {
+        assert!(build_binary_expression(
+            ExpressionFunc::CmpLe,
+            &DataType::Int32,
+            &DataType::String
+        )
+        .is_err());
+        assert!(build_binary_expression(
+            ExpressionFunc::StrContains,
+            &DataType::String,
+            &DataType::Int32
+        )
+        .is_err());
+    }
 }
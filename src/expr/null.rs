@@ -0,0 +1,291 @@
+use std::marker::PhantomData;
+
+use anyhow::{anyhow, Result};
+use bitvec::vec::BitVec;
+
+use super::cmp::{CmpCast, TotalOrd};
+use super::vectorize::NullableBinaryExprFunc;
+use crate::{Array, ArrayBuilderImpl, ArrayImpl, BoolArray, Datum, Expression, ScalarRef};
+
+/// Return if `i1 IS DISTINCT FROM i2`, i.e. `i1 != i2` where `NULL` equals `NULL` and differs from
+/// any other value. Note that `i1` and `i2` could be different types. This function will
+/// automatically cast them into `C` type.
+///
+/// * `I1`: left input type.
+/// * `I2`: right input type.
+/// * `C`: cast type.
+pub struct ExprIsDistinctFrom<I1: Array, I2: Array, C: Array>(pub PhantomData<(I1, I2, C)>);
+
+impl<I1: Array, I2: Array, C: Array> NullableBinaryExprFunc<I1, I2, BoolArray>
+    for ExprIsDistinctFrom<I1, I2, C>
+where
+    for<'a> I1::RefItem<'a>: CmpCast<C::RefItem<'a>>,
+    for<'a> I2::RefItem<'a>: CmpCast<C::RefItem<'a>>,
+    for<'a> C::RefItem<'a>: TotalOrd,
+{
+    fn eval<'a>(&self, i1: Option<I1::RefItem<'a>>, i2: Option<I2::RefItem<'a>>) -> Option<bool> {
+        Some(!not_distinct::<I1, I2, C>(i1, i2))
+    }
+}
+
+/// Return if `i1 IS NOT DISTINCT FROM i2`, i.e. `i1 == i2` where `NULL` equals `NULL` and differs
+/// from any other value. Note that `i1` and `i2` could be different types. This function will
+/// automatically cast them into `C` type.
+///
+/// * `I1`: left input type.
+/// * `I2`: right input type.
+/// * `C`: cast type.
+pub struct ExprIsNotDistinctFrom<I1: Array, I2: Array, C: Array>(pub PhantomData<(I1, I2, C)>);
+
+impl<I1: Array, I2: Array, C: Array> NullableBinaryExprFunc<I1, I2, BoolArray>
+    for ExprIsNotDistinctFrom<I1, I2, C>
+where
+    for<'a> I1::RefItem<'a>: CmpCast<C::RefItem<'a>>,
+    for<'a> I2::RefItem<'a>: CmpCast<C::RefItem<'a>>,
+    for<'a> C::RefItem<'a>: TotalOrd,
+{
+    fn eval<'a>(&self, i1: Option<I1::RefItem<'a>>, i2: Option<I2::RefItem<'a>>) -> Option<bool> {
+        Some(not_distinct::<I1, I2, C>(i1, i2))
+    }
+}
+
+fn not_distinct<'a, I1: Array, I2: Array, C: Array>(
+    i1: Option<I1::RefItem<'a>>,
+    i2: Option<I2::RefItem<'a>>,
+) -> bool
+where
+    I1::RefItem<'a>: CmpCast<C::RefItem<'a>>,
+    I2::RefItem<'a>: CmpCast<C::RefItem<'a>>,
+    C::RefItem<'a>: TotalOrd,
+{
+    match (i1, i2) {
+        (Some(i1), Some(i2)) => i1.cmp_cast().total_order(&i2.cmp_cast()).is_eq(),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Returns `nullif(i1, i2)`, i.e. `NULL` if `i1 = i2`, and `i1` otherwise. Note that `i1` and
+/// `i2` could be different types. This function will automatically cast them into `C` type to
+/// compare them, but the result is of the type of `i1`.
+///
+/// * `I1`: left input type.
+/// * `I2`: right input type.
+/// * `C`: cast type.
+pub struct ExprNullIf<I1: Array, I2: Array, C: Array>(pub PhantomData<(I1, I2, C)>);
+
+impl<I1: Array, I2: Array, C: Array> NullableBinaryExprFunc<I1, I2, I1> for ExprNullIf<I1, I2, C>
+where
+    for<'a> I1::RefItem<'a>: CmpCast<C::RefItem<'a>>,
+    for<'a> I2::RefItem<'a>: CmpCast<C::RefItem<'a>>,
+    for<'a> C::RefItem<'a>: TotalOrd,
+{
+    fn eval<'a>(
+        &self,
+        i1: Option<I1::RefItem<'a>>,
+        i2: Option<I2::RefItem<'a>>,
+    ) -> Option<I1::OwnedItem> {
+        match (i1, i2) {
+            (Some(v1), Some(v2)) if v1.cmp_cast().total_order(&v2.cmp_cast()).is_eq() => None,
+            _ => i1.map(|v1| v1.to_owned_scalar()),
+        }
+    }
+}
+
+/// Returns `coalesce(i1, i2, ...)`, i.e. the first input that is not `NULL`, or `NULL` if all
+/// inputs are `NULL`. All inputs must be of the same type.
+pub struct Coalesce;
+
+impl Coalesce {
+    /// Evaluate the first non-null input of each row.
+    pub fn eval_batch(&self, data: &[Datum]) -> Result<ArrayImpl> {
+        let first = data
+            .first()
+            .ok_or_else(|| anyhow!("Except at least one input for Coalesce"))?;
+        let data_type = first.data_type();
+        if let Some(other) = data.iter().find(|d| d.data_type() != data_type) {
+            return Err(anyhow!(
+                "Inputs of Coalesce should be of type {}, get {}",
+                data_type,
+                other.data_type()
+            ));
+        }
+        let len = data.iter().find_map(Datum::num_rows).unwrap_or(1);
+        if data.iter().any(|d| d.num_rows().is_some_and(|l| l != len)) {
+            return Err(anyhow!("Inputs have different lengths"));
+        }
+        let mut builder = ArrayBuilderImpl::new(&data_type, len);
+        for row in 0..len {
+            builder.push(data.iter().find_map(|d| d.get(row)));
+        }
+        Ok(builder.finish())
+    }
+}
+
+impl Expression for Coalesce {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        let data = data
+            .iter()
+            .map(|array| Datum::array((*array).clone()))
+            .collect::<Vec<_>>();
+        self.eval_batch(&data)
+    }
+
+    fn eval_datums(&self, data: &[Datum]) -> Result<ArrayImpl> {
+        self.eval_batch(data)
+    }
+}
+
+/// `i1 IS NULL` or `i1 IS NOT NULL` on any type.
+///
+/// The result is the null bitmap of `i1`, copied or inverted, so that the items of `i1` are never
+/// read. The result itself is never `NULL`.
+pub struct IsNullExpression {
+    negated: bool,
+}
+
+impl IsNullExpression {
+    /// `i1 IS NULL`
+    pub fn is_null() -> Self {
+        Self { negated: false }
+    }
+
+    /// `i1 IS NOT NULL`
+    pub fn is_not_null() -> Self {
+        Self { negated: true }
+    }
+
+    /// Evaluate the predicate on an array of any type.
+    pub fn eval_batch(&self, array: &ArrayImpl) -> BoolArray {
+        let valid = array.null_bitmap().to_bitvec();
+        let values = if self.negated { valid } else { !valid };
+        let len = values.len();
+        BoolArray::from_bits(values, BitVec::repeat(true, len))
+    }
+}
+
+impl Expression for IsNullExpression {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        match data {
+            [i1] => Ok(self.eval_batch(i1).into()),
+            _ => Err(anyhow!("Except 1 input(s) for IsNullExpression")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DataType, F64Array, I16Array, I32Array, I64Array, NullableBinaryExpression, ScalarRefImpl,
+        StringArray,
+    };
+
+    #[test]
+    fn test_is_distinct_from() {
+        let left: ArrayImpl = I16Array::from_slice(&[Some(1), Some(1), None, None]).into();
+        let right: ArrayImpl = I64Array::from_slice(&[Some(1), Some(2), Some(1), None]).into();
+        let result: BoolArray = NullableBinaryExpression::<I16Array, I64Array, BoolArray, _>::new(
+            ExprIsDistinctFrom::<_, _, I64Array>(PhantomData),
+        )
+        .eval_expr(&[&left, &right])
+        .unwrap()
+        .try_into()
+        .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(false), Some(true), Some(true), Some(false)]
+        );
+
+        let result: BoolArray = NullableBinaryExpression::<I16Array, I64Array, BoolArray, _>::new(
+            ExprIsNotDistinctFrom::<_, _, I64Array>(PhantomData),
+        )
+        .eval_expr(&[&left, &right])
+        .unwrap()
+        .try_into()
+        .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some(true), Some(false), Some(false), Some(true)]
+        );
+    }
+
+    #[test]
+    fn test_nullif() {
+        let expr = NullableBinaryExpression::<I32Array, F64Array, I32Array, _>::new(ExprNullIf::<
+            _,
+            _,
+            F64Array,
+        >(
+            PhantomData
+        ));
+        let result: I32Array = expr
+            .eval_expr(&[
+                &I32Array::from_slice(&[Some(1), Some(2), None, Some(3)]).into(),
+                &F64Array::from_slice(&[Some(1.0), Some(2.5), Some(1.0), None]).into(),
+            ])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [None, Some(2), None, Some(3)]
+        );
+    }
+
+    #[test]
+    fn test_coalesce() {
+        let result: StringArray = Coalesce
+            .eval_datums(&[
+                Datum::array(StringArray::from_slice(&[Some("a"), None, None]).into()),
+                Datum::array(StringArray::from_slice(&[Some("b"), Some("c"), None]).into()),
+                Datum::scalar(Some(ScalarRefImpl::String("d")), &DataType::String),
+            ])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some("a"), Some("c"), Some("d")]
+        );
+
+        let ints: ArrayImpl = I32Array::from_slice(&[None]).into();
+        assert!(Coalesce.eval_expr(&[]).is_err());
+        assert!(Coalesce
+            .eval_expr(&[&ints, &I64Array::from_slice(&[Some(1)]).into()])
+            .is_err());
+        assert!(Coalesce
+            .eval_expr(&[&ints, &ints])
+            .unwrap()
+            .get(0)
+            .is_none());
+    }
+
+    #[test]
+    fn test_is_null() {
+        let array: ArrayImpl =
+            StringArray::from_slice(&[Some("a"), None, Some("b"), None, None]).into();
+        assert_eq!(array.null_count(), 3);
+        let sliced = array.slice(1, 3);
+        assert_eq!(sliced.null_count(), 2);
+
+        let eval = |expr: IsNullExpression, array: &ArrayImpl| {
+            expr.eval_batch(array).iter().collect::<Vec<_>>()
+        };
+        assert_eq!(
+            eval(IsNullExpression::is_null(), &sliced),
+            [Some(true), Some(false), Some(true)]
+        );
+        assert_eq!(
+            eval(IsNullExpression::is_not_null(), &sliced),
+            [Some(false), Some(true), Some(false)]
+        );
+
+        let result = Expression::eval_datums(
+            &IsNullExpression::is_not_null(),
+            &[Datum::scalar(None, &DataType::Int64)],
+        )
+        .unwrap();
+        assert_eq!(result.get(0), Some(ScalarRefImpl::Bool(false)));
+    }
+}
@@ -0,0 +1,302 @@
+use anyhow::{anyhow, Result};
+use regex::{Captures, Regex, RegexBuilder};
+use thiserror::Error;
+
+use crate::{
+    Array, ArrayBuilder, ArrayImpl, BoolArray, DataType, Expression, I32Array, ListArray,
+    ListArrayBuilder, ListValue, StringArray, StringWriter,
+};
+
+use super::vectorize::{BinaryStringExprFunc, UnaryExprFunc};
+
+/// Returned when a regular expression function cannot be built.
+#[derive(Error, Debug)]
+pub enum RegexpError {
+    #[error("invalid regular expression: {0}")]
+    Regex(#[from] regex::Error),
+    #[error("invalid regular expression option: {0:?}")]
+    InvalidFlag(char),
+    #[error("{0:?} does not support the \"global\" option")]
+    GlobalFlag(super::ExpressionFunc),
+    #[error(transparent)]
+    Unsupported(#[from] super::UnsupportedFunction),
+}
+
+/// Compile `pattern` with the Postgres `flags`, and return if the `g` flag is given.
+///
+/// A newline is an ordinary character unless `n` is given, so that `.` matches it and `^` and `$`
+/// only match at the ends of the string. Matching always takes linear time in the length of the
+/// string, even for patterns supplied by users.
+pub(super) fn compile(pattern: &str, flags: &str) -> Result<(Regex, bool), RegexpError> {
+    let mut builder = RegexBuilder::new(pattern);
+    builder.dot_matches_new_line(true);
+    let mut global = false;
+    for flag in flags.chars() {
+        match flag {
+            'c' => builder.case_insensitive(false),
+            'i' => builder.case_insensitive(true),
+            'n' | 'm' => builder.dot_matches_new_line(false).multi_line(true),
+            's' => builder.dot_matches_new_line(true).multi_line(false),
+            'x' => builder.ignore_whitespace(true),
+            'g' => {
+                global = true;
+                continue;
+            }
+            other => return Err(RegexpError::InvalidFlag(other)),
+        };
+    }
+    Ok((builder.build()?, global))
+}
+
+/// Returns `i1 ~ pattern`, i.e. if `i1` contains a match of the pattern.
+pub struct ExprRegexpIsMatch(pub Regex);
+
+impl UnaryExprFunc<StringArray, BoolArray> for ExprRegexpIsMatch {
+    fn eval(&self, i1: &str) -> bool {
+        self.0.is_match(i1)
+    }
+}
+
+/// Returns `regexp_count(i1, pattern)`, i.e. the number of non-overlapping matches in `i1`.
+pub struct ExprRegexpCount(pub Regex);
+
+impl UnaryExprFunc<StringArray, I32Array> for ExprRegexpCount {
+    fn eval(&self, i1: &str) -> i32 {
+        self.0.find_iter(i1).count() as i32
+    }
+}
+
+/// Returns `regexp_replace(i1, pattern, i2)`, i.e. `i1` with the first match replaced by `i2`,
+/// or all matches if `global` is set.
+///
+/// In `i2`, `\1` to `\9` stand for the text of the capture groups, `\&` for the whole match,
+/// and `\\` for a backslash.
+pub struct ExprRegexpReplace {
+    pub regex: Regex,
+    pub global: bool,
+}
+
+impl BinaryStringExprFunc<StringArray, StringArray> for ExprRegexpReplace {
+    fn eval(&self, i1: &str, i2: &str, writer: &mut StringWriter<'_>) -> Result<()> {
+        let limit = if self.global { usize::MAX } else { 1 };
+        let mut last = 0;
+        for captures in self.regex.captures_iter(i1).take(limit) {
+            let whole = captures.get(0).unwrap();
+            writer.push_str(&i1[last..whole.start()]);
+            expand(&captures, i2, writer);
+            last = whole.end();
+        }
+        writer.push_str(&i1[last..]);
+        Ok(())
+    }
+}
+
+/// Write `replacement` with the references to the groups of `captures` replaced.
+fn expand(captures: &Captures<'_>, replacement: &str, writer: &mut StringWriter<'_>) {
+    let mut chars = replacement.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            writer.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(d @ '1'..='9') => {
+                let group = d.to_digit(10).unwrap() as usize;
+                writer.push_str(captures.get(group).map_or("", |m| m.as_str()));
+            }
+            Some('&') => writer.push_str(&captures[0]),
+            Some(other) => {
+                if other != '\\' {
+                    writer.push('\\');
+                }
+                writer.push(other);
+            }
+            None => writer.push('\\'),
+        }
+    }
+}
+
+/// A regular expression function returning lists of strings, i.e. `regexp_match` or
+/// `regexp_split_to_array`.
+pub struct RegexpListExpression {
+    regex: Regex,
+    split: bool,
+}
+
+impl RegexpListExpression {
+    /// `regexp_match(s, pattern)`, i.e. the texts of the capture groups of the first match, or
+    /// the whole match if there is no group. The result is `NULL` if there is no match.
+    pub fn regexp_match(regex: Regex) -> Self {
+        Self {
+            regex,
+            split: false,
+        }
+    }
+
+    /// `regexp_split_to_array(s, pattern)`, i.e. the parts of `s` between the matches. An empty
+    /// match at either end of `s` or right after another match does not split `s`.
+    pub fn regexp_split_to_array(regex: Regex) -> Self {
+        Self { regex, split: true }
+    }
+
+    fn captures<'a>(&self, s: &'a str) -> Option<Vec<Option<&'a str>>> {
+        let captures = self.regex.captures(s)?;
+        let groups = captures.iter().map(|m| m.map(|m| m.as_str()));
+        Some(if captures.len() == 1 {
+            groups.collect()
+        } else {
+            groups.skip(1).collect()
+        })
+    }
+
+    fn split<'a>(&self, s: &'a str) -> Vec<Option<&'a str>> {
+        let mut parts = vec![];
+        let mut last = 0;
+        for m in self.regex.find_iter(s) {
+            if m.is_empty() && (m.start() == last || m.start() == s.len()) {
+                continue;
+            }
+            parts.push(Some(&s[last..m.start()]));
+            last = m.end();
+        }
+        parts.push(Some(&s[last..]));
+        parts
+    }
+
+    /// Evaluate the function on an array of strings.
+    pub fn eval_batch(&self, array: &StringArray) -> ListArray {
+        let data_type = DataType::List(Box::new(DataType::String));
+        let mut builder = ListArrayBuilder::with_type(&data_type, array.len());
+        for s in array.iter() {
+            let items = match s {
+                Some(s) if self.split => Some(self.split(s)),
+                Some(s) => self.captures(s),
+                None => None,
+            };
+            let list = items.map(|items| ListValue::new(StringArray::from_slice(&items).into()));
+            builder.push(list.as_ref().map(ListValue::as_list_ref));
+        }
+        builder.finish()
+    }
+}
+
+impl Expression for RegexpListExpression {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        match data {
+            [i1] => Ok(self.eval_batch((*i1).try_into()?).into()),
+            _ => Err(anyhow!("Except 1 input(s) for RegexpListExpression")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::vectorize::BinaryStringExpression;
+    use crate::{Datum, ScalarRefImpl};
+
+    fn regex(pattern: &str) -> Regex {
+        compile(pattern, "").unwrap().0
+    }
+
+    #[test]
+    fn test_compile() {
+        let (case_insensitive, global) = compile("^a.c$", "ig").unwrap();
+        assert!(global);
+        assert!(case_insensitive.is_match("A\nC"));
+        let (newline, _) = compile("^b$", "n").unwrap();
+        assert!(newline.is_match("a\nb"));
+        assert!(!regex("^b$").is_match("a\nb"));
+        assert!(matches!(compile("(a", ""), Err(RegexpError::Regex(_))));
+        assert!(matches!(
+            compile("a", "z"),
+            Err(RegexpError::InvalidFlag('z'))
+        ));
+    }
+
+    #[test]
+    fn test_regexp_functions() {
+        assert!(ExprRegexpIsMatch(regex("b+")).eval("abbc"));
+        assert!(!ExprRegexpIsMatch(regex("^b+")).eval("abbc"));
+        assert_eq!(ExprRegexpCount(regex("a.")).eval("abacaad"), 3);
+        assert_eq!(ExprRegexpCount(regex("x")).eval(""), 0);
+
+        let lists = |expr: RegexpListExpression, s: &[Option<&str>]| {
+            expr.eval_batch(&StringArray::from_slice(s))
+                .iter()
+                .map(|list| {
+                    list.map(|list| {
+                        list.iter()
+                            .map(|s| s.map(|s| <&str>::try_from(s).unwrap().to_string()))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        let s = |s: &str| Some(s.to_string());
+        let result = lists(
+            RegexpListExpression::regexp_match(regex("(\\w+)@(\\w+)?")),
+            &[Some("to: bob@home"), Some("none"), None, Some("x@")],
+        );
+        assert_eq!(
+            result,
+            [
+                Some(vec![s("bob"), s("home")]),
+                None,
+                None,
+                Some(vec![s("x"), None])
+            ]
+        );
+        let result = lists(
+            RegexpListExpression::regexp_match(regex("\\d+")),
+            &[Some("a12b34")],
+        );
+        assert_eq!(result, [Some(vec![s("12")])]);
+
+        let split = |pattern, input| {
+            lists(
+                RegexpListExpression::regexp_split_to_array(regex(pattern)),
+                &[Some(input)],
+            )
+            .remove(0)
+            .unwrap()
+        };
+        assert_eq!(split(",\\s*", "a, b,c"), [s("a"), s("b"), s("c")]);
+        assert_eq!(split("", "abc"), [s("a"), s("b"), s("c")]);
+        assert_eq!(split("x*", "axb"), [s("a"), s("b")]);
+        assert_eq!(split(",", ",a,"), [s(""), s("a"), s("")]);
+    }
+
+    #[test]
+    fn test_regexp_replace() {
+        let replace = |pattern, global, s: &str, replacement: &str| {
+            let expr = BinaryStringExpression::new(ExprRegexpReplace {
+                regex: regex(pattern),
+                global,
+            });
+            let result = Expression::eval_datums(
+                &expr,
+                &[
+                    Datum::scalar(Some(ScalarRefImpl::String(s)), &DataType::String),
+                    Datum::scalar(Some(ScalarRefImpl::String(replacement)), &DataType::String),
+                ],
+            )
+            .unwrap();
+            <&str>::try_from(result.get(0).unwrap())
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(replace("o", false, "foo", "0"), "f0o");
+        assert_eq!(replace("o", true, "foo", "0"), "f00");
+        assert_eq!(replace("x", true, "foo", "0"), "foo");
+        assert_eq!(
+            replace(
+                "(\\w+) (\\w+)",
+                false,
+                "hello world",
+                "\\2 \\1 [\\&] \\\\ \\x"
+            ),
+            "world hello [hello world] \\ \\x"
+        );
+    }
+}
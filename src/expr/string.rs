@@ -1,12 +1,585 @@
-use crate::{BoolArray, StringArray};
+use anyhow::{anyhow, bail, Result};
 
-use super::vectorize::BinaryExprFunc;
+use crate::{
+    Array, ArrayBuilder, ArrayImpl, BoolArray, DataType, Datum, Expression, I32Array, StringArray,
+    StringArrayBuilder, StringWriter,
+};
+
+use super::binary::substr;
+use super::vectorize::{
+    BinaryExprFunc, BinaryStringExprFunc, TernaryStringExprFunc, UnaryExprFunc, UnaryStringExprFunc,
+};
+
+/// Maximum number of bytes of a string produced by `repeat`, `lpad` and `rpad`, as in Postgres.
+const MAX_STRING_LEN: usize = (1 << 30) - 1;
 
 /// Checks if `i1.contains(i2)` for two string inputs.
 pub struct ExprStrContains;
 
 impl BinaryExprFunc<StringArray, StringArray, BoolArray> for ExprStrContains {
-    fn eval(&self, i1: &str, i2: &str) -> <BoolArray as crate::Array>::OwnedItem {
+    fn eval(&self, i1: &str, i2: &str) -> <BoolArray as Array>::OwnedItem {
         i1.contains(i2)
     }
 }
+
+/// Returns `char_length(i1)`, i.e. the number of characters of `i1`, which is also `length(i1)`
+/// of strings.
+pub struct ExprCharLength;
+
+impl UnaryExprFunc<StringArray, I32Array> for ExprCharLength {
+    fn eval(&self, i1: &str) -> i32 {
+        i1.chars().count() as i32
+    }
+}
+
+/// Returns `upper(i1)`, i.e. `i1` with all characters in upper case.
+pub struct ExprUpper;
+
+impl UnaryStringExprFunc<StringArray> for ExprUpper {
+    fn eval(&self, i1: &str, writer: &mut StringWriter<'_>) -> Result<()> {
+        i1.chars()
+            .flat_map(char::to_uppercase)
+            .for_each(|c| writer.push(c));
+        Ok(())
+    }
+}
+
+/// Returns `lower(i1)`, i.e. `i1` with all characters in lower case.
+pub struct ExprLower;
+
+impl UnaryStringExprFunc<StringArray> for ExprLower {
+    fn eval(&self, i1: &str, writer: &mut StringWriter<'_>) -> Result<()> {
+        i1.chars()
+            .flat_map(char::to_lowercase)
+            .for_each(|c| writer.push(c));
+        Ok(())
+    }
+}
+
+macro_rules! impl_trim_func {
+    ($($Func:ident, $name:literal, $side:literal, $trim:ident);*) => {
+        $(
+            #[doc = concat!("Returns `", $name, "(i1)`, i.e. `i1` without the spaces at ", $side)]
+            #[doc = concat!(", or `", $name, "(i1, i2)` without the characters in `i2`.")]
+            pub struct $Func;
+
+            impl UnaryStringExprFunc<StringArray> for $Func {
+                fn eval(&self, i1: &str, writer: &mut StringWriter<'_>) -> Result<()> {
+                    writer.push_str(i1.$trim(' '));
+                    Ok(())
+                }
+            }
+
+            impl BinaryStringExprFunc<StringArray, StringArray> for $Func {
+                fn eval(&self, i1: &str, i2: &str, writer: &mut StringWriter<'_>) -> Result<()> {
+                    writer.push_str(i1.$trim(|c| i2.contains(c)));
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_trim_func! {
+    ExprTrim, "btrim", "both ends", trim_matches;
+    ExprLTrim, "ltrim", "the start", trim_start_matches;
+    ExprRTrim, "rtrim", "the end", trim_end_matches
+}
+
+/// Byte offset of the `n`-th character of `s`, or the length of `s` if it has fewer characters.
+fn char_offset(s: &str, n: usize) -> usize {
+    if s.is_ascii() {
+        return n.min(s.len());
+    }
+    s.char_indices()
+        .nth(n)
+        .map_or(s.len(), |(offset, _)| offset)
+}
+
+/// The characters of `s` starting at the 1-based position `start`, with at most `count`
+/// characters or up to the end if `count` is `None`. It is the same as [`substr`] of bytes, but
+/// counts characters instead.
+fn char_substr(s: &str, start: i32, count: Option<i32>) -> &str {
+    if s.is_ascii() {
+        // Safety: any part of an ASCII string is valid UTF-8
+        return unsafe { std::str::from_utf8_unchecked(substr(s.as_bytes(), start, count)) };
+    }
+    let end = count.map_or(i64::MAX, |count| start as i64 + count as i64);
+    let clamp = |pos: i64| usize::try_from(pos.max(1) - 1).unwrap_or(usize::MAX);
+    let start = char_offset(s, clamp(start as i64));
+    let end = char_offset(s, clamp(end)).max(start);
+    &s[start..end]
+}
+
+/// Returns `substr(i1, i2)`, i.e. the characters of `i1` from the 1-based position `i2` to the
+/// end, or `substr(i1, i2, i3)` with at most `i3` characters. Fails if `i3` is negative.
+pub struct ExprStrSubstr;
+
+impl BinaryStringExprFunc<StringArray, I32Array> for ExprStrSubstr {
+    fn eval(&self, i1: &str, i2: i32, writer: &mut StringWriter<'_>) -> Result<()> {
+        writer.push_str(char_substr(i1, i2, None));
+        Ok(())
+    }
+}
+
+impl TernaryStringExprFunc<StringArray, I32Array, I32Array> for ExprStrSubstr {
+    fn eval(&self, i1: &str, i2: i32, i3: i32, writer: &mut StringWriter<'_>) -> Result<()> {
+        if i3 < 0 {
+            bail!("negative substring length not allowed");
+        }
+        writer.push_str(char_substr(i1, i2, Some(i3)));
+        Ok(())
+    }
+}
+
+/// Returns `replace(i1, i2, i3)`, i.e. `i1` with all occurrences of `i2` replaced by `i3`. `i1`
+/// is returned as is if `i2` is empty.
+pub struct ExprReplace;
+
+impl TernaryStringExprFunc<StringArray, StringArray, StringArray> for ExprReplace {
+    fn eval(&self, i1: &str, i2: &str, i3: &str, writer: &mut StringWriter<'_>) -> Result<()> {
+        if i2.is_empty() {
+            writer.push_str(i1);
+            return Ok(());
+        }
+        let mut last = 0;
+        for (start, part) in i1.match_indices(i2) {
+            writer.push_str(&i1[last..start]);
+            writer.push_str(i3);
+            last = start + part.len();
+        }
+        writer.push_str(&i1[last..]);
+        Ok(())
+    }
+}
+
+/// Returns `starts_with(i1, i2)`, i.e. if `i1` starts with `i2`.
+pub struct ExprStartsWith;
+
+impl BinaryExprFunc<StringArray, StringArray, BoolArray> for ExprStartsWith {
+    fn eval(&self, i1: &str, i2: &str) -> bool {
+        i1.starts_with(i2)
+    }
+}
+
+/// Returns `ends_with(i1, i2)`, i.e. if `i1` ends with `i2`.
+pub struct ExprEndsWith;
+
+impl BinaryExprFunc<StringArray, StringArray, BoolArray> for ExprEndsWith {
+    fn eval(&self, i1: &str, i2: &str) -> bool {
+        i1.ends_with(i2)
+    }
+}
+
+/// Returns `position(i2 in i1)`, i.e. the 1-based character position of the first occurrence of
+/// `i2` in `i1`, or 0 if there is none. The position of an empty `i2` is 1.
+pub struct ExprPosition;
+
+impl BinaryExprFunc<StringArray, StringArray, I32Array> for ExprPosition {
+    fn eval(&self, i1: &str, i2: &str) -> i32 {
+        match i1.find(i2) {
+            Some(offset) => i1[..offset].chars().count() as i32 + 1,
+            None => 0,
+        }
+    }
+}
+
+/// Returns `split_part(i1, i2, i3)`, i.e. the `i3`-th field of `i1` split by `i2`, counting from
+/// the end if `i3` is negative. The result is empty if there is no such field, and `i1` is a
+/// single field if `i2` is empty. Fails if `i3` is zero.
+pub struct ExprSplitPart;
+
+impl TernaryStringExprFunc<StringArray, StringArray, I32Array> for ExprSplitPart {
+    fn eval(&self, i1: &str, i2: &str, i3: i32, writer: &mut StringWriter<'_>) -> Result<()> {
+        let field = match (i3, i2.is_empty()) {
+            (0, _) => bail!("field position must not be zero"),
+            (1 | -1, true) => Some(i1),
+            (_, true) => None,
+            (n, false) if n > 0 => i1.split(i2).nth(n as usize - 1),
+            (n, false) => i1.rsplit(i2).nth(n.unsigned_abs() as usize - 1),
+        };
+        writer.push_str(field.unwrap_or_default());
+        Ok(())
+    }
+}
+
+/// Returns `repeat(i1, i2)`, i.e. `i1` repeated `i2` times, which is empty if `i2` is not
+/// positive. Fails if the result is too long.
+pub struct ExprRepeat;
+
+impl BinaryStringExprFunc<StringArray, I32Array> for ExprRepeat {
+    fn eval(&self, i1: &str, i2: i32, writer: &mut StringWriter<'_>) -> Result<()> {
+        let times = i2.max(0) as usize;
+        if i1.is_empty() || times == 0 {
+            return Ok(());
+        }
+        if i1.len().saturating_mul(times) > MAX_STRING_LEN {
+            bail!("requested length too large");
+        }
+        (0..times).for_each(|_| writer.push_str(i1));
+        Ok(())
+    }
+}
+
+/// Returns `reverse(i1)`, i.e. the characters of `i1` in reverse order.
+pub struct ExprReverse;
+
+impl UnaryStringExprFunc<StringArray> for ExprReverse {
+    fn eval(&self, i1: &str, writer: &mut StringWriter<'_>) -> Result<()> {
+        i1.chars().rev().for_each(|c| writer.push(c));
+        Ok(())
+    }
+}
+
+/// Writes `s` padded with `fill` on the left or right to `len` characters, or truncated to
+/// `len` characters if it is longer. `s` is not padded if `fill` is empty.
+fn pad(s: &str, len: i32, fill: &str, left: bool, writer: &mut StringWriter<'_>) -> Result<()> {
+    let len = len.max(0) as usize;
+    // a character is at most 4 bytes
+    if len.saturating_mul(4) > MAX_STRING_LEN {
+        bail!("requested length too large");
+    }
+    let truncated = &s[..char_offset(s, len)];
+    let missing = len - truncated.chars().count();
+    let padding = fill
+        .chars()
+        .cycle()
+        .take(if fill.is_empty() { 0 } else { missing });
+    if !left {
+        writer.push_str(truncated);
+    }
+    padding.for_each(|c| writer.push(c));
+    if left {
+        writer.push_str(truncated);
+    }
+    Ok(())
+}
+
+macro_rules! impl_pad_func {
+    ($($Func:ident, $name:literal, $side:literal, $left:literal);*) => {
+        $(
+            #[doc = concat!("Returns `", $name, "(i1, i2)`, i.e. `i1` padded with spaces on the ")]
+            #[doc = concat!($side, " to `i2` characters, or `", $name, "(i1, i2, i3)` padded")]
+            /// with `i3`. `i1` is truncated to `i2` characters if it is longer, and the result is
+            /// empty if `i2` is not positive. Fails if the result is too long.
+            pub struct $Func;
+
+            impl BinaryStringExprFunc<StringArray, I32Array> for $Func {
+                fn eval(&self, i1: &str, i2: i32, writer: &mut StringWriter<'_>) -> Result<()> {
+                    pad(i1, i2, " ", $left, writer)
+                }
+            }
+
+            impl TernaryStringExprFunc<StringArray, I32Array, StringArray> for $Func {
+                fn eval(
+                    &self,
+                    i1: &str,
+                    i2: i32,
+                    i3: &str,
+                    writer: &mut StringWriter<'_>,
+                ) -> Result<()> {
+                    pad(i1, i2, i3, $left, writer)
+                }
+            }
+        )*
+    };
+}
+
+impl_pad_func! {
+    ExprLPad, "lpad", "left", true;
+    ExprRPad, "rpad", "right", false
+}
+
+/// Checks that all inputs of the variadic function `name` are strings of the same length, and
+/// returns the number of rows of the result.
+fn check_strings(name: &str, data: &[Datum]) -> Result<usize> {
+    if let Some(other) = data.iter().find(|d| d.data_type() != DataType::String) {
+        return Err(anyhow!(
+            "Inputs of {} should be strings, get {}",
+            name,
+            other.data_type()
+        ));
+    }
+    let len = data.iter().find_map(Datum::num_rows).unwrap_or(1);
+    if data.iter().any(|d| d.num_rows().is_some_and(|l| l != len)) {
+        return Err(anyhow!("Inputs have different lengths"));
+    }
+    Ok(len)
+}
+
+fn string(d: &Datum, row: usize) -> Option<&str> {
+    d.get(row).map(|s| s.try_into().unwrap())
+}
+
+/// Returns `concat(s1, s2, ...)`, i.e. the strings joined together. `NULL` strings are skipped,
+/// so that the result is never `NULL`.
+pub struct Concat;
+
+impl Concat {
+    /// Evaluate the concatenation of all inputs.
+    pub fn eval_batch(&self, data: &[Datum]) -> Result<ArrayImpl> {
+        let len = check_strings("Concat", data)?;
+        let mut builder = StringArrayBuilder::with_capacity(len);
+        for row in 0..len {
+            builder.push_with(|writer| {
+                data.iter()
+                    .filter_map(|d| string(d, row))
+                    .for_each(|s| writer.push_str(s));
+                Ok::<_, std::convert::Infallible>(())
+            })?;
+        }
+        Ok(builder.finish().into())
+    }
+}
+
+impl Expression for Concat {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        let data = data
+            .iter()
+            .map(|array| Datum::array((*array).clone()))
+            .collect::<Vec<_>>();
+        self.eval_batch(&data)
+    }
+
+    fn eval_datums(&self, data: &[Datum]) -> Result<ArrayImpl> {
+        self.eval_batch(data)
+    }
+}
+
+/// Returns `concat_ws(sep, s1, s2, ...)`, i.e. the strings joined by the separator `sep`. `NULL`
+/// strings are skipped, and the result is `NULL` only if `sep` is `NULL`.
+pub struct ConcatWs;
+
+impl ConcatWs {
+    /// Evaluate the concatenation, where the first input is the separator.
+    pub fn eval_batch(&self, data: &[Datum]) -> Result<ArrayImpl> {
+        if data.is_empty() {
+            return Err(anyhow!("Except at least one input for ConcatWs"));
+        }
+        let len = check_strings("ConcatWs", data)?;
+
+        let mut builder = StringArrayBuilder::with_capacity(len);
+        for row in 0..len {
+            let Some(sep) = string(&data[0], row) else {
+                builder.push(None);
+                continue;
+            };
+            builder.push_with(|writer| {
+                for (i, s) in data[1..].iter().filter_map(|d| string(d, row)).enumerate() {
+                    if i > 0 {
+                        writer.push_str(sep);
+                    }
+                    writer.push_str(s);
+                }
+                Ok::<_, std::convert::Infallible>(())
+            })?;
+        }
+        Ok(builder.finish().into())
+    }
+}
+
+impl Expression for ConcatWs {
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        let data = data
+            .iter()
+            .map(|array| Datum::array((*array).clone()))
+            .collect::<Vec<_>>();
+        self.eval_batch(&data)
+    }
+
+    fn eval_datums(&self, data: &[Datum]) -> Result<ArrayImpl> {
+        self.eval_batch(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::vectorize::{
+        BinaryStringExpression, ErrorPolicy, RowErrors, TernaryStringExpression,
+        UnaryStringExpression,
+    };
+    use crate::ScalarRefImpl;
+
+    fn strings(array: ArrayImpl) -> Vec<Option<String>> {
+        let array: StringArray = array.try_into().unwrap();
+        array.iter().map(|s| s.map(str::to_string)).collect()
+    }
+
+    /// Evaluate a string function on one row.
+    macro_rules! eval {
+        ($Expression:ident, $func:expr, $($input:expr),+) => {{
+            let inputs = [$(Datum::scalar(Some($input.into()), &ScalarRefImpl::from($input).data_type())),+];
+            let result = Expression::eval_datums(&$Expression::new($func), &inputs).unwrap();
+            strings(result).remove(0).unwrap()
+        }};
+    }
+
+    #[test]
+    fn test_string_functions() {
+        assert_eq!(ExprCharLength.eval("héllo"), 5);
+        assert_eq!(eval!(UnaryStringExpression, ExprUpper, "straße"), "STRASSE");
+        assert_eq!(eval!(UnaryStringExpression, ExprLower, "ÀB c"), "àb c");
+        assert_eq!(eval!(UnaryStringExpression, ExprTrim, "  a b  "), "a b");
+        assert_eq!(eval!(UnaryStringExpression, ExprLTrim, "  a b  "), "a b  ");
+        assert_eq!(eval!(UnaryStringExpression, ExprRTrim, "\ta "), "\ta");
+        assert_eq!(eval!(BinaryStringExpression, ExprTrim, "xyaxy", "yx"), "a");
+        assert_eq!(eval!(UnaryStringExpression, ExprReverse, "añb"), "bña");
+
+        assert_eq!(
+            eval!(BinaryStringExpression, ExprStrSubstr, "héllo", 2),
+            "éllo"
+        );
+        assert_eq!(
+            eval!(TernaryStringExpression, ExprStrSubstr, "héllo", 0, 3),
+            "hé"
+        );
+        assert_eq!(
+            eval!(TernaryStringExpression, ExprStrSubstr, "héllo", 4, 9),
+            "lo"
+        );
+        assert_eq!(
+            eval!(TernaryStringExpression, ExprStrSubstr, "hello", -1, 3),
+            "h"
+        );
+        assert_eq!(
+            eval!(TernaryStringExpression, ExprReplace, "abcabc", "bc", "X"),
+            "aXaX"
+        );
+        assert_eq!(
+            eval!(TernaryStringExpression, ExprReplace, "abc", "", "X"),
+            "abc"
+        );
+
+        assert!(ExprStartsWith.eval("hello", "he"));
+        assert!(!ExprEndsWith.eval("hello", "he"));
+        assert_eq!(ExprPosition.eval("héllo", "llo"), 3);
+        assert_eq!(ExprPosition.eval("hello", "x"), 0);
+        assert_eq!(ExprPosition.eval("hello", ""), 1);
+
+        let split = |n: i32| eval!(TernaryStringExpression, ExprSplitPart, "a,b,,c", ",", n);
+        assert_eq!(split(2), "b");
+        assert_eq!(split(3), "");
+        assert_eq!(split(5), "");
+        assert_eq!(split(-1), "c");
+        assert_eq!(
+            eval!(TernaryStringExpression, ExprSplitPart, "abc", "", -1),
+            "abc"
+        );
+        assert_eq!(
+            eval!(TernaryStringExpression, ExprSplitPart, "abc", "", 2),
+            ""
+        );
+
+        assert_eq!(eval!(BinaryStringExpression, ExprRepeat, "ab", 3), "ababab");
+        assert_eq!(eval!(BinaryStringExpression, ExprRepeat, "ab", -1), "");
+        assert_eq!(eval!(BinaryStringExpression, ExprRepeat, "", i32::MAX), "");
+        assert_eq!(eval!(BinaryStringExpression, ExprLPad, "hi", 5), "   hi");
+        assert_eq!(
+            eval!(TernaryStringExpression, ExprLPad, "hi", 5, "xy"),
+            "xyxhi"
+        );
+        assert_eq!(
+            eval!(TernaryStringExpression, ExprRPad, "hé", 4, "ö"),
+            "héöö"
+        );
+        assert_eq!(eval!(BinaryStringExpression, ExprRPad, "hello", 2), "he");
+        assert_eq!(eval!(TernaryStringExpression, ExprRPad, "hi", 5, ""), "hi");
+        assert_eq!(eval!(BinaryStringExpression, ExprLPad, "hi", -1), "");
+    }
+
+    #[test]
+    fn test_string_errors() {
+        let errors = RowErrors::new();
+        let expr = BinaryStringExpression::new(ExprRepeat)
+            .with_error_policy(ErrorPolicy::Null(errors.clone()));
+        let result = expr
+            .eval_batch(
+                &StringArray::from_slice(&[Some("ab"), Some("ab"), None]).into(),
+                &I32Array::from_slice(&[Some(2), Some(i32::MAX), Some(1)]).into(),
+            )
+            .unwrap();
+        assert_eq!(strings(result), [Some("abab".to_string()), None, None]);
+        let errors = errors.take();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "requested length too large in row 1 with inputs (\"ab\", 2147483647)"
+        );
+
+        // a failed row must not leave partial output behind
+        let expr = TernaryStringExpression::new(ExprSplitPart);
+        let error = expr
+            .eval_batch(
+                &StringArray::from_slice(&[Some("a,b"), Some("a,b")]).into(),
+                &StringArray::from_slice(&[Some(","), Some(",")]).into(),
+                &I32Array::from_slice(&[Some(1), Some(0)]).into(),
+            )
+            .err()
+            .unwrap();
+        assert!(error
+            .to_string()
+            .starts_with("field position must not be zero"));
+        assert!(TernaryStringExpression::new(ExprStrSubstr)
+            .eval_batch(
+                &StringArray::from_slice(&[Some("abc")]).into(),
+                &I32Array::from_slice(&[Some(1)]).into(),
+                &I32Array::from_slice(&[Some(-1)]).into(),
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_concat() {
+        let result = Concat
+            .eval_datums(&[
+                Datum::array(StringArray::from_slice(&[Some("a"), None, None]).into()),
+                Datum::scalar(Some(ScalarRefImpl::String("-")), &DataType::String),
+                Datum::array(StringArray::from_slice(&[Some("b"), Some("c"), None]).into()),
+            ])
+            .unwrap();
+        assert_eq!(
+            strings(result),
+            [
+                Some("a-b".to_string()),
+                Some("-c".to_string()),
+                Some("-".to_string())
+            ]
+        );
+        assert_eq!(
+            strings(Concat.eval_datums(&[]).unwrap()),
+            [Some(String::new())]
+        );
+        assert!(Concat
+            .eval_expr(&[&I32Array::from_slice(&[Some(1)]).into()])
+            .is_err());
+    }
+
+    #[test]
+    fn test_concat_ws() {
+        let result: StringArray = ConcatWs
+            .eval_datums(&[
+                Datum::scalar(Some(ScalarRefImpl::String(", ")), &DataType::String),
+                Datum::array(StringArray::from_slice(&[Some("a"), None, None]).into()),
+                Datum::array(StringArray::from_slice(&[Some("b"), Some("c"), None]).into()),
+            ])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            result.iter().collect::<Vec<_>>(),
+            [Some("a, b"), Some("c"), Some("")]
+        );
+
+        let result = ConcatWs
+            .eval_expr(&[&StringArray::from_slice(&[None]).into()])
+            .unwrap();
+        assert!(result.get(0).is_none());
+        assert!(ConcatWs
+            .eval_expr(&[
+                &StringArray::from_slice(&[Some("-")]).into(),
+                &I32Array::from_slice(&[Some(1)]).into()
+            ])
+            .is_err());
+    }
+}
@@ -0,0 +1,321 @@
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use thiserror::Error;
+
+use crate::scalar::days_from_civil;
+use crate::{
+    Array, Date, F64Array, Interval, IntervalArray, StringArray, Timestamp, TimestampArray,
+    MICROS_PER_DAY, MICROS_PER_HOUR, MICROS_PER_MINUTE, MICROS_PER_SECOND,
+};
+
+use super::vectorize::TryBinaryExprFunc;
+
+/// Returned when a string does not name a [`DateTimeField`].
+#[derive(Error, Debug)]
+#[error("Unknown date/time field: {0}")]
+pub struct ParseDateTimeFieldError(String);
+
+/// A field of a timestamp, which is used by `extract` and `date_trunc`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateTimeField {
+    Microseconds,
+    Milliseconds,
+    Second,
+    Minute,
+    Hour,
+    Day,
+    /// ISO 8601 week, which starts on Monday
+    Week,
+    Month,
+    Quarter,
+    Year,
+    Decade,
+    Century,
+    Millennium,
+    /// Day of week from Sunday (0) to Saturday (6)
+    DayOfWeek,
+    /// Day of week from Monday (1) to Sunday (7)
+    IsoDayOfWeek,
+    /// Day of year from 1 to 366
+    DayOfYear,
+    /// Seconds since 1970-01-01 00:00:00 UTC
+    Epoch,
+}
+
+impl FromStr for DateTimeField {
+    type Err = ParseDateTimeFieldError;
+
+    /// Parse a field name, which is case-insensitive and may be singular or plural.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let field = match s.trim().to_ascii_lowercase().as_str() {
+            "microsecond" | "microseconds" => Self::Microseconds,
+            "millisecond" | "milliseconds" => Self::Milliseconds,
+            "second" | "seconds" => Self::Second,
+            "minute" | "minutes" => Self::Minute,
+            "hour" | "hours" => Self::Hour,
+            "day" | "days" => Self::Day,
+            "week" | "weeks" => Self::Week,
+            "month" | "months" => Self::Month,
+            "quarter" => Self::Quarter,
+            "year" | "years" => Self::Year,
+            "decade" | "decades" => Self::Decade,
+            "century" | "centuries" => Self::Century,
+            "millennium" | "millennia" => Self::Millennium,
+            "dow" => Self::DayOfWeek,
+            "isodow" => Self::IsoDayOfWeek,
+            "doy" => Self::DayOfYear,
+            "epoch" => Self::Epoch,
+            _ => return Err(ParseDateTimeFieldError(s.to_string())),
+        };
+        Ok(field)
+    }
+}
+
+/// Day of week from Monday (1) to Sunday (7). 1970-01-01 is a Thursday.
+fn iso_day_of_week(date: Date) -> i64 {
+    (date.days() as i64 + 3).rem_euclid(7) + 1
+}
+
+/// The century of `year`, where the first century starts at year 1.
+fn century(year: i64) -> i64 {
+    if year > 0 {
+        (year + 99) / 100
+    } else {
+        -((99 - year) / 100)
+    }
+}
+
+/// The millennium of `year`, where the first millennium starts at year 1.
+fn millennium(year: i64) -> i64 {
+    if year > 0 {
+        (year + 999) / 1000
+    } else {
+        -((999 - year) / 1000)
+    }
+}
+
+/// Extract `field` from the timestamp.
+pub fn extract(field: DateTimeField, ts: Timestamp) -> f64 {
+    let date = ts.date();
+    let (year, month, day) = date.ymd();
+    let year = year as i64;
+    let time = ts.time().micros();
+    let second_micros = time % MICROS_PER_MINUTE;
+    match field {
+        DateTimeField::Microseconds => second_micros as f64,
+        DateTimeField::Milliseconds => second_micros as f64 / 1e3,
+        DateTimeField::Second => second_micros as f64 / 1e6,
+        DateTimeField::Minute => ((time % MICROS_PER_HOUR) / MICROS_PER_MINUTE) as f64,
+        DateTimeField::Hour => (time / MICROS_PER_HOUR) as f64,
+        DateTimeField::Day => day as f64,
+        DateTimeField::Week => {
+            // the ISO year of a week is the year of its Thursday
+            let thursday = date.days() as i64 - iso_day_of_week(date) + 4;
+            let (iso_year, _, _) = Date::from_days(thursday as i32).ymd();
+            ((thursday - days_from_civil(iso_year as i64, 1, 1)) / 7 + 1) as f64
+        }
+        DateTimeField::Month => month as f64,
+        DateTimeField::Quarter => ((month - 1) / 3 + 1) as f64,
+        DateTimeField::Year => year as f64,
+        DateTimeField::Decade => year.div_euclid(10) as f64,
+        DateTimeField::Century => century(year) as f64,
+        DateTimeField::Millennium => millennium(year) as f64,
+        DateTimeField::DayOfWeek => (iso_day_of_week(date) % 7) as f64,
+        DateTimeField::IsoDayOfWeek => iso_day_of_week(date) as f64,
+        DateTimeField::DayOfYear => (date.days() as i64 - days_from_civil(year, 1, 1) + 1) as f64,
+        DateTimeField::Epoch => ts.micros() as f64 / 1e6,
+    }
+}
+
+/// Truncate the timestamp to the precision of `field`, returning `None` if the field cannot be
+/// truncated to, e.g. [`DateTimeField::Epoch`].
+pub fn date_trunc(field: DateTimeField, ts: Timestamp) -> Option<Timestamp> {
+    let floor = |unit: i64| Timestamp::from_micros(ts.micros() - ts.micros().rem_euclid(unit));
+    let first_day =
+        |year: i64, month: u32| Date::from_ymd(year as i32, month, 1).map(Timestamp::from);
+    let (year, month, _) = ts.date().ymd();
+    let year = year as i64;
+    match field {
+        DateTimeField::Microseconds => Some(ts),
+        DateTimeField::Milliseconds => Some(floor(1000)),
+        DateTimeField::Second => Some(floor(MICROS_PER_SECOND)),
+        DateTimeField::Minute => Some(floor(MICROS_PER_MINUTE)),
+        DateTimeField::Hour => Some(floor(MICROS_PER_HOUR)),
+        DateTimeField::Day => Some(floor(MICROS_PER_DAY)),
+        DateTimeField::Week => {
+            let monday = ts.date().days() as i64 - iso_day_of_week(ts.date()) + 1;
+            Some(Timestamp::from(Date::from_days(monday as i32)))
+        }
+        DateTimeField::Month => first_day(year, month),
+        DateTimeField::Quarter => first_day(year, (month - 1) / 3 * 3 + 1),
+        DateTimeField::Year => first_day(year, 1),
+        DateTimeField::Decade => first_day(year - year.rem_euclid(10), 1),
+        DateTimeField::Century => first_day((century(year) - 1) * 100 + 1, 1),
+        DateTimeField::Millennium => first_day((millennium(year) - 1) * 1000 + 1, 1),
+        DateTimeField::DayOfWeek
+        | DateTimeField::IsoDayOfWeek
+        | DateTimeField::DayOfYear
+        | DateTimeField::Epoch => None,
+    }
+}
+
+/// Returns `i1 + i2` for a date or timestamp `i1` and an interval `i2`. The result is a
+/// timestamp, see [`Timestamp::checked_add_interval`]. Fails if the result is out of the range
+/// of timestamps.
+pub struct ExprAddInterval<I: Array>(pub PhantomData<I>);
+
+impl<I: Array> TryBinaryExprFunc<I, IntervalArray, TimestampArray> for ExprAddInterval<I>
+where
+    for<'a> I::RefItem<'a>: Into<Timestamp>,
+{
+    fn eval<'a>(&self, i1: I::RefItem<'a>, i2: Interval) -> Result<Timestamp> {
+        i1.into()
+            .checked_add_interval(i2)
+            .ok_or_else(|| anyhow!("timestamp out of range"))
+    }
+}
+
+/// Returns `i1 - i2` for two dates or timestamps as an interval of days and microseconds. Fails
+/// if the difference is out of the range of intervals.
+pub struct ExprTimestampSub<I1: Array, I2: Array>(pub PhantomData<(I1, I2)>);
+
+impl<I1: Array, I2: Array> TryBinaryExprFunc<I1, I2, IntervalArray> for ExprTimestampSub<I1, I2>
+where
+    for<'a> I1::RefItem<'a>: Into<Timestamp>,
+    for<'a> I2::RefItem<'a>: Into<Timestamp>,
+{
+    fn eval<'a>(&self, i1: I1::RefItem<'a>, i2: I2::RefItem<'a>) -> Result<Interval> {
+        i1.into()
+            .checked_sub_timestamp(i2.into())
+            .ok_or_else(|| anyhow!("interval out of range"))
+    }
+}
+
+/// Returns field `i1` of date or timestamp `i2`, e.g. `extract('year', ts)`. Fails if `i1` is not
+/// a [`DateTimeField`].
+pub struct ExprExtract<I: Array>(pub PhantomData<I>);
+
+impl<I: Array> TryBinaryExprFunc<StringArray, I, F64Array> for ExprExtract<I>
+where
+    for<'a> I::RefItem<'a>: Into<Timestamp>,
+{
+    fn eval<'a>(&self, i1: &'a str, i2: I::RefItem<'a>) -> Result<f64> {
+        Ok(extract(i1.parse()?, i2.into()))
+    }
+}
+
+/// Truncates date or timestamp `i2` to the precision of field `i1`, e.g.
+/// `date_trunc('month', ts)`. Fails if `i1` is not a [`DateTimeField`] that can be truncated to.
+pub struct ExprDateTrunc<I: Array>(pub PhantomData<I>);
+
+impl<I: Array> TryBinaryExprFunc<StringArray, I, TimestampArray> for ExprDateTrunc<I>
+where
+    for<'a> I::RefItem<'a>: Into<Timestamp>,
+{
+    fn eval<'a>(&self, i1: &'a str, i2: I::RefItem<'a>) -> Result<Timestamp> {
+        let field = i1.parse()?;
+        date_trunc(field, i2.into()).ok_or_else(|| anyhow!("cannot truncate to {:?}", field))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> Timestamp {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_extract() {
+        let t = ts("2021-01-03 04:05:06.789");
+        let cases = [
+            ("year", 2021.0),
+            ("quarter", 1.0),
+            ("month", 1.0),
+            ("day", 3.0),
+            ("hour", 4.0),
+            ("minute", 5.0),
+            ("second", 6.789),
+            ("milliseconds", 6789.0),
+            ("microseconds", 6789000.0),
+            // 2021-01-03 is a Sunday in the last ISO week of 2020
+            ("week", 53.0),
+            ("dow", 0.0),
+            ("isodow", 7.0),
+            ("doy", 3.0),
+            ("decade", 202.0),
+            ("century", 21.0),
+            ("millennium", 3.0),
+            ("epoch", 1609646706.789),
+        ];
+        for (field, expected) in cases {
+            assert_eq!(extract(field.parse().unwrap(), t), expected, "{}", field);
+        }
+        assert_eq!(extract(DateTimeField::Week, ts("2021-01-04")), 1.0);
+        assert_eq!(extract(DateTimeField::Century, ts("2000-12-31")), 20.0);
+        assert!("fortnight".parse::<DateTimeField>().is_err());
+    }
+
+    #[test]
+    fn test_date_trunc() {
+        let t = ts("2021-05-19 04:05:06.789");
+        let cases = [
+            ("milliseconds", "2021-05-19 04:05:06.789"),
+            ("second", "2021-05-19 04:05:06"),
+            ("minute", "2021-05-19 04:05:00"),
+            ("hour", "2021-05-19 04:00:00"),
+            ("day", "2021-05-19 00:00:00"),
+            ("week", "2021-05-17 00:00:00"),
+            ("month", "2021-05-01 00:00:00"),
+            ("quarter", "2021-04-01 00:00:00"),
+            ("year", "2021-01-01 00:00:00"),
+            ("decade", "2020-01-01 00:00:00"),
+            ("century", "2001-01-01 00:00:00"),
+            ("millennium", "2001-01-01 00:00:00"),
+        ];
+        for (field, expected) in cases {
+            assert_eq!(
+                date_trunc(field.parse().unwrap(), t).unwrap(),
+                ts(expected),
+                "{}",
+                field
+            );
+        }
+        assert_eq!(date_trunc(DateTimeField::Epoch, t), None);
+        assert_eq!(
+            date_trunc(DateTimeField::Hour, ts("1969-12-31 23:30:00")).unwrap(),
+            ts("1969-12-31 23:00:00")
+        );
+    }
+
+    #[test]
+    fn test_interval_arithmetic() {
+        let interval: Interval = "1 mon 1 day 01:00:00".parse().unwrap();
+        assert_eq!(
+            ts("2020-01-30 12:00:00").checked_add_interval(interval),
+            Some(ts("2020-03-01 13:00:00"))
+        );
+        let interval: Interval = "-1 mon".parse().unwrap();
+        assert_eq!(
+            ts("2021-03-31").checked_add_interval(interval),
+            Some(ts("2021-02-28"))
+        );
+        assert_eq!(
+            ts("2021-01-02 01:00:00")
+                .checked_sub_timestamp(ts("2021-01-01"))
+                .unwrap()
+                .to_string(),
+            "1 day 01:00:00"
+        );
+        assert_eq!(
+            ts("2021-01-01")
+                .checked_sub_timestamp(ts("2021-01-02 01:00:00"))
+                .unwrap()
+                .to_string(),
+            "-1 day -01:00:00"
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use anyhow::{anyhow, Result};
+
+use super::{
+    build_binary_expression, build_ternary_expression, build_unary_expression, CaseExpression,
+    Datum, Expression, ExpressionFunc, UnsupportedFunction,
+};
+use crate::{ArrayImpl, ColumnRef, DataChunk, DataType, ScalarImpl};
+
+/// Reads the input at position `idx`, which is expected to be of `data_type`.
+pub struct InputRef {
+    idx: usize,
+    data_type: DataType,
+}
+
+impl InputRef {
+    /// Position of the input
+    pub fn index(&self) -> usize {
+        self.idx
+    }
+}
+
+/// A constant, which is repeated for every row of the batch.
+pub struct Literal {
+    value: Option<ScalarImpl>,
+    data_type: DataType,
+}
+
+impl Literal {
+    /// The constant value, or `None` for `NULL`
+    pub fn value(&self) -> Option<&ScalarImpl> {
+        self.value.as_ref()
+    }
+}
+
+/// Applies a kernel to the results of the child expressions.
+pub struct FunctionCall {
+    kernel: Box<dyn Expression>,
+    children: Vec<ExprNode>,
+    return_type: DataType,
+}
+
+impl FunctionCall {
+    /// The arguments of the function
+    pub fn children(&self) -> &[ExprNode] {
+        &self.children
+    }
+}
+
+/// A node of an expression tree.
+///
+/// Leaves read inputs or produce constants, while a [`FunctionCall`] evaluates its children first
+/// and then applies a kernel, e.g. a [`BinaryExpression`](super::vectorize::BinaryExpression),
+/// to their results. For example, `a <= 10` is a function call on an input reference and a
+/// literal. A [`CaseExpression`] evaluates each of its branches on part of the rows only.
+pub enum ExprNode {
+    InputRef(InputRef),
+    Literal(Literal),
+    FunctionCall(FunctionCall),
+    Case(CaseExpression),
+}
+
+impl ExprNode {
+    /// Create a reference to the input at `idx` of `data_type`.
+    pub fn input_ref(idx: usize, data_type: DataType) -> Self {
+        Self::InputRef(InputRef { idx, data_type })
+    }
+
+    /// Create a constant of `value`.
+    pub fn literal(value: ScalarImpl) -> Self {
+        Self::Literal(Literal {
+            data_type: value.data_type(),
+            value: Some(value),
+        })
+    }
+
+    /// Create a `NULL` constant of `data_type`.
+    pub fn null(data_type: DataType) -> Self {
+        Self::Literal(Literal {
+            value: None,
+            data_type,
+        })
+    }
+
+    /// Create a call of the unary function `func`, returning an error if it does not support the
+    /// return type of `child`.
+    pub fn unary(func: ExpressionFunc, child: ExprNode) -> Result<Self, UnsupportedFunction> {
+        let i1 = child.return_type();
+        let kernel = build_unary_expression(func, i1)?;
+        let return_type = func.return_type(&[i1]);
+        Ok(Self::call(kernel, vec![child], return_type))
+    }
+
+    /// Create a call of the binary function `func`, returning an error if it does not support
+    /// the return types of `left` and `right`.
+    pub fn binary(
+        func: ExpressionFunc,
+        left: ExprNode,
+        right: ExprNode,
+    ) -> Result<Self, UnsupportedFunction> {
+        let (i1, i2) = (left.return_type(), right.return_type());
+        let kernel = build_binary_expression(func, i1, i2)?;
+        let return_type = func.return_type(&[i1, i2]);
+        Ok(Self::call(kernel, vec![left, right], return_type))
+    }
+
+    /// Create a call of the ternary function `func`, returning an error if it does not support
+    /// the return types of `c1`, `c2` and `c3`.
+    pub fn ternary(
+        func: ExpressionFunc,
+        c1: ExprNode,
+        c2: ExprNode,
+        c3: ExprNode,
+    ) -> Result<Self, UnsupportedFunction> {
+        let (i1, i2, i3) = (c1.return_type(), c2.return_type(), c3.return_type());
+        let kernel = build_ternary_expression(func, i1, i2, i3)?;
+        let return_type = func.return_type(&[i1, i2, i3]);
+        Ok(Self::call(kernel, vec![c1, c2, c3], return_type))
+    }
+
+    /// Create a call of an arbitrary kernel, which takes the results of `children` as inputs
+    /// and returns an array of `return_type`.
+    pub fn call(
+        kernel: Box<dyn Expression>,
+        children: Vec<ExprNode>,
+        return_type: DataType,
+    ) -> Self {
+        Self::FunctionCall(FunctionCall {
+            kernel,
+            children,
+            return_type,
+        })
+    }
+
+    /// Data type of the result of this expression
+    pub fn return_type(&self) -> &DataType {
+        match self {
+            Self::InputRef(input) => &input.data_type,
+            Self::Literal(literal) => &literal.data_type,
+            Self::FunctionCall(call) => &call.return_type,
+            Self::Case(case) => case.return_type(),
+        }
+    }
+
+    /// Evaluate the expression on `data`, producing `num_rows` rows.
+    pub fn eval(&self, data: &[&ArrayImpl], num_rows: usize) -> Result<ArrayImpl> {
+        let result = self.eval_datum(data)?.to_array(num_rows);
+        if result.len() != num_rows {
+            return Err(anyhow!(
+                "Expect {} rows from expression, get {}",
+                num_rows,
+                result.len()
+            ));
+        }
+        Ok(result)
+    }
+
+    /// Evaluate the expression on `data`, keeping the result as a constant if it does not depend
+    /// on any input, so that it is passed to the kernels without being expanded.
+    fn eval_datum(&self, data: &[&ArrayImpl]) -> Result<Datum> {
+        match self {
+            Self::InputRef(input) => {
+                let array = data
+                    .get(input.idx)
+                    .ok_or_else(|| anyhow!("Input {} out of bounds", input.idx))?;
+                if array.data_type() != input.data_type {
+                    return Err(anyhow!(
+                        "Input {} should be of type {}, get {}",
+                        input.idx,
+                        input.data_type,
+                        array.data_type()
+                    ));
+                }
+                Ok(Datum::array((*array).clone()))
+            }
+            Self::Literal(literal) => Ok(Datum::scalar(
+                literal.value.as_ref().map(ScalarImpl::as_scalar_ref),
+                &literal.data_type,
+            )),
+            Self::FunctionCall(call) => {
+                let inputs = call
+                    .children
+                    .iter()
+                    .map(|child| child.eval_datum(data))
+                    .collect::<Result<Vec<_>>>()?;
+                let result = call.kernel.eval_datums(&inputs)?;
+                if result.len() == 1 && inputs.iter().all(Datum::is_scalar) {
+                    Ok(Datum::scalar(result.get(0), &result.data_type()))
+                } else {
+                    Ok(Datum::array(result))
+                }
+            }
+            Self::Case(case) => match data.first() {
+                Some(array) => Ok(Datum::array(case.eval(data, array.len())?)),
+                None => {
+                    let result = case.eval(data, 1)?;
+                    Ok(Datum::scalar(result.get(0), &result.data_type()))
+                }
+            },
+        }
+    }
+}
+
+impl From<CaseExpression> for ExprNode {
+    fn from(case: CaseExpression) -> Self {
+        Self::Case(case)
+    }
+}
+
+impl Expression for ExprNode {
+    /// Evaluate the expression on `data`, which must have the same length. An expression without
+    /// inputs produces one row.
+    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+        let num_rows = data.first().map_or(1, |array| array.len());
+        if data.iter().any(|array| array.len() != num_rows) {
+            return Err(anyhow!("Inputs have different lengths"));
+        }
+        self.eval(data, num_rows)
+    }
+
+    /// Evaluate the expression on the columns of `chunk` referenced by `inputs`, producing one
+    /// row for each row of `chunk`.
+    fn eval_chunk(&self, chunk: &DataChunk, inputs: &[ColumnRef]) -> Result<ArrayImpl> {
+        let data = inputs
+            .iter()
+            .map(|input| {
+                chunk
+                    .column(input)
+                    .ok_or_else(|| anyhow!("No column {:?} in {}", input, chunk.schema()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.eval(&data, chunk.num_rows())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Array, ArrayBuilder, ArrayLength, BoolArray, Field, I32Array, I64Array, ListArrayBuilder,
+        Schema, StringArray,
+    };
+
+    #[test]
+    fn test_expression_tree() {
+        // a <= b, contains(c, 'x'), a == 2
+        let a = || ExprNode::input_ref(0, DataType::Int32);
+        let le = ExprNode::binary(
+            ExpressionFunc::CmpLe,
+            a(),
+            ExprNode::input_ref(1, DataType::Int64),
+        )
+        .unwrap();
+        let contains = ExprNode::binary(
+            ExpressionFunc::StrContains,
+            ExprNode::input_ref(2, DataType::String),
+            ExprNode::literal(ScalarImpl::String("x".to_string())),
+        )
+        .unwrap();
+        let eq = ExprNode::binary(
+            ExpressionFunc::CmpEq,
+            a(),
+            ExprNode::literal(ScalarImpl::Int16(2)),
+        )
+        .unwrap();
+        assert_eq!(le.return_type(), &DataType::Bool);
+
+        let data: [ArrayImpl; 3] = [
+            I32Array::from_slice(&[Some(1), Some(2), None]).into(),
+            I64Array::from_slice(&[Some(3), Some(1), Some(0)]).into(),
+            StringArray::from_slice(&[Some("xy"), Some("z"), Some("x")]).into(),
+        ];
+        let data = data.iter().collect::<Vec<_>>();
+        let check = |expr: &ExprNode, expected: &[Option<bool>]| {
+            let result: BoolArray = expr.eval_expr(&data).unwrap().try_into().unwrap();
+            assert_eq!(result.iter().collect::<Vec<_>>(), expected);
+        };
+        check(&le, &[Some(true), Some(false), None]);
+        check(&contains, &[Some(true), Some(false), Some(true)]);
+        check(&eq, &[Some(false), Some(true), None]);
+
+        let eq_null =
+            ExprNode::binary(ExpressionFunc::CmpEq, a(), ExprNode::null(DataType::Int32)).unwrap();
+        check(&eq_null, &[None, None, None]);
+
+        assert!(ExprNode::binary(
+            ExpressionFunc::StrContains,
+            a(),
+            ExprNode::input_ref(2, DataType::String)
+        )
+        .is_err());
+        assert!(ExprNode::input_ref(1, DataType::Int32)
+            .eval_expr(&data)
+            .is_err());
+        assert!(ExprNode::input_ref(3, DataType::Int32)
+            .eval_expr(&data)
+            .is_err());
+    }
+
+    #[test]
+    fn test_expression_tree_on_chunk() {
+        let mut tags = ListArrayBuilder::with_type(&"list(string)".parse().unwrap(), 2);
+        tags.push(None);
+        tags.push(None);
+        let tags = tags.finish();
+        let schema = Schema::new(vec![Field::new("tags", tags.data_type())]);
+        let chunk = DataChunk::new(schema, vec![tags.into()]).unwrap();
+
+        let len = ExprNode::call(
+            Box::new(ArrayLength),
+            vec![ExprNode::input_ref(
+                0,
+                chunk.schema().field(0).data_type().clone(),
+            )],
+            DataType::Int32,
+        );
+        let result: I32Array = len
+            .eval_chunk(&chunk, &["tags".into()])
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(result.iter().collect::<Vec<_>>(), [None, None]);
+
+        let one = ExprNode::literal(ScalarImpl::Int32(1));
+        assert_eq!(one.eval_chunk(&chunk, &[]).unwrap().len(), 2);
+        assert_eq!(one.eval_expr(&[]).unwrap().len(), 1);
+
+        // constants are only expanded for the result
+        let one = || ExprNode::literal(ScalarImpl::Int32(1));
+        let eq = ExprNode::binary(ExpressionFunc::CmpEq, one(), one()).unwrap();
+        let result: BoolArray = eq.eval_chunk(&chunk, &[]).unwrap().try_into().unwrap();
+        assert_eq!(result.iter().collect::<Vec<_>>(), [Some(true), Some(true)]);
+    }
+}
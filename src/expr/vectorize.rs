@@ -1,82 +1,503 @@
 use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
 
-use crate::{Array, ArrayBuilder, ArrayImpl, Expression, Scalar, TypeMismatch};
+use crate::{
+    Array, ArrayBuilder, ArrayImpl, DataType, Datum, Expression, Scalar, StringArrayBuilder,
+    StringWriter, TypeMismatch,
+};
 use anyhow::{anyhow, Result};
+use thiserror::Error;
 
-/// A trait over all binary scalar functions, which takes `I1` and `I2` as input parameter, and
-/// outputs array of type `O`.
-pub trait BinaryExprFunc<I1: Array, I2: Array, O: Array> {
-    fn eval<'a>(&self, i1: I1::RefItem<'a>, i2: I2::RefItem<'a>) -> O::OwnedItem;
+/// An error of a fallible function on one row of a batch.
+#[derive(Error, Debug)]
+#[error("{source} in row {row} with inputs ({})", .inputs.join(", "))]
+pub struct RowError {
+    /// Position of the row in the batch
+    pub row: usize,
+    /// The inputs of the row, formatted with [`Debug`](std::fmt::Debug)
+    pub inputs: Vec<String>,
+    /// The error returned by the function
+    pub source: anyhow::Error,
 }
 
-/// Represents a binary expression which taks `I1` and `I2` as input parameter. and outputs aray of
-/// type `O`.
-///
-/// [`BinaryExpression`] automatically vectorizes the scalar function to a vectorized one, while
-/// erasing the concreate array type. Therefore, users simple call
-/// `BinaryExpression::eval(ArrayImpl, ArrayImpl)`, while developers only need to provide
-/// implementation for functions link `cmp_le(i32, i32)`.
-///
-/// [`BinaryExpression`] also erases lifetime from each [`BinaryExprFunc`], so that we can pass
-/// `&ArrayImpl` into the `eval_batch` function instead of specifying a lifetime.
-pub struct BinaryExpression<I1: Array, I2: Array, O: Array, F> {
-    expr: F,
-    _phantom: PhantomData<(I1, I2, O)>,
-}
+/// Collects the [`RowError`]s of expressions with [`ErrorPolicy::Null`]. Clones share the same
+/// errors, so that the caller can keep one clone and read the errors after evaluation.
+#[derive(Clone, Default)]
+pub struct RowErrors(Arc<Mutex<Vec<RowError>>>);
 
-/// Implement [`BinaryExpression`] for any given scalar function `F`.
-///
-/// Note that as we cannot add `From<&'a ArrayImpl>` bound on [`Array`], so we have to specify them
-/// here.
-impl<'a, I1: Array, I2: Array, O: Array, F> BinaryExpression<I1, I2, O, F>
-where
-    &'a I1: TryFrom<&'a ArrayImpl, Error = TypeMismatch>,
-    &'a I2: TryFrom<&'a ArrayImpl, Error = TypeMismatch>,
-    F: BinaryExprFunc<I1, I2, O>,
-{
-    /// Create a binary expression from existing function
-    ///
-    /// Previously (maybe nighly toolchain 2021-12-15), this function is not possible to be
-    /// compiled due to some lifetime diagnose bug in the Rust compiler.
-    pub fn new(expr: F) -> Self {
-        Self {
-            expr,
-            _phantom: PhantomData,
-        }
+impl RowErrors {
+    /// Create an empty collector.
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    /// Evaluate the expression with the given array.
-    pub fn eval_batch(&self, i1: &'a ArrayImpl, i2: &'a ArrayImpl) -> Result<ArrayImpl> {
-        let i1a: &'a I1 = i1.try_into()?;
-        let i2a: &'a I2 = i2.try_into()?;
+    /// Take the errors collected so far.
+    pub fn take(&self) -> Vec<RowError> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
 
-        assert_eq!(i1.len(), i2.len(), "array lenghth mismatch");
+    /// Number of errors collected so far
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
 
-        let mut builder = O::Builder::with_capacity(i1.len());
+    /// Whether no error is collected.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
 
-        for (i1, i2) in i1a.iter().zip(i2a.iter()) {
-            match (i1, i2) {
-                (Some(i1), Some(i2)) => builder.push(Some(self.expr.eval(i1, i2).as_scalar_ref())),
-                _ => builder.push(None),
-            }
+/// Decides what a fallible expression does when the function fails on a row.
+#[derive(Clone, Default)]
+pub enum ErrorPolicy {
+    /// Fail the whole batch with a [`RowError`]
+    #[default]
+    Fail,
+    /// Output `NULL` for the row and push the [`RowError`] to the collector
+    Null(RowErrors),
+}
+
+/// The policy of infallible functions, whose errors never happen
+const FAIL: &ErrorPolicy = &ErrorPolicy::Fail;
+
+/// Push `result` of the function on `row` into `builder`, or handle the error by `policy`.
+/// `inputs` formats the inputs of the row, and is only called on error.
+pub(super) fn push_result<B: ArrayBuilder>(
+    builder: &mut B,
+    policy: &ErrorPolicy,
+    row: usize,
+    result: Result<<B::Array as Array>::OwnedItem>,
+    inputs: impl FnOnce() -> Vec<String>,
+) -> Result<()> {
+    match result {
+        Ok(value) => {
+            builder.push(Some(value.as_scalar_ref()));
+            Ok(())
         }
+        Err(source) => handle_error(builder, policy, row, source, inputs),
+    }
+}
 
-        Ok(builder.finish().into())
+/// Push the string that `write` writes for `row` into `builder`, or handle the error by
+/// `policy`. `inputs` formats the inputs of the row, and is only called on error.
+fn push_written(
+    builder: &mut StringArrayBuilder,
+    policy: &ErrorPolicy,
+    row: usize,
+    write: impl FnOnce(&mut StringWriter<'_>) -> Result<()>,
+    inputs: impl FnOnce() -> Vec<String>,
+) -> Result<()> {
+    match builder.push_with(write) {
+        Ok(()) => Ok(()),
+        Err(source) => handle_error(builder, policy, row, source, inputs),
     }
 }
 
-impl<I1: Array, I2: Array, O: Array, F> Expression for BinaryExpression<I1, I2, O, F>
+fn handle_error<B: ArrayBuilder>(
+    builder: &mut B,
+    policy: &ErrorPolicy,
+    row: usize,
+    source: anyhow::Error,
+    inputs: impl FnOnce() -> Vec<String>,
+) -> Result<()> {
+    let error = RowError {
+        row,
+        inputs: inputs(),
+        source,
+    };
+    match policy {
+        ErrorPolicy::Fail => Err(error.into()),
+        ErrorPolicy::Null(errors) => {
+            errors.0.lock().unwrap().push(error);
+            builder.push(None);
+            Ok(())
+        }
+    }
+}
+
+/// An input of a vectorized expression, either an array or a constant item read once from a
+/// [`Datum`].
+enum Input<'a, A: Array> {
+    Array(&'a A),
+    Scalar(Option<A::RefItem<'a>>),
+}
+
+impl<'a, A: Array> Input<'a, A>
 where
-    for<'a> &'a I1: TryFrom<&'a ArrayImpl, Error = TypeMismatch>,
-    for<'a> &'a I2: TryFrom<&'a ArrayImpl, Error = TypeMismatch>,
-    F: BinaryExprFunc<I1, I2, O>,
+    &'a A: TryFrom<&'a ArrayImpl, Error = TypeMismatch>,
 {
-    fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
-        if data.len() != 2 {
-            return Err(anyhow!("Except two inputs for BinaryExpression"));
+    fn new(datum: &'a Datum) -> Result<Self, TypeMismatch> {
+        let array: &'a A = datum.as_array().try_into()?;
+        Ok(if datum.is_scalar() {
+            Self::Scalar(array.get(0))
+        } else {
+            Self::Array(array)
+        })
+    }
+
+    /// Number of rows, or `None` for a constant
+    fn len(&self) -> Option<usize> {
+        match self {
+            Self::Array(array) => Some(array.len()),
+            Self::Scalar(_) => None,
         }
-        self.eval_batch(data[0], data[1])
     }
+
+    #[inline]
+    fn get(&self, idx: usize) -> Option<A::RefItem<'a>> {
+        match self {
+            Self::Array(array) => array.get(idx),
+            Self::Scalar(item) => *item,
+        }
+    }
+}
+
+/// Generates the traits of scalar functions with a fixed number of inputs, and the expressions
+/// which vectorize such functions. Functions of `$TryExprFunc` may fail on some rows, functions
+/// of `$NullableExprFunc` decide the result of `NULL` inputs themselves, and functions of
+/// `$StringExprFunc` write their string result into the output array directly.
+///
+/// Each input is listed as `{ I1, i1 }`, where `I1` is the array type and `i1` names the argument.
+macro_rules! impl_vectorized_expression {
+    (
+        $ExprFunc:ident, $Expression:ident, $TryExprFunc:ident, $TryExpression:ident,
+        $NullableExprFunc:ident, $NullableExpression:ident, $StringExprFunc:ident,
+        $StringExpression:ident, $num:literal, [$({ $I:ident, $i:ident }),+]
+    ) => {
+        #[doc = concat!(
+            "A trait over all scalar functions with ", $num, " input(s) of type ",
+            $("`", stringify!($I), "` ",)+ "which outputs array of type `O`."
+        )]
+        pub trait $ExprFunc<$($I: Array,)+ O: Array> {
+            fn eval<'a>(&self, $($i: $I::RefItem<'a>),+) -> O::OwnedItem;
+        }
+
+        #[doc = concat!(
+            "A trait over all fallible scalar functions with ", $num, " input(s) of type ",
+            $("`", stringify!($I), "` ",)+ "which outputs array of type `O`."
+        )]
+        pub trait $TryExprFunc<$($I: Array,)+ O: Array> {
+            fn eval<'a>(&self, $($i: $I::RefItem<'a>),+) -> Result<O::OwnedItem>;
+        }
+
+        #[doc = concat!(
+            "A trait over all scalar functions with ", $num, " nullable input(s) of type ",
+            $("`", stringify!($I), "` ",)+ "which outputs array of type `O`. `None` stands for ",
+            "`NULL` in both the inputs and the output."
+        )]
+        pub trait $NullableExprFunc<$($I: Array,)+ O: Array> {
+            fn eval<'a>(&self, $($i: Option<$I::RefItem<'a>>),+) -> Option<O::OwnedItem>;
+        }
+
+        #[doc = concat!(
+            "A trait over all fallible scalar functions with ", $num, " input(s) of type ",
+            $("`", stringify!($I), "` ",)+ "which output a string. The string is written into ",
+            "a [`StringWriter`] piece by piece, so that no `String` is allocated for each row."
+        )]
+        pub trait $StringExprFunc<$($I: Array),+> {
+            fn eval<'a>(&self, $($i: $I::RefItem<'a>,)+ writer: &mut StringWriter<'_>) -> Result<()>;
+        }
+
+        #[doc = concat!(
+            "Represents an expression with ", $num, " input(s), which vectorizes a [`",
+            stringify!($ExprFunc), "`] and erases the concrete array types and lifetimes. ",
+            "The result is `NULL` if any input is `NULL`."
+        )]
+        pub struct $Expression<$($I: Array,)+ O: Array, F> {
+            expr: F,
+            return_type: Option<DataType>,
+            _phantom: PhantomData<($($I,)+ O)>,
+        }
+
+        impl<$($I: Array,)+ O: Array, F> $Expression<$($I,)+ O, F> {
+            /// Create an expression from existing function
+            pub fn new(expr: F) -> Self {
+                Self {
+                    expr,
+                    return_type: None,
+                    _phantom: PhantomData,
+                }
+            }
+
+            fn policy(&self) -> &ErrorPolicy {
+                FAIL
+            }
+        }
+
+        #[doc = concat!(
+            "Represents an expression with ", $num, " input(s), which vectorizes a [`",
+            stringify!($TryExprFunc), "`] and erases the concrete array types and lifetimes. ",
+            "The result is `NULL` if any input is `NULL`, and an error on any row is handled by ",
+            "the [`ErrorPolicy`]."
+        )]
+        pub struct $TryExpression<$($I: Array,)+ O: Array, F> {
+            expr: F,
+            return_type: Option<DataType>,
+            policy: ErrorPolicy,
+            _phantom: PhantomData<($($I,)+ O)>,
+        }
+
+        impl<$($I: Array,)+ O: Array, F> $TryExpression<$($I,)+ O, F> {
+            /// Create an expression from existing function
+            pub fn new(expr: F) -> Self {
+                Self {
+                    expr,
+                    return_type: None,
+                    policy: ErrorPolicy::Fail,
+                    _phantom: PhantomData,
+                }
+            }
+
+            /// Set how errors of the function are handled, which is [`ErrorPolicy::Fail`] by
+            /// default.
+            pub fn with_error_policy(self, policy: ErrorPolicy) -> Self {
+                Self { policy, ..self }
+            }
+
+            fn policy(&self) -> &ErrorPolicy {
+                &self.policy
+            }
+        }
+
+        #[doc = concat!(
+            "Represents an expression with ", $num, " input(s), which vectorizes a [`",
+            stringify!($NullableExprFunc), "`] and erases the concrete array types and ",
+            "lifetimes. The function is called on every row, including rows with `NULL` inputs."
+        )]
+        pub struct $NullableExpression<$($I: Array,)+ O: Array, F> {
+            expr: F,
+            return_type: Option<DataType>,
+            _phantom: PhantomData<($($I,)+ O)>,
+        }
+
+        impl<$($I: Array,)+ O: Array, F> $NullableExpression<$($I,)+ O, F> {
+            /// Create an expression from existing function
+            pub fn new(expr: F) -> Self {
+                Self {
+                    expr,
+                    return_type: None,
+                    _phantom: PhantomData,
+                }
+            }
+        }
+
+        #[doc = concat!(
+            "Represents an expression with ", $num, " input(s), which vectorizes a [`",
+            stringify!($StringExprFunc), "`] and erases the concrete array types and ",
+            "lifetimes. The result is `NULL` if any input is `NULL`, and an error on any row is ",
+            "handled by the [`ErrorPolicy`]."
+        )]
+        pub struct $StringExpression<$($I: Array,)+ F> {
+            expr: F,
+            return_type: Option<DataType>,
+            policy: ErrorPolicy,
+            _phantom: PhantomData<($($I,)+)>,
+        }
+
+        impl<$($I: Array,)+ F> $StringExpression<$($I,)+ F> {
+            /// Create an expression from existing function
+            pub fn new(expr: F) -> Self {
+                Self {
+                    expr,
+                    return_type: None,
+                    policy: ErrorPolicy::Fail,
+                    _phantom: PhantomData,
+                }
+            }
+
+            /// Set how errors of the function are handled, which is [`ErrorPolicy::Fail`] by
+            /// default.
+            pub fn with_error_policy(self, policy: ErrorPolicy) -> Self {
+                Self { policy, ..self }
+            }
+
+            fn policy(&self) -> &ErrorPolicy {
+                &self.policy
+            }
+        }
+
+        impl_vectorized_expression! {
+            @impl propagate, [value anyhow::Ok], [O], O::Builder, $ExprFunc, $Expression, $num,
+            [$({ $I, $i }),+]
+        }
+        impl_vectorized_expression! {
+            @impl propagate, [value std::convert::identity], [O], O::Builder, $TryExprFunc,
+            $TryExpression, $num, [$({ $I, $i }),+]
+        }
+        impl_vectorized_expression! {
+            @impl nullable, [value anyhow::Ok], [O], O::Builder, $NullableExprFunc,
+            $NullableExpression, $num, [$({ $I, $i }),+]
+        }
+        impl_vectorized_expression! {
+            @impl propagate, [write], [], StringArrayBuilder, $StringExprFunc, $StringExpression,
+            $num, [$({ $I, $i }),+]
+        }
+    };
+    // `$mode` is either `propagate` or `nullable`, and `$push` decides how the result of `eval`
+    // is pushed into the `$Builder`, see the `@push` rules. `$O` is the type parameter of the
+    // output array, if any.
+    (
+        @impl $mode:ident, $push:tt, [$($O:ident)?], $Builder:ty, $ExprFunc:ident,
+        $Expression:ident, $num:literal, [$({ $I:ident, $i:ident }),+]
+    ) => {
+        impl<$($I: Array,)+ $($O: Array,)? F> $Expression<$($I,)+ $($O,)? F> {
+            /// Set the data type of the result, so that its parameters, such as the timezone of a
+            /// timestamp, are kept. By default, the result has the default parameters of its
+            /// array type.
+            pub fn with_return_type(self, return_type: DataType) -> Self {
+                Self {
+                    return_type: Some(return_type),
+                    ..self
+                }
+            }
+        }
+
+        impl<'a, $($I: Array,)+ $($O: Array,)? F> $Expression<$($I,)+ $($O,)? F>
+        where
+            $(&'a $I: TryFrom<&'a ArrayImpl, Error = TypeMismatch>,)+
+            F: $ExprFunc<$($I,)+ $($O)?>,
+        {
+            /// Evaluate the expression with the given inputs, of which some may be constants. A
+            /// constant is not expanded to the length of the batch, and the result has one row if
+            /// all inputs are constants.
+            pub fn eval_datums(&self, $($i: &'a Datum),+) -> Result<ArrayImpl> {
+                self.eval_inputs($(Input::<'a, $I>::new($i)?),+)
+            }
+
+            /// Evaluate the expression with the given arrays.
+            pub fn eval_batch(&self, $($i: &'a ArrayImpl),+) -> Result<ArrayImpl> {
+                self.eval_inputs($(Input::Array($i.try_into()?)),+)
+            }
+
+            fn eval_inputs(&self, $($i: Input<'a, $I>),+) -> Result<ArrayImpl> {
+                let lens = [$($i.len()),+];
+                let len = lens.iter().flatten().next().copied().unwrap_or(1);
+                assert!(
+                    lens.iter().flatten().all(|l| *l == len),
+                    "array lenghth mismatch"
+                );
+
+                let mut builder = match &self.return_type {
+                    Some(return_type) => <$Builder>::with_type(return_type, len),
+                    None => <$Builder>::with_capacity(len),
+                };
+                impl_vectorized_expression!(@eval $mode, self, builder, len, $push, [$({ $I, $i }),+]);
+                Ok(builder.finish().into())
+            }
+        }
+
+        impl<$($I: Array,)+ $($O: Array,)? F> Expression for $Expression<$($I,)+ $($O,)? F>
+        where
+            $(for<'a> &'a $I: TryFrom<&'a ArrayImpl, Error = TypeMismatch>,)+
+            F: $ExprFunc<$($I,)+ $($O)?>,
+        {
+            fn eval_expr(&self, data: &[&ArrayImpl]) -> Result<ArrayImpl> {
+                match data {
+                    [$($i),+] => self.eval_batch($($i),+),
+                    _ => Err(anyhow!(
+                        concat!("Except ", $num, " input(s) for ", stringify!($Expression))
+                    )),
+                }
+            }
+
+            fn eval_datums(&self, data: &[Datum]) -> Result<ArrayImpl> {
+                match data {
+                    [$($i),+] => self.eval_datums($($i),+),
+                    _ => Err(anyhow!(
+                        concat!("Except ", $num, " input(s) for ", stringify!($Expression))
+                    )),
+                }
+            }
+        }
+    };
+    (@eval propagate, $self:ident, $builder:ident, $len:ident, $push:tt, [$({ $I:ident, $i:ident }),+]) => {
+        // a `NULL` constant makes every row `NULL`
+        if $(matches!($i, Input::Scalar(None)))||+ {
+            for _ in 0..$len {
+                $builder.push(None);
+            }
+            return Ok($builder.finish().into());
+        }
+
+        impl_vectorized_expression!(@fast_path $self, $builder, $push, [$({ $I, $i }),+]);
+
+        for idx in 0..$len {
+            match ($($i.get(idx),)+) {
+                ($(Some($i),)+) => impl_vectorized_expression!(
+                    @push $push, $self, $builder, idx, ($($i),+), || vec![$(format!("{:?}", $i)),+]
+                ),
+                _ => $builder.push(None),
+            }
+        }
+    };
+    (@eval nullable, $self:ident, $builder:ident, $len:ident, $push:tt, [$({ $I:ident, $i:ident }),+]) => {
+        for idx in 0..$len {
+            let value = $self.expr.eval($($i.get(idx)),+);
+            $builder.push(value.as_ref().map(Scalar::as_scalar_ref));
+        }
+    };
+    // Binary expressions of an array and a non-null constant, e.g. `col < 10` or
+    // `contains(col, 'x')`, iterate the array alone and pass the same constant to every call.
+    (@fast_path $self:ident, $builder:ident, $push:tt, [{ $I1:ident, $i1:ident }, { $I2:ident, $i2:ident }]) => {
+        match (&$i1, &$i2) {
+            (Input::Array(array), Input::Scalar(Some(constant))) => {
+                for (idx, item) in array.iter().enumerate() {
+                    match item {
+                        Some(item) => impl_vectorized_expression!(
+                            @push $push, $self, $builder, idx, (item, *constant),
+                            || vec![format!("{:?}", item), format!("{:?}", constant)]
+                        ),
+                        None => $builder.push(None),
+                    }
+                }
+                return Ok($builder.finish().into());
+            }
+            (Input::Scalar(Some(constant)), Input::Array(array)) => {
+                for (idx, item) in array.iter().enumerate() {
+                    match item {
+                        Some(item) => impl_vectorized_expression!(
+                            @push $push, $self, $builder, idx, (*constant, item),
+                            || vec![format!("{:?}", constant), format!("{:?}", item)]
+                        ),
+                        None => $builder.push(None),
+                    }
+                }
+                return Ok($builder.finish().into());
+            }
+            _ => {}
+        }
+    };
+    (@fast_path $self:ident, $builder:ident, $push:tt, [$($input:tt),+]) => {};
+    // Functions returning a value, which `$wrap` turns into a `Result`
+    (@push [value $wrap:path], $self:ident, $builder:ident, $idx:ident, ($($arg:expr),+), $inputs:expr) => {
+        push_result(&mut $builder, $self.policy(), $idx, $wrap($self.expr.eval($($arg),+)), $inputs)?
+    };
+    // Functions writing a string
+    (@push [write], $self:ident, $builder:ident, $idx:ident, ($($arg:expr),+), $inputs:expr) => {
+        push_written(
+            &mut $builder,
+            $self.policy(),
+            $idx,
+            |writer| $self.expr.eval($($arg,)+ writer),
+            $inputs,
+        )?
+    };
+}
+
+impl_vectorized_expression! {
+    UnaryExprFunc, UnaryExpression, TryUnaryExprFunc, TryUnaryExpression,
+    NullableUnaryExprFunc, NullableUnaryExpression, UnaryStringExprFunc, UnaryStringExpression,
+    "one", [{ I1, i1 }]
+}
+impl_vectorized_expression! {
+    BinaryExprFunc, BinaryExpression, TryBinaryExprFunc, TryBinaryExpression,
+    NullableBinaryExprFunc, NullableBinaryExpression, BinaryStringExprFunc,
+    BinaryStringExpression, "two", [{ I1, i1 }, { I2, i2 }]
+}
+impl_vectorized_expression! {
+    TernaryExprFunc, TernaryExpression, TryTernaryExprFunc, TryTernaryExpression,
+    NullableTernaryExprFunc, NullableTernaryExpression, TernaryStringExprFunc,
+    TernaryStringExpression, "three", [{ I1, i1 }, { I2, i2 }, { I3, i3 }]
 }
 
 #[cfg(test)]
@@ -84,7 +505,7 @@ mod tests {
     use super::*;
     use crate::expr::cmp::*;
     use crate::expr::string::*;
-    use crate::{Array, BoolArray, I32Array, I64Array, StringArray};
+    use crate::{Array, BoolArray, DataType, I32Array, I64Array, ScalarRefImpl, StringArray};
 
     fn check_array_eq<'a, A: Array>(array: &'a A, vec: &[Option<A::RefItem<'a>>])
     where
@@ -151,4 +572,83 @@ mod tests {
             &[Some(true), Some(false), None],
         );
     }
+
+    #[test]
+    fn test_scalar_broadcast() {
+        let expr =
+            BinaryExpression::<I32Array, I64Array, BoolArray, _>::new(ExprCmpLe::<_, _, I64Array>(
+                PhantomData,
+            ));
+        let column = Datum::array(I32Array::from_slice(&[Some(0), Some(20), None]).into());
+        let ten = Datum::scalar(Some(ScalarRefImpl::Int64(10)), &DataType::Int64);
+        let result = expr.eval_datums(&column, &ten).unwrap();
+        check_array_eq::<BoolArray>(
+            &result.try_into().unwrap(),
+            &[Some(true), Some(false), None],
+        );
+
+        let expr = BinaryExpression::<StringArray, StringArray, BoolArray, _>::new(ExprStrContains);
+        let haystack = Datum::array(StringArray::from_slice(&[Some("abc"), Some("xyz")]).into());
+        let needle = Datum::scalar(Some(ScalarRefImpl::String("b")), &DataType::String);
+        let result = expr.eval_datums(&haystack, &needle).unwrap();
+        check_array_eq::<BoolArray>(&result.try_into().unwrap(), &[Some(true), Some(false)]);
+
+        // constant on the left, `NULL` constants, and constants only
+        let result = expr.eval_datums(&needle, &haystack).unwrap();
+        check_array_eq::<BoolArray>(&result.try_into().unwrap(), &[Some(false), Some(false)]);
+        let null = Datum::scalar(None, &DataType::String);
+        let result = expr.eval_datums(&haystack, &null).unwrap();
+        check_array_eq::<BoolArray>(&result.try_into().unwrap(), &[None, None]);
+        let result = expr.eval_datums(&needle, &needle).unwrap();
+        check_array_eq::<BoolArray>(&result.try_into().unwrap(), &[Some(true)]);
+    }
+
+    /// Returns `i1 <= i2 && i2 <= i3`
+    struct ExprBetween;
+
+    impl TernaryExprFunc<I32Array, I32Array, I32Array, BoolArray> for ExprBetween {
+        fn eval(&self, i1: i32, i2: i32, i3: i32) -> bool {
+            i1 <= i2 && i2 <= i3
+        }
+    }
+
+    #[test]
+    fn test_ternary_between() {
+        let expr = TernaryExpression::new(ExprBetween);
+        let result = expr
+            .eval_expr(&[
+                &I32Array::from_slice(&[Some(0), Some(0), None]).into(),
+                &I32Array::from_slice(&[Some(1), Some(2), Some(1)]).into(),
+                &I32Array::from_slice(&[Some(1), Some(1), Some(1)]).into(),
+            ])
+            .unwrap();
+        check_array_eq::<BoolArray>(
+            &result.try_into().unwrap(),
+            &[Some(true), Some(false), None],
+        );
+
+        let result = Expression::eval_datums(
+            &expr,
+            &[
+                Datum::scalar(Some(ScalarRefImpl::Int32(1)), &DataType::Int32),
+                Datum::array(I32Array::from_slice(&[Some(1), Some(2), None]).into()),
+                Datum::scalar(Some(ScalarRefImpl::Int32(1)), &DataType::Int32),
+            ],
+        )
+        .unwrap();
+        check_array_eq::<BoolArray>(
+            &result.try_into().unwrap(),
+            &[Some(true), Some(false), None],
+        );
+
+        let input: ArrayImpl = I32Array::from_slice(&[Some(0)]).into();
+        assert!(expr.eval_expr(&[&input, &input]).is_err());
+        assert!(expr
+            .eval_expr(&[
+                &StringArray::from_slice(&[Some("0")]).into(),
+                &input,
+                &input
+            ])
+            .is_err());
+    }
 }
@@ -1,13 +1,19 @@
 mod array;
+mod chunk;
+mod datatype;
 mod expr;
 pub mod macros;
 mod scalar;
 
 pub use array::*;
+pub use chunk::*;
+pub use datatype::*;
 pub use expr::*;
 pub use scalar::*;
 use thiserror::Error;
 
+/// Returned when a value is converted into a variant of a different type. Holds the kind of the
+/// expected variant, whose parameters are not known, and the actual data type.
 #[derive(Error, Debug)]
 #[error("Type mispatch on conversion: expected {0}, get {1}")]
-pub struct TypeMismatch(&'static str, &'static str);
+pub struct TypeMismatch(DataTypeKind, DataType);
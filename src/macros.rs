@@ -8,7 +8,15 @@ macro_rules! for_all_variants {
             { Float32, float32, F32Array, F32ArrayBuilder, f32, f32},
             { Float64, float64, F64Array, F64ArrayBuilder, f64, f64},
             { Bool, bool, BoolArray, BoolArrayBuilder, bool, bool},
-            { String, string, StringArray, StringArrayBuilder, String, &'a str}
+            { String, string, StringArray, StringArrayBuilder, String, &'a str},
+            { Binary, binary, BinaryArray, BinaryArrayBuilder, Vec<u8>, &'a [u8]},
+            { Decimal, decimal, DecimalArray, DecimalArrayBuilder, Decimal, Decimal},
+            { Date32, date32, Date32Array, Date32ArrayBuilder, Date, Date},
+            { Time64, time64, Time64Array, Time64ArrayBuilder, Time, Time},
+            { Timestamp, timestamp, TimestampArray, TimestampArrayBuilder, Timestamp, Timestamp},
+            { Interval, interval, IntervalArray, IntervalArrayBuilder, Interval, Interval},
+            { List, list, ListArray, ListArrayBuilder, ListValue, ListRef<'a>},
+            { Struct, struct, StructArray, StructArrayBuilder, StructValue, StructRef<'a>}
         }
     };
 }
@@ -24,9 +32,116 @@ macro_rules! for_all_primitive_variants {
             { Int64, int64, I64Array, I64ArrayBuilder, i64, i64},
             { Float32, float32, F32Array, F32ArrayBuilder, f32, f32},
             { Float64, float64, F64Array, F64ArrayBuilder, f64, f64},
-            { Bool, bool, BoolArray, BoolArrayBuilder, bool, bool}
+            { Bool, bool, BoolArray, BoolArrayBuilder, bool, bool},
+            { Date32, date32, Date32Array, Date32ArrayBuilder, Date, Date},
+            { Time64, time64, Time64Array, Time64ArrayBuilder, Time, Time},
+            { Interval, interval, IntervalArray, IntervalArrayBuilder, Interval, Interval}
         }
     };
 }
 
 pub(crate) use for_all_primitive_variants;
+
+/// Lists all combinations of input types that can be compared with each other, together with the
+/// type both sides are cast into before comparing.
+///
+/// Each entry is `{ left variant, left array, right variant, right array, cast array }`.
+macro_rules! for_all_cmp_combinations {
+    ($macro:ident $(, $x:ident)*) => {
+        $macro! {
+            [$($x),*],
+            // comparison between the same types
+            { Int16, I16Array, Int16, I16Array, I16Array },
+            { Int32, I32Array, Int32, I32Array, I32Array },
+            { Int64, I64Array, Int64, I64Array, I64Array },
+            { Float32, F32Array, Float32, F32Array, F32Array },
+            { Float64, F64Array, Float64, F64Array, F64Array },
+            { Bool, BoolArray, Bool, BoolArray, BoolArray },
+            { String, StringArray, String, StringArray, StringArray },
+            { Binary, BinaryArray, Binary, BinaryArray, BinaryArray },
+            { Decimal, DecimalArray, Decimal, DecimalArray, DecimalArray },
+            { Date32, Date32Array, Date32, Date32Array, Date32Array },
+            { Time64, Time64Array, Time64, Time64Array, Time64Array },
+            { Timestamp, TimestampArray, Timestamp, TimestampArray, TimestampArray },
+            { Interval, IntervalArray, Interval, IntervalArray, IntervalArray },
+            // comparison across integer types
+            { Int16, I16Array, Int32, I32Array, I32Array },
+            { Int32, I32Array, Int16, I16Array, I32Array },
+            { Int16, I16Array, Int64, I64Array, I64Array },
+            { Int64, I64Array, Int16, I16Array, I64Array },
+            { Int32, I32Array, Int64, I64Array, I64Array },
+            { Int64, I64Array, Int32, I32Array, I64Array },
+            // comparison across float types
+            { Float32, F32Array, Float64, F64Array, F64Array },
+            { Float64, F64Array, Float32, F32Array, F64Array },
+            // comparison across integer and float types
+            { Int16, I16Array, Float32, F32Array, F32Array },
+            { Float32, F32Array, Int16, I16Array, F32Array },
+            { Int16, I16Array, Float64, F64Array, F64Array },
+            { Float64, F64Array, Int16, I16Array, F64Array },
+            { Int32, I32Array, Float32, F32Array, F64Array },
+            { Float32, F32Array, Int32, I32Array, F64Array },
+            { Int32, I32Array, Float64, F64Array, F64Array },
+            { Float64, F64Array, Int32, I32Array, F64Array },
+            { Int64, I64Array, Float32, F32Array, F64Array },
+            { Float32, F32Array, Int64, I64Array, F64Array },
+            { Int64, I64Array, Float64, F64Array, F64Array },
+            { Float64, F64Array, Int64, I64Array, F64Array },
+            // comparison across integer and decimal types
+            { Int16, I16Array, Decimal, DecimalArray, DecimalArray },
+            { Decimal, DecimalArray, Int16, I16Array, DecimalArray },
+            { Int32, I32Array, Decimal, DecimalArray, DecimalArray },
+            { Decimal, DecimalArray, Int32, I32Array, DecimalArray },
+            { Int64, I64Array, Decimal, DecimalArray, DecimalArray },
+            { Decimal, DecimalArray, Int64, I64Array, DecimalArray },
+            // comparison between dates and timestamps
+            { Date32, Date32Array, Timestamp, TimestampArray, TimestampArray },
+            { Timestamp, TimestampArray, Date32, Date32Array, TimestampArray }
+        }
+    };
+}
+
+pub(crate) use for_all_cmp_combinations;
+
+/// Lists all combinations of input types of arithmetic functions, together with the type both
+/// sides are cast into, which is also the type of the result.
+///
+/// Each entry is `{ left variant, left array, right variant, right array, cast variant, cast array }`.
+macro_rules! for_all_arith_combinations {
+    ($macro:ident $(, $x:ident)*) => {
+        $macro! {
+            [$($x),*],
+            // arithmetic between the same types
+            { Int16, I16Array, Int16, I16Array, Int16, I16Array },
+            { Int32, I32Array, Int32, I32Array, Int32, I32Array },
+            { Int64, I64Array, Int64, I64Array, Int64, I64Array },
+            { Float32, F32Array, Float32, F32Array, Float32, F32Array },
+            { Float64, F64Array, Float64, F64Array, Float64, F64Array },
+            // arithmetic across integer types
+            { Int16, I16Array, Int32, I32Array, Int32, I32Array },
+            { Int32, I32Array, Int16, I16Array, Int32, I32Array },
+            { Int16, I16Array, Int64, I64Array, Int64, I64Array },
+            { Int64, I64Array, Int16, I16Array, Int64, I64Array },
+            { Int32, I32Array, Int64, I64Array, Int64, I64Array },
+            { Int64, I64Array, Int32, I32Array, Int64, I64Array },
+            // arithmetic across float types
+            { Float32, F32Array, Float64, F64Array, Float64, F64Array },
+            { Float64, F64Array, Float32, F32Array, Float64, F64Array },
+            // arithmetic across integer and float types
+            { Int16, I16Array, Float32, F32Array, Float32, F32Array },
+            { Float32, F32Array, Int16, I16Array, Float32, F32Array },
+            { Int16, I16Array, Float64, F64Array, Float64, F64Array },
+            { Float64, F64Array, Int16, I16Array, Float64, F64Array },
+            { Int32, I32Array, Float32, F32Array, Float64, F64Array },
+            { Float32, F32Array, Int32, I32Array, Float64, F64Array },
+            { Int32, I32Array, Float64, F64Array, Float64, F64Array },
+            { Float64, F64Array, Int32, I32Array, Float64, F64Array },
+            { Int64, I64Array, Float32, F32Array, Float64, F64Array },
+            { Float32, F32Array, Int64, I64Array, Float64, F64Array },
+            { Int64, I64Array, Float64, F64Array, Float64, F64Array },
+            { Float64, F64Array, Int64, I64Array, Float64, F64Array }
+        }
+    };
+}
+
+pub(crate) use for_all_arith_combinations;
@@ -0,0 +1,277 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// Maximum number of decimal digits a [`Decimal`] can hold.
+pub const MAX_DECIMAL_PRECISION: u8 = 38;
+
+/// Errors on creating or converting [`Decimal`]s.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DecimalError {
+    #[error("Decimal overflow: value does not fit into precision {0}")]
+    Overflow(u8),
+    #[error("Invalid decimal precision {0} and scale {1}")]
+    InvalidPrecisionScale(u8, u8),
+    #[error("Invalid decimal literal: {0}")]
+    Parse(String),
+}
+
+/// A fixed-point decimal number, which is `mantissa * 10^-scale`.
+///
+/// Two decimals of different scales are compared by their numeric value, e.g. `1.0 == 1.00`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u8,
+}
+
+/// Returns `10^exp`, or `None` if it does not fit into `i128`.
+fn pow10(exp: u32) -> Option<i128> {
+    10_i128.checked_pow(exp)
+}
+
+impl Decimal {
+    /// Create a decimal of `mantissa * 10^-scale`.
+    pub fn new(mantissa: i128, scale: u8) -> Result<Self, DecimalError> {
+        if scale > MAX_DECIMAL_PRECISION {
+            return Err(DecimalError::InvalidPrecisionScale(
+                MAX_DECIMAL_PRECISION,
+                scale,
+            ));
+        }
+        let decimal = Self { mantissa, scale };
+        decimal.check_precision(MAX_DECIMAL_PRECISION)?;
+        Ok(decimal)
+    }
+
+    /// Create a decimal without checking that the mantissa fits into [`MAX_DECIMAL_PRECISION`].
+    pub(crate) fn new_unchecked(mantissa: i128, scale: u8) -> Self {
+        Self { mantissa, scale }
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// Returns an error if the decimal has more than `precision` digits.
+    pub fn check_precision(&self, precision: u8) -> Result<(), DecimalError> {
+        match pow10(precision as u32) {
+            Some(bound) if self.mantissa.unsigned_abs() >= bound.unsigned_abs() => {
+                Err(DecimalError::Overflow(precision))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Convert the decimal to the given `scale`.
+    ///
+    /// When the scale is reduced, the value is rounded half away from zero, e.g. `2.5` becomes
+    /// `3` and `-2.5` becomes `-3`. An error is returned if the mantissa overflows when the scale
+    /// is increased.
+    pub fn rescale(&self, scale: u8) -> Result<Self, DecimalError> {
+        if scale > MAX_DECIMAL_PRECISION {
+            return Err(DecimalError::InvalidPrecisionScale(
+                MAX_DECIMAL_PRECISION,
+                scale,
+            ));
+        }
+        let mantissa = match scale.cmp(&self.scale) {
+            Ordering::Equal => self.mantissa,
+            Ordering::Greater => pow10((scale - self.scale) as u32)
+                .and_then(|factor| self.mantissa.checked_mul(factor))
+                .ok_or(DecimalError::Overflow(MAX_DECIMAL_PRECISION))?,
+            Ordering::Less => {
+                // both scales are at most 38, and `10^38` fits into `i128`
+                let factor = pow10((self.scale - scale) as u32).unwrap();
+                let quotient = self.mantissa / factor;
+                let remainder = self.mantissa % factor;
+                if remainder.unsigned_abs() * 2 >= factor.unsigned_abs() {
+                    quotient + self.mantissa.signum()
+                } else {
+                    quotient
+                }
+            }
+        };
+        Ok(Self { mantissa, scale })
+    }
+
+    /// Convert the decimal to the given `precision` and `scale`, returning an error if the value
+    /// does not fit.
+    pub fn rescale_with_precision(&self, precision: u8, scale: u8) -> Result<Self, DecimalError> {
+        if precision == 0 || precision > MAX_DECIMAL_PRECISION || scale > precision {
+            return Err(DecimalError::InvalidPrecisionScale(precision, scale));
+        }
+        let decimal = self.rescale(scale)?;
+        decimal.check_precision(precision)?;
+        Ok(decimal)
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl Hash for Decimal {
+    /// Hash the value with trailing zeros of the fraction removed, so that equal decimals of
+    /// different scales have the same hash.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let (mut mantissa, mut scale) = (self.mantissa, self.scale);
+        while scale > 0 && mantissa % 10 == 0 {
+            mantissa /= 10;
+            scale -= 1;
+        }
+        (mantissa, scale).hash(state);
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Bring both sides to the larger scale. If that overflows, the rescaled value is larger
+        // in magnitude than any mantissa, so the order is decided by its sign.
+        match self.scale.cmp(&other.scale) {
+            Ordering::Equal => self.mantissa.cmp(&other.mantissa),
+            Ordering::Less => match self.rescale(other.scale) {
+                Ok(this) => this.mantissa.cmp(&other.mantissa),
+                Err(_) => self.mantissa.cmp(&0),
+            },
+            Ordering::Greater => other.cmp(self).reverse(),
+        }
+    }
+}
+
+macro_rules! impl_decimal_from_int {
+    ($($t:ty),*) => {
+        $(
+            #[doc = concat!("Implement [`", stringify!($t), "`] -> [`Decimal`] with scale 0")]
+            impl From<$t> for Decimal {
+                fn from(v: $t) -> Self {
+                    Self::new_unchecked(v as i128, 0)
+                }
+            }
+        )*
+    };
+}
+
+impl_decimal_from_int! { i16, i32, i64 }
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let scale = self.scale as usize;
+        if scale == 0 {
+            write!(f, "{}{}", sign, digits)
+        } else if digits.len() > scale {
+            let (int, frac) = digits.split_at(digits.len() - scale);
+            write!(f, "{}{}.{}", sign, int, frac)
+        } else {
+            write!(f, "{}0.{:0>width$}", sign, digits, width = scale)
+        }
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = DecimalError;
+
+    /// Parse a decimal literal such as `-12.340`. The scale is the number of fractional digits.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || DecimalError::Parse(s.to_string());
+        let trimmed = s.trim();
+        let (negative, unsigned) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if int.is_empty() && frac.is_empty() {
+            return Err(err());
+        }
+        if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(err());
+        }
+        let scale = u8::try_from(frac.len())
+            .ok()
+            .filter(|scale| *scale <= MAX_DECIMAL_PRECISION)
+            .ok_or_else(err)?;
+        let mut mantissa: i128 = 0;
+        for b in int.bytes().chain(frac.bytes()) {
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add((b - b'0') as i128))
+                .ok_or(DecimalError::Overflow(MAX_DECIMAL_PRECISION))?;
+        }
+        if negative {
+            mantissa = -mantissa;
+        }
+        Self::new(mantissa, scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_display() {
+        for s in ["0", "1.5", "-1.50", "0.05", "-0.005", "123456.789"] {
+            assert_eq!(dec(s).to_string(), s);
+        }
+        assert_eq!(dec("+.5").to_string(), "0.5");
+        assert_eq!(dec("-3.").to_string(), "-3");
+        assert!("".parse::<Decimal>().is_err());
+        assert!("1.2.3".parse::<Decimal>().is_err());
+        assert!("1e5".parse::<Decimal>().is_err());
+        assert!("1".repeat(39).parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn test_rescale_rounding() {
+        assert_eq!(dec("2.5").rescale(0).unwrap().to_string(), "3");
+        assert_eq!(dec("-2.5").rescale(0).unwrap().to_string(), "-3");
+        assert_eq!(dec("2.49").rescale(1).unwrap().to_string(), "2.5");
+        assert_eq!(dec("2.44").rescale(1).unwrap().to_string(), "2.4");
+        assert_eq!(dec("1.5").rescale(3).unwrap().to_string(), "1.500");
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(
+            dec("123.45").rescale_with_precision(4, 2),
+            Err(DecimalError::Overflow(4))
+        );
+        assert!(dec("99.994").rescale_with_precision(4, 2).is_ok());
+        assert!(dec("99.995").rescale_with_precision(4, 2).is_err());
+        assert!(dec("1").rescale_with_precision(3, 4).is_err());
+        assert!(Decimal::from(i64::MAX).rescale(30).is_err());
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(dec("1.0"), dec("1.00"));
+        assert!(dec("1.01") > dec("1.0"));
+        assert!(dec("-1.01") < dec("-1"));
+        let huge = Decimal::new(10_i128.pow(37), 0).unwrap();
+        assert!(huge > dec("0.00000000000000000000000000000000000001"));
+        assert!(Decimal::from(-1_i16) < dec("0.5"));
+    }
+}
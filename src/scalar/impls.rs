@@ -13,6 +13,20 @@ macro_rules! impl_scalar_dispatch {
                     )*
                 }
             }
+
+            /// Get data type of the current scalar
+            pub fn data_type(&self) -> DataType {
+                self.as_scalar_ref().data_type()
+            }
+
+            /// Get a reference of the current scalar
+            pub fn as_scalar_ref(&self) -> ScalarRefImpl<'_> {
+                match self {
+                    $(
+                        Self::$Abc(v) => ScalarRefImpl::$Abc(v.as_scalar_ref()),
+                    )*
+                }
+            }
         }
     };
 }
@@ -31,6 +45,24 @@ macro_rules! impl_scalar_ref_dispatch {
                     )*
                 }
             }
+
+            /// Get data type of the current scalar
+            pub fn data_type(&self) -> DataType {
+                match self {
+                    $(
+                        Self::$Abc(v) => v.data_type(),
+                    )*
+                }
+            }
+
+            /// Convert the reference into an owned scalar
+            pub fn to_owned_scalar(&self) -> ScalarImpl {
+                match self {
+                    $(
+                        Self::$Abc(v) => ScalarImpl::$Abc(v.to_owned_scalar()),
+                    )*
+                }
+            }
         }
     };
 }
@@ -47,7 +79,7 @@ macro_rules! impl_scalar_convision {
                 fn try_from(that: ScalarImpl) -> Result<Self, Self::Error> {
                     match that {
                         ScalarImpl::$Abc(v) => Ok(v),
-                        other => Err(TypeMismatch(stringify!($Abc), other.identifier())),
+                        other => Err(TypeMismatch(DataTypeKind::$Abc, other.data_type())),
                     }
                 }
             }
@@ -65,7 +97,7 @@ macro_rules! impl_scalar_convision {
                 fn try_from(that: ScalarRefImpl<'a>) -> Result<Self, Self::Error> {
                     match that {
                         ScalarRefImpl::$Abc(v) => Ok(v),
-                        other => Err(TypeMismatch(stringify!($Abc), other.identifier())),
+                        other => Err(TypeMismatch(DataTypeKind::$Abc, other.data_type())),
                     }
                 }
             }
@@ -104,6 +136,10 @@ macro_rules! impl_scalar {
                 fn to_owned_scalar(&self) -> $Owned {
                     *self
                 }
+
+                fn data_type(&self) -> DataType {
+                    DataType::$Abc
+                }
             }
         )*
     };
@@ -131,4 +167,143 @@ impl<'a> ScalarRef<'a> for &'a str {
     fn to_owned_scalar(&self) -> Self::ScalarType {
         self.to_string()
     }
+
+    fn data_type(&self) -> DataType {
+        DataType::String
+    }
+}
+
+/// Implement [`Scalar`] for `Vec<u8>`
+impl Scalar for Vec<u8> {
+    type ArrayType = BinaryArray;
+
+    type RefType<'a> = &'a [u8];
+
+    fn as_scalar_ref(&self) -> Self::RefType<'_> {
+        self.as_slice()
+    }
+}
+
+/// Implement [`ScalarRef`] for `&[u8]`
+impl<'a> ScalarRef<'a> for &'a [u8] {
+    type ArrayType = BinaryArray;
+
+    type ScalarType = Vec<u8>;
+
+    fn to_owned_scalar(&self) -> Self::ScalarType {
+        self.to_vec()
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Binary
+    }
+}
+
+/// Implement [`Scalar`] for [`Decimal`]
+impl Scalar for Decimal {
+    type ArrayType = DecimalArray;
+
+    type RefType<'a> = Decimal;
+
+    fn as_scalar_ref(&self) -> Self::RefType<'_> {
+        *self
+    }
+}
+
+/// Implement [`ScalarRef`] for [`Decimal`]
+impl<'a> ScalarRef<'a> for Decimal {
+    type ArrayType = DecimalArray;
+
+    type ScalarType = Decimal;
+
+    fn to_owned_scalar(&self) -> Self::ScalarType {
+        *self
+    }
+
+    /// A single decimal value is typed with the maximum precision.
+    fn data_type(&self) -> DataType {
+        DataType::Decimal {
+            precision: MAX_DECIMAL_PRECISION,
+            scale: self.scale(),
+        }
+    }
+}
+
+/// Implement [`Scalar`] for [`ListValue`]
+impl Scalar for ListValue {
+    type ArrayType = ListArray;
+
+    type RefType<'a> = ListRef<'a>;
+
+    fn as_scalar_ref(&self) -> Self::RefType<'_> {
+        self.as_list_ref()
+    }
+}
+
+/// Implement [`ScalarRef`] for [`ListRef`]
+impl<'a> ScalarRef<'a> for ListRef<'a> {
+    type ArrayType = ListArray;
+
+    type ScalarType = ListValue;
+
+    fn to_owned_scalar(&self) -> Self::ScalarType {
+        ListValue::new(self.values())
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::List(Box::new(self.value_type()))
+    }
+}
+
+/// Implement [`Scalar`] for [`StructValue`]
+impl Scalar for StructValue {
+    type ArrayType = StructArray;
+
+    type RefType<'a> = StructRef<'a>;
+
+    fn as_scalar_ref(&self) -> Self::RefType<'_> {
+        self.as_struct_ref()
+    }
+}
+
+/// Implement [`ScalarRef`] for [`StructRef`]
+impl<'a> ScalarRef<'a> for StructRef<'a> {
+    type ArrayType = StructArray;
+
+    type ScalarType = StructValue;
+
+    fn to_owned_scalar(&self) -> Self::ScalarType {
+        self.to_struct_value()
+    }
+
+    fn data_type(&self) -> DataType {
+        DataType::Struct(self.fields().to_vec())
+    }
+}
+
+/// Implement [`Scalar`] for [`Timestamp`]
+impl Scalar for Timestamp {
+    type ArrayType = TimestampArray;
+
+    type RefType<'a> = Timestamp;
+
+    fn as_scalar_ref(&self) -> Self::RefType<'_> {
+        *self
+    }
+}
+
+/// Implement [`ScalarRef`] for [`Timestamp`]
+impl<'a> ScalarRef<'a> for Timestamp {
+    type ArrayType = TimestampArray;
+
+    type ScalarType = Timestamp;
+
+    fn to_owned_scalar(&self) -> Self::ScalarType {
+        *self
+    }
+
+    /// A single timestamp value has no timezone tag.
+    fn data_type(&self) -> DataType {
+        DataType::Timestamp { tz: None }
+    }
 }
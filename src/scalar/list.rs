@@ -0,0 +1,117 @@
+use std::fmt;
+
+use crate::{ArrayImpl, DataType, ScalarRefImpl};
+
+/// A list of values of the same type, which is the owned item of
+/// [`ListArray`](crate::ListArray).
+///
+/// The values are kept in an [`ArrayImpl`], so a list taken from an array shares the buffers with
+/// that array.
+#[derive(Clone)]
+pub struct ListValue(ArrayImpl);
+
+impl ListValue {
+    /// Create a list of the items of `values`.
+    pub fn new(values: ArrayImpl) -> Self {
+        Self(values)
+    }
+
+    /// The items of this list
+    pub fn values(&self) -> &ArrayImpl {
+        &self.0
+    }
+
+    /// Get a reference of this list
+    pub fn as_list_ref(&self) -> ListRef<'_> {
+        ListRef {
+            values: &self.0,
+            start: 0,
+            end: self.0.len(),
+        }
+    }
+}
+
+impl From<ArrayImpl> for ListValue {
+    fn from(values: ArrayImpl) -> Self {
+        Self(values)
+    }
+}
+
+impl fmt::Debug for ListValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_list_ref().fmt(f)
+    }
+}
+
+impl PartialEq for ListValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_list_ref() == other.as_list_ref()
+    }
+}
+
+/// A borrowed list, which is a window of `start..end` over the child values of a
+/// [`ListArray`](crate::ListArray) or a [`ListValue`].
+#[derive(Clone, Copy)]
+pub struct ListRef<'a> {
+    values: &'a ArrayImpl,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> ListRef<'a> {
+    /// Create a reference of the items `start..end` of `values`.
+    pub(crate) fn new(values: &'a ArrayImpl, start: usize, end: usize) -> Self {
+        debug_assert!(start <= end && end <= values.len());
+        Self { values, start, end }
+    }
+
+    /// Number of items of this list
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Indicates whether this list is empty
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Get the item at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn get(&self, idx: usize) -> Option<ScalarRefImpl<'a>> {
+        assert!(idx < self.len(), "index out of bounds");
+        self.values.get(self.start + idx)
+    }
+
+    /// Get iterator of the items of this list
+    pub fn iter(&self) -> impl Iterator<Item = Option<ScalarRefImpl<'a>>> + 'a {
+        let values = self.values;
+        (self.start..self.end).map(move |idx| values.get(idx))
+    }
+
+    /// The items of this list, which shares the buffers with the original values.
+    pub fn values(&self) -> ArrayImpl {
+        self.values.slice(self.start, self.len())
+    }
+
+    /// Data type of the items of this list
+    pub fn value_type(&self) -> DataType {
+        self.values.data_type()
+    }
+}
+
+impl fmt::Debug for ListRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for ListRef<'_> {
+    /// Two lists are equal if they have equal items. The data types of lists are not compared,
+    /// so that two empty lists are always equal.
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
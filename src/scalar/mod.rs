@@ -1,8 +1,17 @@
 use std::fmt::Debug;
 
-use crate::Array;
+use crate::{Array, DataType};
 
+mod decimal;
 mod impls;
+mod list;
+mod struct_value;
+mod temporal;
+
+pub use decimal::*;
+pub use list::*;
+pub use struct_value::*;
+pub use temporal::*;
 
 /// An owned single value
 ///
@@ -32,6 +41,9 @@ pub trait ScalarRef<'a>: Debug + Clone + Copy + Send + 'a {
 
     /// Convert the reference into an owned value
     fn to_owned_scalar(&self) -> Self::ScalarType;
+
+    /// Data type of the value
+    fn data_type(&self) -> DataType;
 }
 
 /// Encapsules all variables of [`Scalar`]
@@ -44,6 +56,14 @@ pub enum ScalarImpl {
     Float64(f64),
     Bool(bool),
     String(String),
+    Binary(Vec<u8>),
+    Decimal(Decimal),
+    Date32(Date),
+    Time64(Time),
+    Timestamp(Timestamp),
+    Interval(Interval),
+    List(ListValue),
+    Struct(StructValue),
 }
 
 /// Encapsules all variables of [`ScalarRef`]
@@ -56,6 +76,14 @@ pub enum ScalarRefImpl<'a> {
     Float64(f64),
     Bool(bool),
     String(&'a str),
+    Binary(&'a [u8]),
+    Decimal(Decimal),
+    Date32(Date),
+    Time64(Time),
+    Timestamp(Timestamp),
+    Interval(Interval),
+    List(ListRef<'a>),
+    Struct(StructRef<'a>),
 }
 
 #[cfg(test)]
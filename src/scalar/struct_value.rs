@@ -0,0 +1,134 @@
+use std::fmt;
+
+use crate::{Array, ArrayBuilderImpl, Field, ScalarImpl, ScalarRefImpl, StructArray};
+
+/// A struct of named fields, which is the owned item of [`StructArray`].
+///
+/// The value is kept as a [`StructArray`] of one item, so a struct taken from an array shares the
+/// buffers with that array.
+#[derive(Clone)]
+pub struct StructValue(StructArray);
+
+impl StructValue {
+    /// Create a struct with a value for each field.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values does not match the fields, or a value does not have the
+    /// type of its field.
+    pub fn new(fields: Vec<Field>, values: Vec<Option<ScalarImpl>>) -> Self {
+        assert_eq!(fields.len(), values.len(), "number of fields mismatch");
+        let children = fields
+            .iter()
+            .zip(values)
+            .map(|(field, value)| {
+                let mut builder = ArrayBuilderImpl::new(field.data_type(), 1);
+                builder.push(value.as_ref().map(ScalarImpl::as_scalar_ref));
+                builder.finish()
+            })
+            .collect();
+        Self(StructArray::new(fields, children, 1, None))
+    }
+
+    /// Take the struct at index `idx` of `array`, sharing the buffers.
+    pub(crate) fn from_array(array: &StructArray, idx: usize) -> Self {
+        Self(array.slice(idx, 1))
+    }
+
+    /// Get a reference of this struct
+    pub fn as_struct_ref(&self) -> StructRef<'_> {
+        self.0.get(0).unwrap()
+    }
+}
+
+impl From<StructArray> for StructValue {
+    /// Take the only struct of `array`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `array` does not have exactly one item, which is not null.
+    fn from(array: StructArray) -> Self {
+        assert!(
+            array.len() == 1 && array.get(0).is_some(),
+            "expect one non-null struct"
+        );
+        Self(array)
+    }
+}
+
+impl fmt::Debug for StructValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_struct_ref().fmt(f)
+    }
+}
+
+impl PartialEq for StructValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_struct_ref() == other.as_struct_ref()
+    }
+}
+
+/// A borrowed struct, which is a row of a [`StructArray`] or a [`StructValue`].
+#[derive(Clone, Copy)]
+pub struct StructRef<'a> {
+    array: &'a StructArray,
+    idx: usize,
+}
+
+impl<'a> StructRef<'a> {
+    /// Take an owned struct, which shares the buffers with the array.
+    pub(crate) fn to_struct_value(self) -> StructValue {
+        StructValue::from_array(self.array, self.idx)
+    }
+
+    /// Create a reference of the struct at index `idx` of `array`.
+    pub(crate) fn new(array: &'a StructArray, idx: usize) -> Self {
+        Self { array, idx }
+    }
+
+    /// Names and types of the fields
+    pub fn fields(&self) -> &'a [Field] {
+        self.array.fields()
+    }
+
+    /// Get the value of the field at position `idx`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    pub fn field(&self, idx: usize) -> Option<ScalarRefImpl<'a>> {
+        self.array.child_value(idx, self.idx)
+    }
+
+    /// Get the value of the field of `name`, or `None` if there is no such field.
+    pub fn field_by_name(&self, name: &str) -> Option<Option<ScalarRefImpl<'a>>> {
+        self.array.field_index(name).map(|idx| self.field(idx))
+    }
+
+    /// Get iterator of the values of all fields
+    pub fn iter(&self) -> impl Iterator<Item = Option<ScalarRefImpl<'a>>> + 'a {
+        let (array, idx) = (self.array, self.idx);
+        (0..array.fields().len()).map(move |field| array.child_value(field, idx))
+    }
+}
+
+impl fmt::Debug for StructRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.fields().iter().map(Field::name).zip(self.iter()))
+            .finish()
+    }
+}
+
+impl PartialEq for StructRef<'_> {
+    /// Two structs are equal if they have the same field names and equal values.
+    fn eq(&self, other: &Self) -> bool {
+        self.fields().len() == other.fields().len()
+            && self
+                .fields()
+                .iter()
+                .zip(other.fields())
+                .all(|(a, b)| a.name() == b.name())
+            && self.iter().eq(other.iter())
+    }
+}
@@ -0,0 +1,611 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+pub const MICROS_PER_SECOND: i64 = 1_000_000;
+pub const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+pub const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+pub const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+
+/// Returned when a string cannot be parsed into a temporal value.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid {0} literal: {1}")]
+pub struct ParseTemporalError(&'static str, String);
+
+/// Number of days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Inverse of [`days_from_civil`], returns `(year, month, day)`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Number of days in the given month.
+pub(crate) fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Parse an unsigned decimal number of at most `max_digits` digits.
+fn parse_digits(s: &str, max_digits: usize) -> Option<i64> {
+    if s.is_empty() || s.len() > max_digits || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Parse `HH:MM[:SS[.ffffff]]` into microseconds. Hours are not limited to a day.
+fn parse_hms(s: &str) -> Option<i64> {
+    let mut parts = s.split(':');
+    let hour = parse_digits(parts.next()?, 9)?;
+    let minute = parse_digits(parts.next()?, 2)?;
+    let (second, micros) = match parts.next() {
+        Some(sec) => {
+            let (sec, frac) = sec.split_once('.').unwrap_or((sec, ""));
+            let micros = if frac.is_empty() {
+                0
+            } else {
+                parse_digits(frac, 6)? * 10_i64.pow(6 - frac.len() as u32)
+            };
+            (parse_digits(sec, 2)?, micros)
+        }
+        None => (0, 0),
+    };
+    if parts.next().is_some() || minute >= 60 || second >= 60 {
+        return None;
+    }
+    Some(hour * MICROS_PER_HOUR + minute * MICROS_PER_MINUTE + second * MICROS_PER_SECOND + micros)
+}
+
+/// Format a non-negative number of microseconds as `HH:MM:SS[.ffffff]`, without trailing zeros
+/// in the fraction.
+fn fmt_hms(f: &mut fmt::Formatter<'_>, micros: i64) -> fmt::Result {
+    let (hour, rest) = (micros / MICROS_PER_HOUR, micros % MICROS_PER_HOUR);
+    let (minute, rest) = (rest / MICROS_PER_MINUTE, rest % MICROS_PER_MINUTE);
+    let (second, frac) = (rest / MICROS_PER_SECOND, rest % MICROS_PER_SECOND);
+    write!(f, "{:02}:{:02}:{:02}", hour, minute, second)?;
+    if frac != 0 {
+        let frac = format!("{:06}", frac);
+        write!(f, ".{}", frac.trim_end_matches('0'))?;
+    }
+    Ok(())
+}
+
+/// A calendar date, stored as the number of days since 1970-01-01.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(i32);
+
+impl Date {
+    pub fn from_days(days: i32) -> Self {
+        Self(days)
+    }
+
+    /// Number of days since 1970-01-01
+    pub fn days(&self) -> i32 {
+        self.0
+    }
+
+    /// Create a date from year, month and day, returning `None` if the date is invalid or out
+    /// of range.
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year as i64, month) {
+            return None;
+        }
+        i32::try_from(days_from_civil(year as i64, month, day))
+            .ok()
+            .map(Self)
+    }
+
+    /// Returns `(year, month, day)` of the date.
+    pub fn ymd(&self) -> (i32, u32, u32) {
+        let (year, month, day) = civil_from_days(self.0 as i64);
+        (year as i32, month, day)
+    }
+}
+
+/// Format `date` as `YYYY-MM-DD`, followed by `time` if any. Years before 1 AD are written as in
+/// Postgres, e.g. year 0 is `0001-01-01 BC`.
+fn fmt_date_time(f: &mut fmt::Formatter<'_>, date: Date, time: Option<Time>) -> fmt::Result {
+    let (year, month, day) = date.ymd();
+    let bc = year <= 0;
+    let year = if bc { 1 - year as i64 } else { year as i64 };
+    write!(f, "{:04}-{:02}-{:02}", year, month, day)?;
+    if let Some(time) = time {
+        f.write_str(" ")?;
+        fmt_hms(f, time.0)?;
+    }
+    if bc {
+        f.write_str(" BC")?;
+    }
+    Ok(())
+}
+
+/// Split the ` BC` suffix of years before 1 AD off `s`, returning whether there was one.
+fn strip_bc(s: &str) -> (&str, bool) {
+    match s
+        .len()
+        .checked_sub(2)
+        .and_then(|pos| s.get(pos..).map(|bc| (pos, bc)))
+    {
+        Some((pos, bc)) if bc.eq_ignore_ascii_case("bc") => (s[..pos].trim_end(), true),
+        _ => (s, false),
+    }
+}
+
+/// Parse `YYYY-MM-DD` into a date, where the year is before 1 AD if `bc` is set.
+fn parse_date(s: &str, bc: bool) -> Option<Date> {
+    let mut parts = s.splitn(3, '-');
+    let year = parse_digits(parts.next()?, 7)?;
+    let month = parse_digits(parts.next()?, 2)?;
+    let day = parse_digits(parts.next()?, 2)?;
+    let year = match bc {
+        true if year == 0 => return None,
+        true => 1 - year,
+        false => year,
+    };
+    Date::from_ymd(i32::try_from(year).ok()?, month as u32, day as u32)
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_date_time(f, *self, None)
+    }
+}
+
+impl FromStr for Date {
+    type Err = ParseTemporalError;
+
+    /// Parse a date in the format of `YYYY-MM-DD[ BC]`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (date, bc) = strip_bc(s.trim());
+        parse_date(date, bc).ok_or_else(|| ParseTemporalError("date", s.to_string()))
+    }
+}
+
+/// A time of day, stored as the number of microseconds since midnight.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time(i64);
+
+impl Time {
+    /// Create a time from microseconds since midnight, returning `None` if it is not within a day.
+    pub fn from_micros(micros: i64) -> Option<Self> {
+        (0..MICROS_PER_DAY)
+            .contains(&micros)
+            .then_some(Self(micros))
+    }
+
+    /// Number of microseconds since midnight
+    pub fn micros(&self) -> i64 {
+        self.0
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_hms(f, self.0)
+    }
+}
+
+impl FromStr for Time {
+    type Err = ParseTemporalError;
+
+    /// Parse a time in the format of `HH:MM[:SS[.ffffff]]`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_hms(s.trim())
+            .and_then(Self::from_micros)
+            .ok_or_else(|| ParseTemporalError("time", s.to_string()))
+    }
+}
+
+/// A point in time, stored as the number of microseconds since 1970-01-01 00:00:00 UTC.
+///
+/// The timezone tag of a timestamp column is part of its
+/// [`DataType`](crate::DataType::Timestamp), and does not change the stored value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    pub fn from_micros(micros: i64) -> Self {
+        Self(micros)
+    }
+
+    /// Number of microseconds since 1970-01-01 00:00:00 UTC
+    pub fn micros(&self) -> i64 {
+        self.0
+    }
+
+    /// Date part of the timestamp
+    pub fn date(&self) -> Date {
+        Date(self.0.div_euclid(MICROS_PER_DAY) as i32)
+    }
+
+    /// Time part of the timestamp
+    pub fn time(&self) -> Time {
+        Time(self.0.rem_euclid(MICROS_PER_DAY))
+    }
+
+    /// Combine a date and a time, returning `None` on overflow.
+    pub fn from_date_time(date: Date, time: Time) -> Option<Self> {
+        // computed in `i128`, since the first microsecond of the earliest date may be out of range
+        // even if the timestamp is not
+        let micros = date.0 as i128 * MICROS_PER_DAY as i128 + time.0 as i128;
+        i64::try_from(micros).ok().map(Self)
+    }
+}
+
+impl Timestamp {
+    /// Add an interval to the timestamp, returning `None` on overflow.
+    ///
+    /// Months are added first, keeping the time of day and clamping the day to the end of the
+    /// month, e.g. `2020-01-31 + 1 mon = 2020-02-29`. Days and microseconds are added afterwards.
+    pub fn checked_add_interval(&self, interval: Interval) -> Option<Self> {
+        let (year, month, day) = self.date().ymd();
+        let months = year as i64 * 12 + month as i64 - 1 + interval.months as i64;
+        let (year, month) = (months.div_euclid(12), months.rem_euclid(12) as u32 + 1);
+        let day = day.min(days_in_month(year, month));
+        let date = Date::from_ymd(i32::try_from(year).ok()?, month, day)?;
+        Self::from_date_time(date, self.time())?
+            .0
+            .checked_add((interval.days as i64).checked_mul(MICROS_PER_DAY)?)?
+            .checked_add(interval.micros)
+            .map(Self)
+    }
+
+    /// Returns `self - other` as an interval of days and microseconds, e.g. `1 day 01:00:00`.
+    /// Returns `None` on overflow.
+    pub fn checked_sub_timestamp(&self, other: Timestamp) -> Option<Interval> {
+        let micros = self.0.checked_sub(other.0)?;
+        let days = i32::try_from(micros / MICROS_PER_DAY).ok()?;
+        Some(Interval::new(0, days, micros % MICROS_PER_DAY))
+    }
+}
+
+/// Implement [`Date`] -> [`Timestamp`] at midnight. Dates out of the range of timestamps, which
+/// is about 292 thousand years around 1970, saturate.
+impl From<Date> for Timestamp {
+    fn from(date: Date) -> Self {
+        Self((date.0 as i64).saturating_mul(MICROS_PER_DAY))
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_date_time(f, self.date(), Some(self.time()))
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = ParseTemporalError;
+
+    /// Parse a timestamp in the format of `YYYY-MM-DD[( |T)HH:MM[:SS[.ffffff]]][offset][ BC]`,
+    /// where the optional offset is `Z` or `(+|-)HH[:MM]`. A timestamp with an offset is converted
+    /// to UTC.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = || {
+            let (s, bc) = strip_bc(s.trim());
+            let (date, rest) = match s.find([' ', 'T']) {
+                Some(pos) => (&s[..pos], s[pos + 1..].trim_start()),
+                None => (s, ""),
+            };
+            let date = parse_date(date, bc)?;
+            let (time, offset) = if let Some(time) = rest.strip_suffix('Z') {
+                (time, 0)
+            } else if let Some(pos) = rest.rfind(['+', '-']) {
+                let sign = if rest.as_bytes()[pos] == b'-' { -1 } else { 1 };
+                let offset = &rest[pos + 1..];
+                let offset = match offset.split_once(':') {
+                    Some((h, m)) => parse_hms(&format!("{}:{}", h, m))?,
+                    None if offset.len() == 4 => {
+                        parse_hms(&format!("{}:{}", &offset[..2], &offset[2..]))?
+                    }
+                    None => parse_digits(offset, 2)? * MICROS_PER_HOUR,
+                };
+                (&rest[..pos], sign * offset)
+            } else {
+                (rest, 0)
+            };
+            let time = match time.trim() {
+                "" => Time(0),
+                time => Time::from_micros(parse_hms(time)?)?,
+            };
+            Self::from_date_time(date, time)?
+                .0
+                .checked_sub(offset)
+                .map(Self)
+        };
+        parse().ok_or_else(|| ParseTemporalError("timestamp", s.to_string()))
+    }
+}
+
+/// A time span of months, days and microseconds.
+///
+/// The three parts are kept apart because the length of a month or a day is not fixed. When
+/// compared with each other, a month counts as 30 days, and a day as 24 hours, so that
+/// `1 mon = 30 days`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Interval {
+    months: i32,
+    days: i32,
+    micros: i64,
+}
+
+impl Interval {
+    pub fn new(months: i32, days: i32, micros: i64) -> Self {
+        Self {
+            months,
+            days,
+            micros,
+        }
+    }
+
+    pub fn months(&self) -> i32 {
+        self.months
+    }
+
+    pub fn days(&self) -> i32 {
+        self.days
+    }
+
+    pub fn micros(&self) -> i64 {
+        self.micros
+    }
+
+    /// Total length in microseconds, where a month counts as 30 days.
+    fn span(&self) -> i128 {
+        (self.months as i128 * 30 + self.days as i128) * MICROS_PER_DAY as i128
+            + self.micros as i128
+    }
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        self.span() == other.span()
+    }
+}
+
+impl Eq for Interval {}
+
+impl Hash for Interval {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.span().hash(state);
+    }
+}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.span().cmp(&other.span())
+    }
+}
+
+impl fmt::Display for Interval {
+    /// Format the interval like `1 year 2 mons 3 days 04:05:06.5`. Zero parts are omitted.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |n: i64| if n.abs() == 1 { "" } else { "s" };
+        let mut parts = vec![];
+        let (years, months) = (self.months / 12, self.months % 12);
+        if years != 0 {
+            parts.push(format!("{} year{}", years, plural(years as i64)));
+        }
+        if months != 0 {
+            parts.push(format!("{} mon{}", months, plural(months as i64)));
+        }
+        if self.days != 0 {
+            parts.push(format!("{} day{}", self.days, plural(self.days as i64)));
+        }
+        write!(f, "{}", parts.join(" "))?;
+        if self.micros != 0 || parts.is_empty() {
+            if !parts.is_empty() {
+                write!(f, " ")?;
+            }
+            if self.micros < 0 {
+                write!(f, "-")?;
+            }
+            fmt_hms(f, self.micros.unsigned_abs() as i64)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Interval {
+    type Err = ParseTemporalError;
+
+    /// Parse an interval of `<number> <unit>` pairs and an optional `[-]HH:MM[:SS[.ffffff]]`, e.g.
+    /// `1 year -2 mons 3 days 04:05:06`. Units may be singular or plural, and only seconds may
+    /// have a fraction.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = || {
+            let mut interval = Self::default();
+            let mut tokens = s.split_whitespace().peekable();
+            tokens.peek()?;
+            while let Some(token) = tokens.next() {
+                let (negative, unsigned) = match token.strip_prefix('-') {
+                    Some(rest) => (true, rest),
+                    None => (false, token.strip_prefix('+').unwrap_or(token)),
+                };
+                let sign = if negative { -1 } else { 1 };
+                if unsigned.contains(':') {
+                    let micros = sign * parse_hms(unsigned)?;
+                    interval.micros = interval.micros.checked_add(micros)?;
+                    continue;
+                }
+                let unit = tokens.next()?.to_ascii_lowercase();
+                // which part of the interval the unit belongs to, and its size in that part
+                let (part, size) = match unit.as_str() {
+                    "year" | "years" => (0, 12),
+                    "mon" | "mons" | "month" | "months" => (0, 1),
+                    "week" | "weeks" => (1, 7),
+                    "day" | "days" => (1, 1),
+                    "hour" | "hours" => (2, MICROS_PER_HOUR),
+                    "min" | "mins" | "minute" | "minutes" => (2, MICROS_PER_MINUTE),
+                    "sec" | "secs" | "second" | "seconds" => (2, MICROS_PER_SECOND),
+                    "ms" | "millisecond" | "milliseconds" => (2, 1000),
+                    "us" | "microsecond" | "microseconds" => (2, 1),
+                    _ => return None,
+                };
+                let (int, frac) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+                let mut value = (sign * parse_digits(int, 18)?).checked_mul(size)?;
+                if !frac.is_empty() {
+                    if size != MICROS_PER_SECOND {
+                        return None;
+                    }
+                    let frac = parse_digits(frac, 6)? * 10_i64.pow(6 - frac.len() as u32);
+                    value = value.checked_add(sign * frac)?;
+                }
+                match part {
+                    0 => {
+                        interval.months = interval.months.checked_add(i32::try_from(value).ok()?)?
+                    }
+                    1 => interval.days = interval.days.checked_add(i32::try_from(value).ok()?)?,
+                    _ => interval.micros = interval.micros.checked_add(value)?,
+                }
+            }
+            Some(interval)
+        };
+        parse().ok_or_else(|| ParseTemporalError("interval", s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date() {
+        for s in [
+            "1970-01-01",
+            "2000-02-29",
+            "1969-12-31",
+            "0001-01-01",
+            "9999-12-31",
+        ] {
+            assert_eq!(s.parse::<Date>().unwrap().to_string(), s);
+        }
+        assert_eq!("1970-01-02".parse::<Date>().unwrap().days(), 1);
+        assert_eq!("1969-12-31".parse::<Date>().unwrap().days(), -1);
+        assert!("2001-02-29".parse::<Date>().is_err());
+        assert!("2001-13-01".parse::<Date>().is_err());
+        assert!("2001-01".parse::<Date>().is_err());
+
+        // years before 1 AD are written with `BC`, and both ends of the range round-trip
+        assert_eq!(
+            "0000-12-31".parse::<Date>().unwrap().to_string(),
+            "0001-12-31 BC"
+        );
+        assert_eq!(
+            "0044-03-15 bc".parse::<Date>().unwrap(),
+            Date::from_ymd(-43, 3, 15).unwrap()
+        );
+        assert!("0000-01-01 BC".parse::<Date>().is_err());
+        assert!("-0001-06-01".parse::<Date>().is_err());
+        for date in [Date::from_days(i32::MIN), Date::from_days(i32::MAX)] {
+            assert_eq!(date.to_string().parse::<Date>().unwrap(), date);
+        }
+    }
+
+    #[test]
+    fn test_time() {
+        for s in ["00:00:00", "23:59:59.999999", "12:30:00.5"] {
+            assert_eq!(s.parse::<Time>().unwrap().to_string(), s);
+        }
+        assert_eq!("01:02".parse::<Time>().unwrap().to_string(), "01:02:00");
+        assert!("24:00:00".parse::<Time>().is_err());
+        assert!("12:60:00".parse::<Time>().is_err());
+        assert!("12:00:00.1234567".parse::<Time>().is_err());
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let ts: Timestamp = "2023-03-04 05:06:07.25".parse().unwrap();
+        assert_eq!(ts.to_string(), "2023-03-04 05:06:07.25");
+        assert_eq!("2023-03-04T05:06:07.25Z".parse::<Timestamp>().unwrap(), ts);
+        assert_eq!(
+            "2023-03-04 13:06:07.25+08:00".parse::<Timestamp>().unwrap(),
+            ts
+        );
+        assert_eq!(
+            "2023-03-03 23:06:07.25-0600".parse::<Timestamp>().unwrap(),
+            ts
+        );
+        assert_eq!(
+            "1969-12-31".parse::<Timestamp>().unwrap().to_string(),
+            "1969-12-31 00:00:00"
+        );
+        assert_eq!(
+            Timestamp::from_micros(-1).to_string(),
+            "1969-12-31 23:59:59.999999"
+        );
+        assert!("2023-03-04 25:00:00".parse::<Timestamp>().is_err());
+
+        let ts: Timestamp = "0001-02-03 04:05:06+01 BC".parse().unwrap();
+        assert_eq!(ts.to_string(), "0001-02-03 03:05:06 BC");
+        for ts in [
+            Timestamp::from_micros(i64::MIN),
+            Timestamp::from_micros(i64::MAX),
+        ] {
+            assert_eq!(ts.to_string().parse::<Timestamp>().unwrap(), ts);
+        }
+    }
+
+    #[test]
+    fn test_interval() {
+        let interval: Interval = "1 year 2 mons 3 days 04:05:06.5".parse().unwrap();
+        assert_eq!(interval.months(), 14);
+        assert_eq!(interval.days(), 3);
+        assert_eq!(interval.to_string(), "1 year 2 mons 3 days 04:05:06.5");
+        assert_eq!(
+            "-1 day -02:00:00".parse::<Interval>().unwrap().to_string(),
+            "-1 day -02:00:00"
+        );
+        assert_eq!(
+            "2 weeks 90 minutes 1.5 seconds"
+                .parse::<Interval>()
+                .unwrap()
+                .to_string(),
+            "14 days 01:30:01.5"
+        );
+        assert_eq!(Interval::default().to_string(), "00:00:00");
+        assert_eq!(
+            "1 mon".parse::<Interval>().unwrap(),
+            "30 days".parse::<Interval>().unwrap()
+        );
+        assert!("1 fortnight".parse::<Interval>().is_err());
+        assert!("1.5 days".parse::<Interval>().is_err());
+        assert!("".parse::<Interval>().is_err());
+    }
+}